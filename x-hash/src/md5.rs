@@ -413,6 +413,13 @@ impl Md5 {
     }
 }
 
+impl Default for Md5 {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl super::Digest for Md5 {
     type OutputType = [u8; Self::RESULT_SIZE];
     type BlockType = [u8; BLOCK_SIZE];
@@ -404,6 +404,13 @@ impl Sha1 {
     }
 }
 
+impl Default for Sha1 {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl super::Digest for Sha1 {
     type OutputType = [u8; RESULT_SIZE];
     type BlockType = [u8; BLOCK_SIZE];
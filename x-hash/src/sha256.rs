@@ -443,6 +443,13 @@ impl Sha256 {
     }
 }
 
+impl Default for Sha256 {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl super::Digest for Sha256 {
     type OutputType = [u8; RESULT_SIZE];
     type BlockType = [u8; BLOCK_SIZE];
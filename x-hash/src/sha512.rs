@@ -417,6 +417,13 @@ impl Sha512 {
     }
 }
 
+impl Default for Sha512 {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl super::Digest for Sha512 {
     type OutputType = [u8; RESULT_SIZE];
     type BlockType = [u8; BLOCK_SIZE];
@@ -0,0 +1,255 @@
+//! # builder
+//!
+//! Incremental zip/tar archive creation from files, directories and in-memory data.
+//!
+use std::fs::File;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::archive::{CompressionMethod, TarCompression};
+use crate::directory;
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::path::join_relative;
+use crate::result::FsIOResult;
+
+/// `Builder` 创建的归档格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// zip文件
+    Zip(CompressionMethod),
+    /// tar文件(可选gzip压缩)
+    Tar(TarCompression),
+}
+
+enum Writer {
+    Zip(ZipWriter<File>, SimpleFileOptions),
+    Tar(::tar::Builder<File>),
+    TarGz(::tar::Builder<GzEncoder<File>>),
+}
+
+/// 逐个添加条目并直接写入归档文件的构建器, 添加的内容不需要先写入磁盘
+///
+/// 条目名称使用 `/` 分隔, 不能是绝对路径或包含 `..`. 添加完所有条目后需要调用 `finish`.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{archive, file};
+/// use x_io::archive::{ArchiveFormat, Builder, TarCompression};
+/// use x_io::walk::{Walk, WalkOptions};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/archive_test/builder/assets/app.css", "body {}").unwrap();
+///
+///     let mut builder = Builder::create(
+///         "./target/__test/archive_test/builder/site.tar.gz",
+///         ArchiveFormat::Tar(TarCompression::Gzip(6)),
+///     )
+///     .unwrap();
+///     builder.add_bytes("index.html", b"<html></html>").unwrap();
+///     builder.add_dir("assets").unwrap();
+///     for entry in Walk::new("./target/__test/archive_test/builder/assets", WalkOptions::default()) {
+///         let entry = entry.unwrap();
+///         if entry.is_file() {
+///             let name = format!("assets/{}", entry.path().file_name().unwrap().to_string_lossy());
+///             builder.add_file(&name, entry.path()).unwrap();
+///         }
+///     }
+///     assert!(builder.add_bytes("../escape.txt", b"").is_err());
+///     builder.finish().unwrap();
+///
+///     archive::untar(
+///         "./target/__test/archive_test/builder/site.tar.gz",
+///         "./target/__test/archive_test/builder/dest",
+///     )
+///     .unwrap();
+///     let text = file::read_text_file("./target/__test/archive_test/builder/dest/index.html").unwrap();
+///     assert_eq!(text, "<html></html>");
+///     let text = file::read_text_file("./target/__test/archive_test/builder/dest/assets/app.css").unwrap();
+///     assert_eq!(text, "body {}");
+/// }
+/// ```
+pub struct Builder {
+    path: PathBuf,
+    writer: Writer,
+}
+
+impl Builder {
+    /// 创建归档文件(包括父级目录, 如果存在则覆盖文件)
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 归档文件路径
+    /// * `format` - 归档格式
+    pub fn create<T: AsPath + ?Sized>(path: &T, format: ArchiveFormat) -> FsIOResult<Builder> {
+        let archive_path = path.as_path();
+        directory::create_parent(&archive_path)?;
+
+        let file = match File::create(archive_path) {
+            Ok(file) => file,
+            Err(error) => return Err(FsIOError::io(Operation::Create, &archive_path, error)),
+        };
+
+        let writer = match format {
+            ArchiveFormat::Zip(method) => {
+                let method = match method {
+                    CompressionMethod::Stored => zip::CompressionMethod::Stored,
+                    CompressionMethod::Deflated => zip::CompressionMethod::Deflated,
+                };
+                let options = SimpleFileOptions::default().compression_method(method);
+                Writer::Zip(ZipWriter::new(file), options)
+            }
+            ArchiveFormat::Tar(TarCompression::None) => Writer::Tar(::tar::Builder::new(file)),
+            ArchiveFormat::Tar(TarCompression::Gzip(level)) => Writer::TarGz(::tar::Builder::new(
+                GzEncoder::new(file, Compression::new(level)),
+            )),
+        };
+
+        Ok(Builder {
+            path: archive_path.to_path_buf(),
+            writer,
+        })
+    }
+
+    /// 返回归档文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 添加磁盘上的文件(跟随符号链接), tar条目保留文件的权限和修改时间
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 条目名称
+    /// * `path` - 文件路径
+    pub fn add_file<T: AsPath + ?Sized>(&mut self, name: &str, path: &T) -> FsIOResult<()> {
+        let name = entry_name(name)?;
+        let file_path = path.as_path();
+
+        let mut file = match File::open(file_path) {
+            Ok(file) => file,
+            Err(error) => return Err(FsIOError::io(Operation::Read, &file_path, error)),
+        };
+
+        let result = match self.writer {
+            Writer::Zip(ref mut writer, options) => writer
+                .start_file(name, options)
+                .map_err(io::Error::from)
+                .and_then(|_| io::copy(&mut file, writer))
+                .map(|_| ()),
+            Writer::Tar(ref mut builder) => builder.append_file(name, &mut file),
+            Writer::TarGz(ref mut builder) => builder.append_file(name, &mut file),
+        };
+
+        self.check(result)
+    }
+
+    /// 添加(空)目录条目
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 条目名称
+    pub fn add_dir(&mut self, name: &str) -> FsIOResult<()> {
+        let name = entry_name(name)?;
+
+        let result = match self.writer {
+            Writer::Zip(ref mut writer, options) => {
+                writer.add_directory(name, options).map_err(io::Error::from)
+            }
+            Writer::Tar(ref mut builder) => append_tar(builder, &name, 0o755, true, &[]),
+            Writer::TarGz(ref mut builder) => append_tar(builder, &name, 0o755, true, &[]),
+        };
+
+        self.check(result)
+    }
+
+    /// 添加内存中的数据作为文件条目(tar条目的权限为 `0o644`, 修改时间为当前时间)
+    ///
+    /// # 参数
+    ///
+    /// * `name` - 条目名称
+    /// * `data` - 文件内容
+    pub fn add_bytes(&mut self, name: &str, data: &[u8]) -> FsIOResult<()> {
+        let name = entry_name(name)?;
+
+        let result = match self.writer {
+            Writer::Zip(ref mut writer, options) => writer
+                .start_file(name, options)
+                .map_err(io::Error::from)
+                .and_then(|_| writer.write_all(data)),
+            Writer::Tar(ref mut builder) => append_tar(builder, &name, 0o644, false, data),
+            Writer::TarGz(ref mut builder) => append_tar(builder, &name, 0o644, false, data),
+        };
+
+        self.check(result)
+    }
+
+    /// 写入归档的结尾并关闭文件
+    pub fn finish(self) -> FsIOResult<()> {
+        let result = match self.writer {
+            Writer::Zip(writer, _) => writer.finish().map(|_| ()).map_err(io::Error::from),
+            Writer::Tar(builder) => builder.into_inner().and_then(|mut file| file.flush()),
+            Writer::TarGz(builder) => builder
+                .into_inner()
+                .and_then(|encoder| encoder.finish())
+                .map(|_| ()),
+        };
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(FsIOError::io(Operation::Write, &self.path, error)),
+        }
+    }
+
+    fn check(&self, result: io::Result<()>) -> FsIOResult<()> {
+        match result {
+            Ok(_) => Ok(()),
+            Err(error) => Err(FsIOError::io(Operation::Write, &self.path, error)),
+        }
+    }
+}
+
+/// 校验条目名称并去掉结尾的 `/`
+fn entry_name(name: &str) -> FsIOResult<String> {
+    let trimmed = name.trim_end_matches('/');
+    let relative = join_relative(Path::new(""), Path::new(trimmed))?;
+    if relative.as_os_str().is_empty() {
+        return Err(FsIOError::io(
+            Operation::WriteArchive,
+            name,
+            io::Error::new(io::ErrorKind::InvalidInput, "invalid archive entry name"),
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+fn append_tar<W: Write>(
+    builder: &mut ::tar::Builder<W>,
+    name: &str,
+    mode: u32,
+    is_dir: bool,
+    data: &[u8],
+) -> io::Result<()> {
+    let mut header = ::tar::Header::new_gnu();
+    header.set_entry_type(if is_dir {
+        ::tar::EntryType::Directory
+    } else {
+        ::tar::EntryType::Regular
+    });
+    header.set_mode(mode);
+    header.set_size(data.len() as u64);
+    let modified = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    header.set_mtime(modified);
+    builder.append_data(&mut header, name, data)
+}
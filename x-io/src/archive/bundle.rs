@@ -0,0 +1,324 @@
+//! # bundle
+//!
+//! Release bundles: archives with an embedded checksum manifest and a sidecar checksum file.
+//!
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::path::Path;
+
+use x_hash::DigestFmt;
+use zip::ZipArchive;
+
+use crate::archive::tar::open_tar;
+use crate::archive::{
+    collect_entries, entry_name, is_zip_file, ArchiveFormat, Builder, TarCompression,
+};
+use crate::error::{FsIOError, Operation};
+use crate::file;
+use crate::file::ChecksumAlgorithm;
+use crate::filter::PathFilter;
+use crate::path::as_path::AsPath;
+use crate::result::FsIOResult;
+use crate::walk::SymlinkPolicy;
+
+/// 发布包选项
+#[derive(Debug, Clone)]
+pub struct BundleOptions {
+    /// 归档格式
+    pub format: ArchiveFormat,
+    /// 是否在归档根目录嵌入清单文件 `MANIFEST.<扩展名>`(例如 `MANIFEST.sha256`), 每个文件一行 `<校验和>  <相对路径>`
+    pub manifest: bool,
+    /// 路径过滤器(路径相对于源目录), 被排除的文件和目录不会被打包
+    pub filter: Option<PathFilter>,
+}
+
+impl Default for BundleOptions {
+    fn default() -> Self {
+        BundleOptions {
+            format: ArchiveFormat::Tar(TarCompression::Gzip(6)),
+            manifest: true,
+            filter: None,
+        }
+    }
+}
+
+/// 将目录打包为发布包, 并在归档旁边写入归档的校验和文件(例如 `release.tar.gz.sha256`, 参见 `file::checksum_path`)
+///
+/// 符号链接会被跟随. 使用 `verify_bundle` 验证发布包.
+///
+/// # 参数
+///
+/// * `dir` - 源目录
+/// * `out` - 归档文件路径
+/// * `options` - 发布包选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{archive, file};
+/// use x_io::archive::BundleOptions;
+/// use x_hash::Sha256;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/archive_test/write_bundle/dist/bin/app", "app").unwrap();
+///     file::write_text_file("./target/__test/archive_test/write_bundle/dist/README.md", "readme").unwrap();
+///
+///     let bundle_path = "./target/__test/archive_test/write_bundle/release.tar.gz";
+///     archive::write_bundle::<Sha256, _, _>(
+///         "./target/__test/archive_test/write_bundle/dist",
+///         bundle_path,
+///         BundleOptions::default(),
+///     )
+///     .unwrap();
+///
+///     assert!(file::checksum_path::<Sha256, _>(bundle_path).exists());
+///     assert!(archive::verify_bundle::<Sha256, _>(bundle_path).is_ok());
+/// }
+/// ```
+pub fn write_bundle<D, S, T>(dir: &S, out: &T, options: BundleOptions) -> FsIOResult<()>
+where
+    D: ChecksumAlgorithm,
+    S: AsPath + ?Sized,
+    T: AsPath + ?Sized,
+{
+    let source_path = dir.as_path();
+    let target_path = out.as_path();
+
+    if !source_path.is_dir() {
+        return Err(FsIOError::NotFile(
+            format!("Path: {:?} is not a directory.", &source_path).to_string(),
+        ));
+    }
+
+    let entries = collect_entries(
+        source_path,
+        None,
+        options.filter.as_ref(),
+        SymlinkPolicy::Follow,
+    )?;
+
+    let mut builder = Builder::create(target_path, options.format)?;
+    let mut manifest = String::new();
+    for relative in &entries {
+        let entry_path = source_path.join(relative);
+        let name = entry_name(relative);
+
+        if entry_path.is_dir() {
+            builder.add_dir(&name)?;
+            continue;
+        }
+
+        if options.manifest {
+            let checksum = match File::open(&entry_path).and_then(|mut file| digest::<D>(&mut file))
+            {
+                Ok(checksum) => checksum,
+                Err(error) => return Err(FsIOError::io(Operation::Read, &entry_path, error)),
+            };
+            manifest.push_str(&format!("{}  {}\n", checksum, name));
+        }
+        builder.add_file(&name, &entry_path)?;
+    }
+
+    if options.manifest {
+        builder.add_bytes(&manifest_name::<D>(), manifest.as_bytes())?;
+    }
+    builder.finish()?;
+
+    let checksum = match File::open(target_path).and_then(|mut file| digest::<D>(&mut file)) {
+        Ok(checksum) => checksum,
+        Err(error) => return Err(FsIOError::io(Operation::Read, &target_path, error)),
+    };
+    let file_name = target_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    file::write_file_atomic(
+        &file::checksum_path::<D, _>(target_path),
+        format!("{}  {}\n", checksum, file_name).as_bytes(),
+    )
+}
+
+/// 验证 `write_bundle` 创建的发布包
+///
+/// 先使用旁边的校验和文件验证归档, 归档中有清单文件时再验证清单中的每个文件.
+/// 校验和不一致时返回 `FsIOError::ChecksumMismatch`, 清单中的文件不在归档中时返回 `FsIOError::NotFound`,
+/// 归档中的文件不在清单中时返回 `FsIOError::Parse`.
+///
+/// # 参数
+///
+/// * `path` - 归档文件路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{archive, file};
+/// use x_io::archive::{ArchiveFormat, BundleOptions, CompressionMethod};
+/// use x_io::error::FsIOError;
+/// use x_hash::Sha256;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/archive_test/verify_bundle/dist/app.txt", "app").unwrap();
+///
+///     let bundle_path = "./target/__test/archive_test/verify_bundle/release.zip";
+///     let options = BundleOptions {
+///         format: ArchiveFormat::Zip(CompressionMethod::Deflated),
+///         ..BundleOptions::default()
+///     };
+///     archive::write_bundle::<Sha256, _, _>("./target/__test/archive_test/verify_bundle/dist", bundle_path, options).unwrap();
+///     assert!(archive::verify_bundle::<Sha256, _>(bundle_path).is_ok());
+///
+///     // a corrupted download is reported
+///     let mut data = file::read_file(bundle_path).unwrap();
+///     let last = data.len() - 1;
+///     data[last] ^= 0xff;
+///     file::write_file(bundle_path, &data).unwrap();
+///     let result = archive::verify_bundle::<Sha256, _>(bundle_path);
+///     assert!(matches!(result, Err(FsIOError::ChecksumMismatch { .. })));
+/// }
+/// ```
+pub fn verify_bundle<D, T>(path: &T) -> FsIOResult<()>
+where
+    D: ChecksumAlgorithm,
+    T: AsPath + ?Sized,
+{
+    let bundle_path = path.as_path();
+
+    let checksum_file_path = file::checksum_path::<D, _>(bundle_path);
+    let checksum_text = file::read_text_file(&checksum_file_path)?;
+    let expected = match checksum_text.split_whitespace().next() {
+        Some(expected) => expected.to_ascii_lowercase(),
+        None => {
+            return Err(FsIOError::Parse(
+                format!("Checksum file: {:?} is empty.", &checksum_file_path).to_string(),
+                None,
+            ))
+        }
+    };
+    let actual = match File::open(bundle_path).and_then(|mut file| digest::<D>(&mut file)) {
+        Ok(actual) => actual,
+        Err(error) => return Err(FsIOError::io(Operation::Read, &bundle_path, error)),
+    };
+    if actual != expected {
+        return Err(FsIOError::ChecksumMismatch {
+            path: bundle_path.to_path_buf(),
+            expected,
+            actual,
+        });
+    }
+
+    let checksums = match archive_checksums::<D>(bundle_path) {
+        Ok(checksums) => checksums,
+        Err(error) => return Err(FsIOError::io(Operation::Read, &bundle_path, error)),
+    };
+    let manifest_name = manifest_name::<D>();
+    let manifest = match checksums.get(&manifest_name) {
+        Some((_, content)) => String::from_utf8_lossy(content).into_owned(),
+        None => return Ok(()),
+    };
+
+    let mut listed = BTreeMap::new();
+    for line in manifest.lines().filter(|line| !line.trim().is_empty()) {
+        match line.split_once("  ") {
+            Some((checksum, name)) => {
+                listed.insert(name.to_string(), checksum.to_ascii_lowercase());
+            }
+            None => {
+                return Err(FsIOError::Parse(
+                    format!(
+                        "Invalid manifest line: {:?} in bundle: {:?}",
+                        line, &bundle_path
+                    )
+                    .to_string(),
+                    None,
+                ))
+            }
+        }
+    }
+
+    for (name, expected) in &listed {
+        match checksums.get(name) {
+            Some((actual, _)) if actual == expected => {}
+            Some((actual, _)) => {
+                return Err(FsIOError::ChecksumMismatch {
+                    path: bundle_path.join(name),
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                })
+            }
+            None => return Err(FsIOError::NotFound(bundle_path.join(name), None)),
+        }
+    }
+    if let Some(name) = checksums
+        .keys()
+        .find(|name| **name != manifest_name && !listed.contains_key(*name))
+    {
+        return Err(FsIOError::Parse(
+            format!(
+                "File: {:?} in bundle: {:?} is not listed in the manifest.",
+                name, &bundle_path
+            )
+            .to_string(),
+            None,
+        ));
+    }
+
+    Ok(())
+}
+
+fn manifest_name<D: ChecksumAlgorithm>() -> String {
+    format!("MANIFEST.{}", D::EXTENSION)
+}
+
+fn digest<D: ChecksumAlgorithm>(reader: &mut dyn Read) -> io::Result<String> {
+    let mut hasher = D::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        match reader.read(&mut buffer)? {
+            0 => break,
+            size => hasher.update(&buffer[..size]),
+        }
+    }
+    Ok(DigestFmt(hasher.result()).to_string())
+}
+
+/// 返回归档中每个文件的(校验和, 清单内容), 只保留清单文件的内容
+fn archive_checksums<D: ChecksumAlgorithm>(
+    path: &Path,
+) -> io::Result<BTreeMap<String, (String, Vec<u8>)>> {
+    let manifest_name = manifest_name::<D>();
+    let mut checksums = BTreeMap::new();
+    let mut record = |name: String, reader: &mut dyn Read| -> io::Result<()> {
+        if name == manifest_name {
+            let mut content = vec![];
+            reader.read_to_end(&mut content)?;
+            checksums.insert(name, (String::new(), content));
+        } else {
+            checksums.insert(name, (digest::<D>(reader)?, vec![]));
+        }
+        Ok(())
+    };
+
+    if is_zip_file(path)? {
+        let mut archive = ZipArchive::new(File::open(path)?).map_err(io::Error::from)?;
+        for index in 0..archive.len() {
+            let mut entry = archive.by_index(index).map_err(io::Error::from)?;
+            if entry.is_file() {
+                let name = entry.name().to_string();
+                record(name, &mut entry)?;
+            }
+        }
+    } else {
+        let mut archive = ::tar::Archive::new(open_tar(path)?);
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if entry.header().entry_type().is_file() {
+                let name = entry_name(&entry.path()?);
+                record(name, &mut entry)?;
+            }
+        }
+    }
+
+    Ok(checksums)
+}
@@ -0,0 +1,213 @@
+//! # extract
+//!
+//! Archive extraction with filtering, leading component stripping and progress reporting.
+//!
+use std::fmt;
+use std::fs::symlink_metadata;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::archive::is_zip_file;
+use crate::archive::tar::extract_tar;
+use crate::archive::zip::extract_zip;
+use crate::error::{FsIOError, Operation};
+use crate::filter::PathFilter;
+use crate::path::as_path::AsPath;
+use crate::path::join_relative;
+use crate::progress::{ProgressCallback, ProgressEvent};
+use crate::result::FsIOResult;
+
+/// 解压选项
+pub struct ExtractOptions {
+    /// 路径过滤器(路径为去掉前缀之后的条目路径), 被排除的条目不会被解压
+    pub filter: Option<PathFilter>,
+    /// 是否覆盖已存在的文件, 为false时跳过已存在的文件
+    pub overwrite: bool,
+    /// 去掉条目路径开头的组件数量(与 `tar --strip-components` 相同), 组件数量不超过该值的条目会被跳过
+    pub strip_components: usize,
+    /// 进度回调, 每创建一个目录时发出 `DirCreated`, 每解压一个文件或链接后发出 `BytesWritten`
+    pub progress_callback: Option<ProgressCallback>,
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        ExtractOptions {
+            filter: None,
+            overwrite: true,
+            strip_components: 0,
+            progress_callback: None,
+        }
+    }
+}
+
+impl fmt::Debug for ExtractOptions {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter
+            .debug_struct("ExtractOptions")
+            .field("filter", &self.filter)
+            .field("overwrite", &self.overwrite)
+            .field("strip_components", &self.strip_components)
+            .field("progress_callback", &self.progress_callback.is_some())
+            .finish()
+    }
+}
+
+impl ExtractOptions {
+    /// 返回条目去掉前缀之后的相对路径和输出路径, 条目被跳过时返回 `None`
+    ///
+    /// 条目路径不安全(绝对路径或包含 `..`)或输出路径的父级目录是符号链接时返回错误.
+    pub(crate) fn plan(
+        &self,
+        dest_dir: &Path,
+        name: &Path,
+        is_dir: bool,
+        size: u64,
+        modified: Option<SystemTime>,
+    ) -> FsIOResult<Option<(PathBuf, PathBuf)>> {
+        let relative = match strip_name(name, self.strip_components)? {
+            Some(relative) => relative,
+            None => return Ok(None),
+        };
+
+        if let Some(ref filter) = self.filter {
+            if !filter.matches_archive_entry(&relative, is_dir, size, modified) {
+                return Ok(None);
+            }
+        }
+
+        let output_path = join_relative(dest_dir, &relative)?;
+        if !is_dir && !self.overwrite && symlink_metadata(&output_path).is_ok() {
+            return Ok(None);
+        }
+
+        let mut parent = dest_dir.to_path_buf();
+        if let Some(relative_parent) = relative.parent() {
+            for component in relative_parent.components() {
+                parent.push(component);
+                if symlink_metadata(&parent).is_ok_and(|metadata| metadata.file_type().is_symlink())
+                {
+                    return Err(FsIOError::io(
+                        Operation::Extract,
+                        &name,
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "unsafe path in archive through symbolic link",
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(Some((relative, output_path)))
+    }
+
+    /// 调用进度回调
+    pub(crate) fn emit(&self, event: ProgressEvent) {
+        if let Some(ref callback) = self.progress_callback {
+            callback(&event);
+        }
+    }
+}
+
+/// 去掉条目路径开头的 `count` 个组件, 剩余组件为空时返回 `None`, 路径不安全时返回错误
+pub(crate) fn strip_name(name: &Path, count: usize) -> FsIOResult<Option<PathBuf>> {
+    // validates the whole name, so stripping cannot hide a `..` or an absolute path
+    join_relative(Path::new(""), name)?;
+
+    let remaining: PathBuf = name
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .skip(count)
+        .collect();
+
+    if remaining.as_os_str().is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(remaining))
+    }
+}
+
+/// 将zip或tar(包括tar.gz)文件解压到目录, 根据文件内容判断归档格式
+///
+/// 拒绝解压到目录之外的条目, 指向目录之外的符号链接和经过符号链接写入的条目.
+///
+/// # 参数
+///
+/// * `archive_path` - 归档文件路径
+/// * `dest_dir` - 目标目录
+/// * `options` - 解压选项
+///
+/// # 示例
+///
+/// ```
+/// use std::path::Path;
+/// use std::sync::{Arc, Mutex};
+/// use x_io::{archive, directory, file};
+/// use x_io::archive::{ExtractOptions, TarOptions};
+/// use x_io::filter::PathFilter;
+/// use x_io::progress::ProgressEvent;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/archive_test/extract_with/src/pkg-1.0/README.md", "readme").unwrap();
+///     file::write_text_file("./target/__test/archive_test/extract_with/src/pkg-1.0/bin/tool", "tool").unwrap();
+///     archive::tar_dir(
+///         "./target/__test/archive_test/extract_with/src",
+///         "./target/__test/archive_test/extract_with/pkg.tar.gz",
+///         TarOptions::default(),
+///     )
+///     .unwrap();
+///     directory::delete("./target/__test/archive_test/extract_with/dest").unwrap();
+///
+///     let written = Arc::new(Mutex::new(vec![]));
+///     let events = written.clone();
+///     let options = ExtractOptions {
+///         filter: Some(PathFilter::new().include("bin/*").unwrap()),
+///         strip_components: 1,
+///         progress_callback: Some(Box::new(move |event| {
+///             if let ProgressEvent::BytesWritten(path, _) = event {
+///                 events.lock().unwrap().push(path.clone());
+///             }
+///         })),
+///         ..ExtractOptions::default()
+///     };
+///     archive::extract_with(
+///         "./target/__test/archive_test/extract_with/pkg.tar.gz",
+///         "./target/__test/archive_test/extract_with/dest",
+///         options,
+///     )
+///     .unwrap();
+///
+///     let text = file::read_text_file("./target/__test/archive_test/extract_with/dest/bin/tool").unwrap();
+///     assert_eq!(text, "tool");
+///     assert!(!Path::new("./target/__test/archive_test/extract_with/dest/README.md").exists());
+///     assert_eq!(written.lock().unwrap().len(), 1);
+///
+///     // existing files are kept when overwrite is disabled
+///     file::write_text_file("./target/__test/archive_test/extract_with/dest/bin/tool", "local").unwrap();
+///     let options = ExtractOptions { overwrite: false, strip_components: 1, ..ExtractOptions::default() };
+///     archive::extract_with(
+///         "./target/__test/archive_test/extract_with/pkg.tar.gz",
+///         "./target/__test/archive_test/extract_with/dest",
+///         options,
+///     )
+///     .unwrap();
+///     let text = file::read_text_file("./target/__test/archive_test/extract_with/dest/bin/tool").unwrap();
+///     assert_eq!(text, "local");
+///     assert!(Path::new("./target/__test/archive_test/extract_with/dest/README.md").exists());
+/// }
+/// ```
+pub fn extract_with<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    archive_path: &S,
+    dest_dir: &T,
+    options: ExtractOptions,
+) -> FsIOResult<()> {
+    let source_path = archive_path.as_path();
+    let target_path = dest_dir.as_path();
+
+    match is_zip_file(source_path) {
+        Ok(true) => extract_zip(source_path, target_path, &options),
+        Ok(false) => extract_tar(source_path, target_path, &options),
+        Err(error) => Err(FsIOError::io(Operation::Read, &source_path, error)),
+    }
+}
@@ -0,0 +1,157 @@
+//! # archive
+//!
+//! Archive creation and extraction (requires the `archive` feature).
+//!
+use std::fs::{read_link, remove_file, File};
+use std::io;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use crate::dunce;
+use crate::error::{FsIOError, Operation};
+use crate::filter::PathFilter;
+use crate::ignore::IgnoreSet;
+use crate::path::resolve_symlinks;
+use crate::result::FsIOResult;
+use crate::walk::{SymlinkPolicy, Walk, WalkOptions};
+
+pub use self::builder::{ArchiveFormat, Builder};
+pub use self::bundle::{verify_bundle, write_bundle, BundleOptions};
+pub use self::extract::{extract_with, ExtractOptions};
+pub use self::tar::{tar_dir, untar, TarCompression, TarOptions};
+pub use self::zip::{unzip, zip_dir, CompressionMethod, ZipOptions};
+
+mod builder;
+mod bundle;
+mod extract;
+mod tar;
+mod zip;
+
+/// 返回目录下所有未被忽略条目的相对路径(按字典序, 父目录在子条目之前)
+fn collect_entries(
+    root: &Path,
+    ignore: Option<&IgnoreSet>,
+    filter: Option<&PathFilter>,
+    symlinks: SymlinkPolicy,
+) -> FsIOResult<Vec<PathBuf>> {
+    let options = WalkOptions {
+        symlinks,
+        ..WalkOptions::default()
+    };
+
+    let mut walk = Walk::new(root, options);
+    if let Some(filter) = filter {
+        walk = walk.with_filter(filter.clone());
+    }
+
+    let mut entries = vec![];
+    for entry in walk {
+        let entry = entry?;
+        let relative = match entry.path().strip_prefix(root) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => continue,
+        };
+
+        if ignore.is_some_and(|ignore| ignore.is_ignored_with(&relative, entry.is_dir())) {
+            entry.skip_subtree();
+            continue;
+        }
+        entries.push(relative);
+    }
+
+    Ok(entries)
+}
+
+/// 返回符号链接(相对于归档根目录的路径)的目标是否位于解压目录之内
+///
+/// 先按字面检查, 再按磁盘上已解压的内容(包括之前创建的符号链接)解析目标路径,
+/// 因此 `sub/up -> ..` 之后的 `x -> sub/up/sub/up/..` 会被拒绝.
+fn is_contained_link(canonical_dest: &Path, link: &Path, target: &Path) -> bool {
+    let mut depth: usize = 0;
+    let parent = link.parent().unwrap_or(Path::new(""));
+    for component in parent.components().chain(target.components()) {
+        match component {
+            Component::Normal(_) => depth += 1,
+            Component::CurDir => {}
+            Component::ParentDir => match depth.checked_sub(1) {
+                Some(value) => depth = value,
+                None => return false,
+            },
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+
+    match resolve_symlinks(&canonical_dest.join(parent).join(target)) {
+        Ok(resolved) => resolved.starts_with(canonical_dest),
+        Err(_) => false,
+    }
+}
+
+/// 解压完成后按磁盘上的最终内容重新检查创建的符号链接(相对于解压目录的路径), 删除指向解压目录之外的链接并返回错误
+///
+/// 之后的条目可能改变之前创建的链接的解析结果(`x -> d/..` 之后的 `d -> .`), 因此只在创建时检查是不够的.
+fn verify_links(canonical_dest: &Path, links: &[PathBuf]) -> FsIOResult<()> {
+    let mut unsafe_link = None;
+    for relative in links {
+        let link_path = canonical_dest.join(relative);
+        // the link may have been replaced by a later entry
+        let target = match read_link(&link_path) {
+            Ok(target) => target,
+            Err(_) => continue,
+        };
+        if is_contained_link(canonical_dest, relative, &target) {
+            continue;
+        }
+
+        if let Err(error) = remove_file(&link_path) {
+            return Err(FsIOError::io(Operation::Delete, &link_path, error));
+        }
+        if unsafe_link.is_none() {
+            unsafe_link = Some((relative, target));
+        }
+    }
+
+    match unsafe_link {
+        Some((relative, target)) => Err(FsIOError::io(
+            Operation::Extract,
+            &relative,
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unsafe symbolic link in archive to: {:?}", target),
+            ),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 返回解压目录的规范路径, 用于解析符号链接的目标
+fn canonical_dest(path: &Path) -> FsIOResult<PathBuf> {
+    match dunce::canonicalize(path) {
+        Ok(canonical_path) => Ok(canonical_path),
+        Err(error) => Err(FsIOError::io(Operation::Canonicalize, &path, error)),
+    }
+}
+
+/// 将相对路径转换为归档条目名称(使用 `/` 分隔)
+fn entry_name(relative: &Path) -> String {
+    let names: Vec<String> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    names.join("/")
+}
+
+/// 根据文件开头的签名返回文件是否是zip文件(否则按tar文件处理)
+fn is_zip_file(path: &Path) -> io::Result<bool> {
+    let mut magic = [0u8; 4];
+    let mut file = File::open(path)?;
+    let mut size = 0;
+    while size < magic.len() {
+        match file.read(&mut magic[size..])? {
+            0 => break,
+            read => size += read,
+        }
+    }
+
+    Ok(size == magic.len() && (magic == *b"PK\x03\x04" || magic == *b"PK\x05\x06"))
+}
@@ -0,0 +1,422 @@
+//! # tar
+//!
+//! Tar (and tar.gz) archive creation and extraction.
+//!
+use std::fs::{hard_link, remove_file, symlink_metadata, File};
+use std::io;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tar::{Archive, Builder};
+
+use crate::archive::extract::{strip_name, ExtractOptions};
+use crate::archive::{
+    canonical_dest, collect_entries, entry_name, is_contained_link, verify_links,
+};
+use crate::directory;
+use crate::error::{FsIOError, Operation};
+use crate::filter::PathFilter;
+use crate::ignore::IgnoreSet;
+use crate::path::as_path::AsPath;
+use crate::path::{join_relative, resolve_symlinks};
+use crate::progress::ProgressEvent;
+use crate::result::FsIOResult;
+use crate::walk::SymlinkPolicy;
+
+/// tar压缩方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TarCompression {
+    /// 不压缩(.tar)
+    None,
+    /// gzip压缩(.tar.gz), 参数为压缩级别(0-9)
+    Gzip(u32),
+}
+
+/// tar打包选项
+#[derive(Debug, Clone)]
+pub struct TarOptions {
+    /// 压缩方式
+    pub compression: TarCompression,
+    /// 符号链接的处理方式, 默认保留符号链接(`SymlinkPolicy::Preserve`)
+    pub symlinks: SymlinkPolicy,
+    /// 忽略规则(路径相对于源目录), 被忽略的文件和目录不会被打包
+    pub ignore: Option<IgnoreSet>,
+    /// 路径过滤器(路径相对于源目录), 被排除的文件和目录不会被打包
+    pub filter: Option<PathFilter>,
+}
+
+impl Default for TarOptions {
+    fn default() -> Self {
+        TarOptions {
+            compression: TarCompression::None,
+            symlinks: SymlinkPolicy::Preserve,
+            ignore: None,
+            filter: None,
+        }
+    }
+}
+
+/// 将目录打包为tar文件(保留unix权限和符号链接), 如果存在则覆盖文件
+///
+/// # 参数
+///
+/// * `src_dir` - 源目录
+/// * `tar_path` - tar文件路径
+/// * `options` - 打包选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{archive, directory, file};
+/// use x_io::archive::{TarCompression, TarOptions};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/archive_test/tar_dir/src/dir1/file.txt", "some content").unwrap();
+///
+///     let options = TarOptions {
+///         compression: TarCompression::Gzip(6),
+///         ..TarOptions::default()
+///     };
+///     let result = archive::tar_dir(
+///         "./target/__test/archive_test/tar_dir/src",
+///         "./target/__test/archive_test/tar_dir/out.tar.gz",
+///         options,
+///     );
+///     assert!(result.is_ok());
+///
+///     directory::delete("./target/__test/archive_test/tar_dir/dest").unwrap();
+///     let result = archive::untar(
+///         "./target/__test/archive_test/tar_dir/out.tar.gz",
+///         "./target/__test/archive_test/tar_dir/dest",
+///     );
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file("./target/__test/archive_test/tar_dir/dest/dir1/file.txt").unwrap();
+///     assert_eq!(text, "some content");
+/// }
+/// ```
+pub fn tar_dir<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    src_dir: &S,
+    tar_path: &T,
+    options: TarOptions,
+) -> FsIOResult<()> {
+    let source_path = src_dir.as_path();
+    let target_path = tar_path.as_path();
+
+    if !source_path.is_dir() {
+        return Err(FsIOError::NotFile(
+            format!("Path: {:?} is not a directory.", &source_path).to_string(),
+        ));
+    }
+
+    let entries = collect_entries(
+        source_path,
+        options.ignore.as_ref(),
+        options.filter.as_ref(),
+        options.symlinks,
+    )?;
+    directory::create_parent(tar_path)?;
+
+    let file = match File::create(target_path) {
+        Ok(file) => file,
+        Err(error) => return Err(FsIOError::io(Operation::Create, &target_path, error)),
+    };
+
+    let result = match options.compression {
+        TarCompression::None => append_entries(file, source_path, &entries, &options).map(|_| ()),
+        TarCompression::Gzip(level) => {
+            let encoder = GzEncoder::new(file, Compression::new(level));
+            append_entries(encoder, source_path, &entries, &options)
+                .and_then(|encoder| encoder.finish())
+                .map(|_| ())
+        }
+    };
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::WriteArchive, &target_path, error)),
+    }
+}
+
+fn append_entries<W: Write>(
+    writer: W,
+    source_path: &Path,
+    entries: &[PathBuf],
+    options: &TarOptions,
+) -> io::Result<W> {
+    let mut builder = Builder::new(writer);
+    builder.follow_symlinks(options.symlinks == SymlinkPolicy::Follow);
+
+    for relative in entries {
+        builder.append_path_with_name(source_path.join(relative), entry_name(relative))?;
+    }
+
+    let mut writer = builder.into_inner()?;
+    writer.flush()?;
+    Ok(writer)
+}
+
+/// 将tar或tar.gz文件(自动识别gzip)解压到目录, 拒绝解压到目录之外的条目
+///
+/// 指向目录之外的符号链接会被拒绝. 需要过滤条目或去掉路径前缀时使用 `extract_with`.
+///
+/// # 参数
+///
+/// * `tar_path` - tar文件路径
+/// * `dest_dir` - 目标目录
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{archive, file};
+/// use x_io::archive::TarOptions;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/archive_test/untar/src/file.txt", "some content").unwrap();
+///     archive::tar_dir(
+///         "./target/__test/archive_test/untar/src",
+///         "./target/__test/archive_test/untar/out.tar",
+///         TarOptions::default(),
+///     ).unwrap();
+///
+///     let result = archive::untar(
+///         "./target/__test/archive_test/untar/out.tar",
+///         "./target/__test/archive_test/untar/dest",
+///     );
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file("./target/__test/archive_test/untar/dest/file.txt").unwrap();
+///     assert_eq!(text, "some content");
+///
+///     // links are resolved through links extracted before them
+///     #[cfg(unix)]
+///     {
+///         use std::os::unix::fs::symlink;
+///
+///         let _ = std::fs::remove_dir_all("./target/__test/archive_test/untar/chain");
+///         std::fs::create_dir_all("./target/__test/archive_test/untar/chain/src/sub").unwrap();
+///         symlink("..", "./target/__test/archive_test/untar/chain/src/sub/up").unwrap();
+///         symlink("sub/up/sub/up/..", "./target/__test/archive_test/untar/chain/src/x").unwrap();
+///         archive::tar_dir(
+///             "./target/__test/archive_test/untar/chain/src",
+///             "./target/__test/archive_test/untar/chain/out.tar",
+///             TarOptions::default(),
+///         ).unwrap();
+///
+///         let result = archive::untar(
+///             "./target/__test/archive_test/untar/chain/out.tar",
+///             "./target/__test/archive_test/untar/chain/dest",
+///         );
+///         assert!(result.is_err());
+///     }
+///
+///     // hard links are not created through symbolic links that leave the directory
+///     #[cfg(unix)]
+///     {
+///         use tar::{Builder, EntryType, Header};
+///
+///         let _ = std::fs::remove_dir_all("./target/__test/archive_test/untar/hard_link");
+///         file::write_text_file("./target/__test/archive_test/untar/hard_link/secret", "secret").unwrap();
+///
+///         let mut builder = Builder::new(vec![]);
+///         for (name, entry_type, target) in [
+///             ("x", EntryType::Symlink, "d/.."),
+///             ("d", EntryType::Symlink, "."),
+///             ("h", EntryType::Link, "x/secret"),
+///         ] {
+///             let mut header = Header::new_gnu();
+///             header.set_entry_type(entry_type);
+///             header.set_size(0);
+///             builder.append_link(&mut header, name, target).unwrap();
+///         }
+///         let data = builder.into_inner().unwrap();
+///         file::write_file("./target/__test/archive_test/untar/hard_link/out.tar", &data).unwrap();
+///
+///         let result = archive::untar(
+///             "./target/__test/archive_test/untar/hard_link/out.tar",
+///             "./target/__test/archive_test/untar/hard_link/dest",
+///         );
+///         assert!(result.is_err());
+///         assert!(std::fs::symlink_metadata("./target/__test/archive_test/untar/hard_link/dest/h").is_err());
+///         assert!(std::fs::symlink_metadata("./target/__test/archive_test/untar/hard_link/dest/x").is_err());
+///     }
+/// }
+/// ```
+pub fn untar<S: AsPath + ?Sized, T: AsPath + ?Sized>(tar_path: &S, dest_dir: &T) -> FsIOResult<()> {
+    extract_tar(
+        tar_path.as_path(),
+        dest_dir.as_path(),
+        &ExtractOptions::default(),
+    )
+}
+
+/// 按解压选项将tar文件解压到目录
+pub(crate) fn extract_tar(
+    source_path: &Path,
+    target_path: &Path,
+    options: &ExtractOptions,
+) -> FsIOResult<()> {
+    let reader = match open_tar(source_path) {
+        Ok(reader) => reader,
+        Err(error) => return Err(FsIOError::io(Operation::Read, &source_path, error)),
+    };
+
+    directory::create(&target_path)?;
+    let canonical_target = canonical_dest(target_path)?;
+
+    let mut archive = Archive::new(reader);
+    archive.set_preserve_permissions(true);
+    archive.set_overwrite(true);
+
+    // links created before a failing entry are checked as well
+    let mut links = vec![];
+    let result = unpack_entries(
+        &mut archive,
+        source_path,
+        target_path,
+        &canonical_target,
+        options,
+        &mut links,
+    );
+    let verified = verify_links(&canonical_target, &links);
+    result.and(verified)
+}
+
+/// 解压所有条目, 创建的符号链接(相对于解压目录的路径)保存到 `links`
+fn unpack_entries(
+    archive: &mut Archive<Box<dyn Read>>,
+    source_path: &Path,
+    target_path: &Path,
+    canonical_target: &Path,
+    options: &ExtractOptions,
+    links: &mut Vec<PathBuf>,
+) -> FsIOResult<()> {
+    let entries = match archive.entries() {
+        Ok(entries) => entries,
+        Err(error) => return Err(FsIOError::io(Operation::ReadArchive, &source_path, error)),
+    };
+
+    for entry in entries {
+        let mut entry = match entry {
+            Ok(entry) => entry,
+            Err(error) => return Err(FsIOError::io(Operation::ReadArchive, &source_path, error)),
+        };
+
+        let name = match entry.path() {
+            Ok(name) => name.into_owned(),
+            Err(error) => return Err(FsIOError::io(Operation::ReadArchive, &source_path, error)),
+        };
+        let entry_type = entry.header().entry_type();
+        let is_dir = entry_type.is_dir();
+        let modified = entry
+            .header()
+            .mtime()
+            .ok()
+            .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds));
+
+        let (relative, output_path) =
+            match options.plan(target_path, &name, is_dir, entry.size(), modified)? {
+                Some(value) => value,
+                None => continue,
+            };
+
+        if is_dir {
+            let created = !output_path.is_dir();
+            if let Err(error) = entry.unpack(&output_path) {
+                return Err(FsIOError::io(
+                    Operation::CreateDirectory,
+                    &output_path,
+                    error,
+                ));
+            }
+            if created {
+                options.emit(ProgressEvent::DirCreated(output_path));
+            }
+            continue;
+        }
+
+        directory::create_parent(&output_path)?;
+
+        let is_link = entry_type.is_symlink() || entry_type.is_hard_link();
+        if is_link {
+            let link = match entry.link_name() {
+                Ok(Some(link)) => link.into_owned(),
+                Ok(None) => PathBuf::new(),
+                Err(error) => {
+                    return Err(FsIOError::io(Operation::ReadArchive, &source_path, error))
+                }
+            };
+            let unsafe_link = || {
+                FsIOError::io(
+                    Operation::Extract,
+                    &name,
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unsafe link in archive to: {:?}", &link),
+                    ),
+                )
+            };
+
+            // hard link targets are archive paths and are stripped like entry names,
+            // `hard_link` follows symbolic links already extracted so the resolved target is checked
+            let link_target = if entry_type.is_hard_link() {
+                let stripped = match strip_name(&link, options.strip_components)? {
+                    Some(stripped) => stripped,
+                    None => return Err(unsafe_link()),
+                };
+                let link_target = join_relative(canonical_target, &stripped)?;
+                match resolve_symlinks(&link_target) {
+                    Ok(resolved) if resolved.starts_with(canonical_target) => Some(link_target),
+                    _ => return Err(unsafe_link()),
+                }
+            } else if is_contained_link(canonical_target, &relative, &link) {
+                None
+            } else {
+                return Err(unsafe_link());
+            };
+
+            if symlink_metadata(&output_path).is_ok() {
+                if let Err(error) = remove_file(&output_path) {
+                    return Err(FsIOError::io(Operation::Delete, &output_path, error));
+                }
+            }
+
+            let result = match link_target {
+                Some(link_target) => hard_link(&link_target, &output_path),
+                None => entry.unpack(&output_path).map(|_| ()),
+            };
+            if let Err(error) = result {
+                return Err(FsIOError::io(Operation::Extract, &output_path, error));
+            }
+            // a hard link to a symbolic link is a symbolic link as well
+            if entry_type.is_symlink() || output_path.is_symlink() {
+                links.push(relative);
+            }
+            options.emit(ProgressEvent::BytesWritten(output_path, 0));
+            continue;
+        }
+
+        if let Err(error) = entry.unpack(&output_path) {
+            return Err(FsIOError::io(Operation::Extract, &output_path, error));
+        }
+        let size = entry.size();
+        options.emit(ProgressEvent::BytesWritten(output_path, size));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn open_tar(path: &Path) -> io::Result<Box<dyn Read>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let is_gzip = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+
+    if is_gzip {
+        Ok(Box::new(GzDecoder::new(reader)))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
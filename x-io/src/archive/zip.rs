@@ -0,0 +1,480 @@
+//! # zip
+//!
+//! Zip archive creation and extraction.
+//!
+use std::fs::{read_link, remove_file, symlink_metadata, File, OpenOptions};
+use std::io;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::archive::extract::ExtractOptions;
+use crate::archive::{
+    canonical_dest, collect_entries, entry_name, is_contained_link, verify_links,
+};
+use crate::directory;
+use crate::error::{FsIOError, Operation};
+use crate::filter::PathFilter;
+use crate::ignore::IgnoreSet;
+use crate::path::as_path::AsPath;
+use crate::path::create_symlink;
+use crate::progress::ProgressEvent;
+use crate::result::FsIOResult;
+use crate::walk::SymlinkPolicy;
+
+/// 压缩方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionMethod {
+    /// 不压缩
+    Stored,
+    /// Deflate压缩
+    Deflated,
+}
+
+/// zip压缩选项
+#[derive(Debug, Clone)]
+pub struct ZipOptions {
+    /// 压缩方式
+    pub method: CompressionMethod,
+    /// 压缩级别(Deflated为0-9), None表示默认级别
+    pub level: Option<i64>,
+    /// 忽略规则(路径相对于源目录), 被忽略的文件和目录不会被压缩
+    pub ignore: Option<IgnoreSet>,
+    /// 路径过滤器(路径相对于源目录), 被排除的文件和目录不会被压缩
+    pub filter: Option<PathFilter>,
+    /// 符号链接的处理方式, 默认压缩其指向的内容(`SymlinkPolicy::Follow`)
+    pub symlinks: SymlinkPolicy,
+}
+
+impl Default for ZipOptions {
+    fn default() -> Self {
+        ZipOptions {
+            method: CompressionMethod::Deflated,
+            level: None,
+            ignore: None,
+            filter: None,
+            symlinks: SymlinkPolicy::Follow,
+        }
+    }
+}
+
+/// 将目录压缩为zip文件, 如果存在则覆盖文件
+///
+/// # 参数
+///
+/// * `src_dir` - 源目录
+/// * `zip_path` - zip文件路径
+/// * `options` - 压缩选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{archive, directory, file};
+/// use x_io::archive::ZipOptions;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/archive_test/zip_dir/src/dir1/file.txt", "some content").unwrap();
+///
+///     let result = archive::zip_dir(
+///         "./target/__test/archive_test/zip_dir/src",
+///         "./target/__test/archive_test/zip_dir/out.zip",
+///         ZipOptions::default(),
+///     );
+///     assert!(result.is_ok());
+///
+///     directory::delete("./target/__test/archive_test/zip_dir/dest").unwrap();
+///     let result = archive::unzip(
+///         "./target/__test/archive_test/zip_dir/out.zip",
+///         "./target/__test/archive_test/zip_dir/dest",
+///     );
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file("./target/__test/archive_test/zip_dir/dest/dir1/file.txt").unwrap();
+///     assert_eq!(text, "some content");
+/// }
+/// ```
+///
+/// 保留符号链接(解压时重新创建):
+///
+/// ```
+/// use x_io::{archive, directory, file};
+/// use x_io::archive::ZipOptions;
+/// use x_io::walk::SymlinkPolicy;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/archive_test/zip_dir_symlinks/src/v1.2.3/app.txt", "app").unwrap();
+///
+///     #[cfg(unix)]
+///     {
+///         let link_path = "./target/__test/archive_test/zip_dir_symlinks/src/current";
+///         let _ = std::fs::remove_file(link_path);
+///         std::os::unix::fs::symlink("v1.2.3", link_path).unwrap();
+///
+///         let options = ZipOptions { symlinks: SymlinkPolicy::Preserve, ..ZipOptions::default() };
+///         archive::zip_dir(
+///             "./target/__test/archive_test/zip_dir_symlinks/src",
+///             "./target/__test/archive_test/zip_dir_symlinks/out.zip",
+///             options,
+///         )
+///         .unwrap();
+///
+///         directory::delete("./target/__test/archive_test/zip_dir_symlinks/dest").unwrap();
+///         archive::unzip(
+///             "./target/__test/archive_test/zip_dir_symlinks/out.zip",
+///             "./target/__test/archive_test/zip_dir_symlinks/dest",
+///         )
+///         .unwrap();
+///
+///         let target = std::fs::read_link("./target/__test/archive_test/zip_dir_symlinks/dest/current").unwrap();
+///         assert_eq!(target, std::path::PathBuf::from("v1.2.3"));
+///         let text = file::read_text_file("./target/__test/archive_test/zip_dir_symlinks/dest/current/app.txt").unwrap();
+///         assert_eq!(text, "app");
+///     }
+/// }
+/// ```
+pub fn zip_dir<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    src_dir: &S,
+    zip_path: &T,
+    options: ZipOptions,
+) -> FsIOResult<()> {
+    let source_path = src_dir.as_path();
+    let target_path = zip_path.as_path();
+
+    if !source_path.is_dir() {
+        return Err(FsIOError::NotFile(
+            format!("Path: {:?} is not a directory.", &source_path).to_string(),
+        ));
+    }
+
+    let entries = collect_entries(
+        source_path,
+        options.ignore.as_ref(),
+        options.filter.as_ref(),
+        options.symlinks,
+    )?;
+    directory::create_parent(zip_path)?;
+
+    let file = match File::create(target_path) {
+        Ok(file) => file,
+        Err(error) => return Err(FsIOError::io(Operation::Create, &target_path, error)),
+    };
+
+    let method = match options.method {
+        CompressionMethod::Stored => zip::CompressionMethod::Stored,
+        CompressionMethod::Deflated => zip::CompressionMethod::Deflated,
+    };
+    let file_options = SimpleFileOptions::default()
+        .compression_method(method)
+        .compression_level(options.level);
+
+    let mut writer = ZipWriter::new(file);
+    for relative in entries {
+        let entry_path = source_path.join(&relative);
+        let name = entry_name(&relative);
+
+        let is_symlink = options.symlinks == SymlinkPolicy::Preserve && entry_path.is_symlink();
+        let result = if is_symlink {
+            read_link(&entry_path).and_then(|target| {
+                writer
+                    .add_symlink_from_path(&name, target, file_options)
+                    .map_err(io::Error::from)
+            })
+        } else if entry_path.is_dir() {
+            writer
+                .add_directory(name, file_options)
+                .map_err(io::Error::from)
+        } else {
+            writer
+                .start_file(name, file_options)
+                .map_err(io::Error::from)
+                .and_then(|_| File::open(&entry_path))
+                .and_then(|mut entry_file| io::copy(&mut entry_file, &mut writer))
+                .map(|_| ())
+        };
+
+        if let Err(error) = result {
+            return Err(FsIOError::io(Operation::WriteArchive, &entry_path, error));
+        }
+    }
+
+    match writer.finish() {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(
+            Operation::WriteArchive,
+            &target_path,
+            io::Error::from(error),
+        )),
+    }
+}
+
+/// 将zip文件解压到目录, 拒绝解压到目录之外的条目
+///
+/// 符号链接条目会被重新创建为符号链接, 指向目录之外的符号链接会被拒绝. 需要过滤条目或去掉路径前缀时使用 `extract_with`.
+///
+/// # 参数
+///
+/// * `zip_path` - zip文件路径
+/// * `dest_dir` - 目标目录
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{archive, file};
+/// use x_io::archive::ZipOptions;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/archive_test/unzip/src/file.txt", "some content").unwrap();
+///     archive::zip_dir(
+///         "./target/__test/archive_test/unzip/src",
+///         "./target/__test/archive_test/unzip/out.zip",
+///         ZipOptions::default(),
+///     ).unwrap();
+///
+///     let result = archive::unzip(
+///         "./target/__test/archive_test/unzip/out.zip",
+///         "./target/__test/archive_test/unzip/dest",
+///     );
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file("./target/__test/archive_test/unzip/dest/file.txt").unwrap();
+///     assert_eq!(text, "some content");
+///
+///     // a symbolic link at the output path is replaced, not written through
+///     #[cfg(unix)]
+///     {
+///         let outside_path = "./target/__test/archive_test/unzip/outside.txt";
+///         file::write_text_file(outside_path, "outside").unwrap();
+///         std::fs::remove_file("./target/__test/archive_test/unzip/dest/file.txt").unwrap();
+///         std::os::unix::fs::symlink("../outside.txt", "./target/__test/archive_test/unzip/dest/file.txt").unwrap();
+///
+///         archive::unzip(
+///             "./target/__test/archive_test/unzip/out.zip",
+///             "./target/__test/archive_test/unzip/dest",
+///         )
+///         .unwrap();
+///         assert_eq!(file::read_text_file(outside_path).unwrap(), "outside");
+///         assert!(!std::path::Path::new("./target/__test/archive_test/unzip/dest/file.txt").is_symlink());
+///
+///         // links are checked again once all entries are extracted, so entry order does not matter
+///         let order_path = "./target/__test/archive_test/unzip/order.zip";
+///         let mut writer = zip::ZipWriter::new(std::fs::File::create(order_path).unwrap());
+///         let options = zip::write::SimpleFileOptions::default();
+///         writer.add_symlink("x", "d/..", options).unwrap();
+///         writer.add_symlink("d", ".", options).unwrap();
+///         writer.finish().unwrap();
+///
+///         let _ = std::fs::remove_dir_all("./target/__test/archive_test/unzip/order");
+///         let result = archive::unzip(order_path, "./target/__test/archive_test/unzip/order");
+///         assert!(result.is_err());
+///         assert!(!std::path::Path::new("./target/__test/archive_test/unzip/order/x").is_symlink());
+///
+///         // including when a later entry fails
+///         let failing_path = "./target/__test/archive_test/unzip/failing.zip";
+///         let mut writer = zip::ZipWriter::new(std::fs::File::create(failing_path).unwrap());
+///         writer.add_symlink("x", "d/..", options).unwrap();
+///         writer.add_symlink("d", ".", options).unwrap();
+///         writer.start_file("../escape.txt", options).unwrap();
+///         writer.finish().unwrap();
+///
+///         let _ = std::fs::remove_dir_all("./target/__test/archive_test/unzip/failing");
+///         let result = archive::unzip(failing_path, "./target/__test/archive_test/unzip/failing");
+///         assert!(result.is_err());
+///         assert!(!std::path::Path::new("./target/__test/archive_test/unzip/failing/x").is_symlink());
+///     }
+/// }
+/// ```
+pub fn unzip<S: AsPath + ?Sized, T: AsPath + ?Sized>(zip_path: &S, dest_dir: &T) -> FsIOResult<()> {
+    extract_zip(
+        zip_path.as_path(),
+        dest_dir.as_path(),
+        &ExtractOptions::default(),
+    )
+}
+
+/// 按解压选项将zip文件解压到目录
+pub(crate) fn extract_zip(
+    source_path: &Path,
+    target_path: &Path,
+    options: &ExtractOptions,
+) -> FsIOResult<()> {
+    let file = match File::open(source_path) {
+        Ok(file) => file,
+        Err(error) => return Err(FsIOError::io(Operation::Read, &source_path, error)),
+    };
+
+    let mut archive = match ZipArchive::new(file) {
+        Ok(archive) => archive,
+        Err(error) => {
+            return Err(FsIOError::io(
+                Operation::ReadArchive,
+                &source_path,
+                io::Error::from(error),
+            ))
+        }
+    };
+
+    directory::create(&target_path)?;
+    let canonical_target = canonical_dest(target_path)?;
+
+    // links created before a failing entry are checked as well
+    let mut links = vec![];
+    let result = unpack_entries(
+        &mut archive,
+        source_path,
+        target_path,
+        &canonical_target,
+        options,
+        &mut links,
+    );
+    let verified = verify_links(&canonical_target, &links);
+    result.and(verified)
+}
+
+/// 解压所有条目, 创建的符号链接(相对于解压目录的路径)保存到 `links`
+fn unpack_entries(
+    archive: &mut ZipArchive<File>,
+    source_path: &Path,
+    target_path: &Path,
+    canonical_target: &Path,
+    options: &ExtractOptions,
+    links: &mut Vec<PathBuf>,
+) -> FsIOResult<()> {
+    for index in 0..archive.len() {
+        let mut entry = match archive.by_index(index) {
+            Ok(entry) => entry,
+            Err(error) => {
+                return Err(FsIOError::io(
+                    Operation::ReadArchive,
+                    &source_path,
+                    io::Error::from(error),
+                ))
+            }
+        };
+
+        let name = match entry.enclosed_name() {
+            Some(name) => name,
+            None => {
+                return Err(FsIOError::io(
+                    Operation::Extract,
+                    entry.name(),
+                    io::Error::new(io::ErrorKind::InvalidData, "unsafe path in archive"),
+                ))
+            }
+        };
+        let is_dir = entry.is_dir();
+        let modified = entry.last_modified().and_then(|value| {
+            system_time(
+                value.year(),
+                value.month(),
+                value.day(),
+                value.hour(),
+                value.minute(),
+                value.second(),
+            )
+        });
+
+        let (relative, output_path) =
+            match options.plan(target_path, &name, is_dir, entry.size(), modified)? {
+                Some(value) => value,
+                None => continue,
+            };
+
+        if is_dir {
+            if !output_path.is_dir() {
+                directory::create(&output_path)?;
+                options.emit(ProgressEvent::DirCreated(output_path));
+            }
+            continue;
+        }
+
+        directory::create_parent(&output_path)?;
+
+        if entry.is_symlink() {
+            let mut target = String::new();
+            if let Err(error) = entry.read_to_string(&mut target) {
+                return Err(FsIOError::io(Operation::ReadArchive, &source_path, error));
+            }
+            if !is_contained_link(canonical_target, &relative, Path::new(&target)) {
+                return Err(FsIOError::io(
+                    Operation::Extract,
+                    entry.name(),
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unsafe symbolic link in archive to: {:?}", &target),
+                    ),
+                ));
+            }
+
+            if output_path.is_symlink() || output_path.is_file() {
+                if let Err(error) = remove_file(&output_path) {
+                    return Err(FsIOError::io(Operation::Delete, &output_path, error));
+                }
+            }
+            create_symlink(Path::new(&target), &output_path)?;
+            links.push(relative);
+            options.emit(ProgressEvent::BytesWritten(output_path, 0));
+            continue;
+        }
+
+        // never write through a symbolic link left at the output path
+        if symlink_metadata(&output_path).is_ok_and(|metadata| !metadata.is_dir()) {
+            if let Err(error) = remove_file(&output_path) {
+                return Err(FsIOError::io(Operation::Delete, &output_path, error));
+            }
+        }
+        let result = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&output_path)
+            .and_then(|mut output_file| io::copy(&mut entry, &mut output_file));
+        let size = match result {
+            Ok(size) => size,
+            Err(error) => return Err(FsIOError::io(Operation::Extract, &output_path, error)),
+        };
+
+        #[cfg(unix)]
+        {
+            use std::fs::{set_permissions, Permissions};
+            use std::os::unix::fs::PermissionsExt;
+
+            if let Some(mode) = entry.unix_mode() {
+                let _ = set_permissions(&output_path, Permissions::from_mode(mode & 0o7777));
+            }
+        }
+        options.emit(ProgressEvent::BytesWritten(output_path, size));
+    }
+
+    Ok(())
+}
+
+/// 将zip条目的(本地)日期时间按UTC转换为 `SystemTime`, 日期无效时返回 `None`
+fn system_time(
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+) -> Option<SystemTime> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // days since 1970-01-01 in the proleptic gregorian calendar
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (i64::from(month) + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let seconds =
+        days * 86_400 + i64::from(hour) * 3_600 + i64::from(minute) * 60 + i64::from(second);
+    u64::try_from(seconds)
+        .ok()
+        .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds))
+}
@@ -0,0 +1,290 @@
+//! # cas
+//!
+//! Content-addressed store, saving blobs under their SHA-256 digest.
+//!
+use std::fmt;
+use std::fmt::Display;
+use std::fs::{read_dir, remove_dir, remove_file, rename};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use x_hash::{DigestFmt, Sha256};
+
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::path::unique_sibling;
+use crate::result::FsIOResult;
+use crate::{directory, file};
+
+/// 内容的SHA-256摘要
+///
+/// # 示例
+///
+/// ```
+/// use x_io::cas::Digest;
+///
+/// fn main() {
+///     let digest = Digest::of(b"abc");
+///     let hex = digest.to_string();
+///     assert_eq!(hex, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+///     assert_eq!(Digest::from_hex(&hex).unwrap(), digest);
+///     assert!(Digest::from_hex("abc").is_err());
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Digest([u8; 32]);
+
+impl Digest {
+    /// 计算内容的摘要
+    ///
+    /// # 参数
+    ///
+    /// * `data` - 内容
+    pub fn of(data: &[u8]) -> Digest {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        Digest(hasher.result())
+    }
+
+    /// 解析64个字符的十六进制摘要
+    ///
+    /// # 参数
+    ///
+    /// * `hex` - 十六进制字符串
+    pub fn from_hex(hex: &str) -> FsIOResult<Digest> {
+        let invalid = || {
+            FsIOError::Parse(
+                format!("Invalid SHA-256 digest: {:?}", hex).to_string(),
+                None,
+            )
+        };
+
+        if hex.len() != 64 || !hex.bytes().all(|value| value.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let mut bytes = [0u8; 32];
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            *byte = match u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16) {
+                Ok(value) => value,
+                Err(_) => return Err(invalid()),
+            };
+        }
+
+        Ok(Digest(bytes))
+    }
+
+    /// 返回摘要字节
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl Display for Digest {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        DigestFmt(&self.0).fmt(formatter)
+    }
+}
+
+/// 内容寻址存储, 内容保存在 `<root>/<摘要前2位>/<摘要其余部分>`
+///
+/// 相同的内容只保存一次, 写入先写入临时文件再重命名, 因此不会读取到写入一半的内容.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::cas::Store;
+///
+/// fn main() {
+///     let store = Store::new("./target/__test/cas_test/store");
+///
+///     let digest = store.put(b"some content").unwrap();
+///     assert!(store.contains(&digest));
+///     assert_eq!(store.get(&digest).unwrap(), b"some content");
+///
+///     // the same content is stored once
+///     assert_eq!(store.put(b"some content").unwrap(), digest);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    /// 创建存储(根目录在第一次写入时创建)
+    ///
+    /// # 参数
+    ///
+    /// * `root` - 根目录
+    pub fn new<T: AsPath + ?Sized>(root: &T) -> Store {
+        Store {
+            root: root.as_path().to_path_buf(),
+        }
+    }
+
+    /// 返回根目录
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 返回摘要对应的文件路径
+    ///
+    /// # 参数
+    ///
+    /// * `digest` - 摘要
+    pub fn path(&self, digest: &Digest) -> PathBuf {
+        let hex = digest.to_string();
+        self.root.join(&hex[..2]).join(&hex[2..])
+    }
+
+    /// 保存内容并返回其摘要, 内容已存在时不重复写入
+    ///
+    /// # 参数
+    ///
+    /// * `data` - 内容
+    pub fn put(&self, data: &[u8]) -> FsIOResult<Digest> {
+        let digest = Digest::of(data);
+        let blob_path = self.path(&digest);
+        if blob_path.is_file() {
+            return Ok(digest);
+        }
+
+        let temp_path = unique_sibling(&blob_path, "tmp");
+        file::write_file(&temp_path, data)?;
+        if let Err(error) = rename(&temp_path, &blob_path) {
+            let _ = remove_file(&temp_path);
+            return Err(FsIOError::io(
+                Operation::Rename(blob_path),
+                &temp_path,
+                error,
+            ));
+        }
+
+        Ok(digest)
+    }
+
+    /// 读取摘要对应的内容, 不存在时返回 `FsIOError::NotFound`
+    ///
+    /// # 参数
+    ///
+    /// * `digest` - 摘要
+    pub fn get(&self, digest: &Digest) -> FsIOResult<Vec<u8>> {
+        file::read_file(&self.path(digest))
+    }
+
+    /// 返回是否保存了摘要对应的内容
+    ///
+    /// # 参数
+    ///
+    /// * `digest` - 摘要
+    pub fn contains(&self, digest: &Digest) -> bool {
+        self.path(digest).is_file()
+    }
+
+    /// 删除摘要对应的内容, 返回是否删除了内容
+    ///
+    /// # 参数
+    ///
+    /// * `digest` - 摘要
+    pub fn remove(&self, digest: &Digest) -> FsIOResult<bool> {
+        let blob_path = self.path(digest);
+        match remove_file(&blob_path) {
+            Ok(_) => Ok(true),
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+            Err(error) => Err(FsIOError::io(Operation::Delete, &blob_path, error)),
+        }
+    }
+
+    /// 返回所有已保存内容的摘要(按顺序)
+    pub fn digests(&self) -> FsIOResult<Vec<Digest>> {
+        let mut digests = vec![];
+        if !self.root.is_dir() {
+            return Ok(digests);
+        }
+
+        for fan_out_path in list_directory(&self.root)? {
+            let prefix = match fan_out_path.file_name().and_then(|name| name.to_str()) {
+                Some(prefix) if prefix.len() == 2 && fan_out_path.is_dir() => prefix.to_string(),
+                _ => continue,
+            };
+
+            for blob_path in list_directory(&fan_out_path)? {
+                let rest = match blob_path.file_name().and_then(|name| name.to_str()) {
+                    Some(rest) => rest,
+                    None => continue,
+                };
+                if let Ok(digest) = Digest::from_hex(&format!("{}{}", prefix, rest)) {
+                    digests.push(digest);
+                }
+            }
+        }
+        digests.sort();
+
+        Ok(digests)
+    }
+
+    /// 垃圾回收, 删除 `keep` 返回false的内容以及清空的子目录, 返回删除的摘要
+    ///
+    /// # 参数
+    ///
+    /// * `keep` - 返回是否保留该摘要的内容
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::cas::Store;
+    /// use x_io::directory;
+    ///
+    /// fn main() {
+    ///     directory::delete("./target/__test/cas_test/gc").unwrap();
+    ///     let store = Store::new("./target/__test/cas_test/gc");
+    ///     let live = store.put(b"live").unwrap();
+    ///     let garbage = store.put(b"garbage").unwrap();
+    ///
+    ///     let removed = store.gc(|digest| *digest == live).unwrap();
+    ///     assert_eq!(removed, vec![garbage]);
+    ///     assert_eq!(store.digests().unwrap(), vec![live]);
+    /// }
+    /// ```
+    pub fn gc<F: FnMut(&Digest) -> bool>(&self, mut keep: F) -> FsIOResult<Vec<Digest>> {
+        let mut removed = vec![];
+
+        for digest in self.digests()? {
+            if !keep(&digest) && self.remove(&digest)? {
+                removed.push(digest);
+            }
+        }
+
+        for digest in &removed {
+            if let Some(fan_out_path) = self.path(digest).parent() {
+                // only succeeds once the fan-out directory is empty
+                let _ = remove_dir(fan_out_path);
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// 删除存储的所有内容
+    pub fn clear(&self) -> FsIOResult<()> {
+        directory::delete(&self.root)
+    }
+}
+
+fn list_directory(path: &Path) -> FsIOResult<Vec<PathBuf>> {
+    let entries = match read_dir(path) {
+        Ok(entries) => entries,
+        Err(error) => return Err(FsIOError::io(Operation::ReadDirectory, &path, error)),
+    };
+
+    let mut paths = vec![];
+    for entry in entries {
+        match entry {
+            Ok(entry) => paths.push(entry.path()),
+            Err(error) => return Err(FsIOError::io(Operation::ReadDirectory, &path, error)),
+        }
+    }
+
+    Ok(paths)
+}
@@ -1,9 +1,26 @@
-use std::fs::{create_dir_all, remove_dir_all};
+use std::collections::BTreeMap;
+use std::fs::{
+    copy as copy_file, create_dir, create_dir_all, hard_link, metadata, read_dir, read_link,
+    remove_dir_all, remove_file, rename, symlink_metadata, Metadata,
+};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-use crate::error::FsIOError;
+use crate::cas::Digest;
+use crate::error::{FsIOError, Operation};
+use crate::file;
+use crate::filter::PathFilter;
+use crate::ignore::IgnoreSet;
 use crate::path::as_path::AsPath;
-use crate::path::parent_directory;
+use crate::path::{
+    create_symlink, file_id, is_descendant_of, is_same_file, join_relative, name_eq_ignore_case,
+    parent_directory, unique_sibling, FileId,
+};
+use crate::progress::ProgressEvent;
 use crate::result::FsIOResult;
+use crate::throttle::Throttle;
+use crate::walk::{DirEntryInfo, SymlinkPolicy, Walk, WalkOptions};
 
 /// 创建一个目录
 ///
@@ -13,8 +30,10 @@ use crate::result::FsIOResult;
 ///
 /// # 示例
 ///
-/// use crate::x-io::directory;
+/// ```
+/// use crate::x_io::directory;
 /// use std::path::Path;
+///
 /// fn main() {
 ///     let result = directory::create("./target/__test/directory_test/dir1/dir2");
 ///     assert!(result.is_ok());
@@ -22,18 +41,83 @@ use crate::result::FsIOResult;
 ///     let path = Path::new("./target/__test/directory_test/dir1/dir2");
 ///     assert!(path.exists());
 /// }
+/// ```
 pub fn create<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
+    create_reporting(path).map(|_| ())
+}
+
+/// `create_reporting` 和 `file::ensure_exists_reporting` 的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Created {
+    /// 新创建
+    New,
+    /// 已经存在
+    AlreadyExisted,
+}
+
+impl Created {
+    /// 返回是否是新创建的
+    pub fn is_new(&self) -> bool {
+        *self == Created::New
+    }
+}
+
+/// 创建一个目录(包括父级目录), 返回目录是新创建的还是已经存在
+///
+/// 多个调用方同时创建同一个目录时只有一个得到 `Created::New`.
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::directory;
+/// use x_io::directory::Created;
+///
+/// fn main() {
+///     directory::delete("./target/__test/directory_test/create_reporting").unwrap();
+///     let path = "./target/__test/directory_test/create_reporting/cache";
+///
+///     assert_eq!(directory::create_reporting(path).unwrap(), Created::New);
+///     assert_eq!(directory::create_reporting(path).unwrap(), Created::AlreadyExisted);
+///
+///     let path = "./target/__test/directory_test/create_reporting/shared";
+///     let handles: Vec<_> = (0..8)
+///         .map(|_| std::thread::spawn(move || directory::create_reporting(path).unwrap()))
+///         .collect();
+///     let created: Vec<Created> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+///     assert_eq!(created.iter().filter(|created| created.is_new()).count(), 1);
+/// }
+/// ```
+pub fn create_reporting<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Created> {
     let directory_path = path.as_path();
 
-    if directory_path.is_dir() && directory_path.exists() {
-        return Ok(());
+    if directory_path.is_dir() {
+        return Ok(Created::AlreadyExisted);
     }
 
-    match create_dir_all(&directory_path) {
-        Ok(_) => Ok(()),
-        Err(error) => Err(FsIOError::IOError(
-            format!("Unable to create directory: {:?}.", &directory_path).to_string(),
-            Some(error),
+    if let Some(parent) = directory_path.parent() {
+        if !parent.as_os_str().is_empty() {
+            if let Err(error) = create_dir_all(parent) {
+                return Err(FsIOError::io(Operation::CreateDirectory, &parent, error));
+            }
+        }
+    }
+
+    // only the caller whose create_dir succeeds reports the directory as new
+    match create_dir(directory_path) {
+        Ok(_) => Ok(Created::New),
+        Err(ref error)
+            if error.kind() == std::io::ErrorKind::AlreadyExists && directory_path.is_dir() =>
+        {
+            Ok(Created::AlreadyExisted)
+        }
+        Err(error) => Err(FsIOError::io(
+            Operation::CreateDirectory,
+            &directory_path,
+            error,
         )),
     }
 }
@@ -65,6 +149,163 @@ pub fn create_parent<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
     }
 }
 
+/// 使用指定的权限创建目录(包括父级目录)
+///
+/// 权限只应用于新创建的目录(包括中间目录), 已存在的目录不会修改. 目录创建时就使用该权限(`DirBuilderExt::mode`),
+/// 创建后不再修改权限, 因此与 `mkdir` 相同, 最终权限会去掉umask中的位. 其他平台上忽略 `mode`.
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+/// * `mode` - unix目录权限, 例如 `0o750`
+///
+/// # 示例
+///
+/// ```
+/// use x_io::directory;
+///
+/// fn main() {
+///     directory::delete("./target/__test/directory_test/create_with_mode").unwrap();
+///     let path = "./target/__test/directory_test/create_with_mode/releases/current";
+///     directory::create_with_mode(path, 0o750).unwrap();
+///     assert!(std::path::Path::new(path).is_dir());
+///
+///     #[cfg(unix)]
+///     {
+///         use std::os::unix::fs::PermissionsExt;
+///
+///         // never wider than the requested mode, the umask may remove more bits
+///         let mode = std::fs::metadata(path).unwrap().permissions().mode();
+///         assert_eq!(mode & 0o777 & !0o750, 0);
+///         let parent = "./target/__test/directory_test/create_with_mode/releases";
+///         let mode = std::fs::metadata(parent).unwrap().permissions().mode();
+///         assert_eq!(mode & 0o777 & !0o750, 0);
+///     }
+/// }
+/// ```
+pub fn create_with_mode<T: AsPath + ?Sized>(path: &T, mode: u32) -> FsIOResult<()> {
+    let directory_path = path.as_path();
+
+    let mut missing = vec![];
+    let mut current = Some(directory_path);
+    while let Some(value) = current {
+        if value.as_os_str().is_empty() || value.is_dir() {
+            break;
+        }
+        missing.push(value);
+        current = value.parent();
+    }
+
+    for value in missing.into_iter().rev() {
+        let result = match create_dir_with_mode(value, mode) {
+            Ok(_) => Ok(()),
+            Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists && value.is_dir() => {
+                Ok(())
+            }
+            Err(error) => Err(error),
+        };
+        if let Err(error) = result {
+            return Err(FsIOError::io(Operation::CreateDirectory, &value, error));
+        }
+    }
+    Ok(())
+}
+
+/// 使用指定的权限给提供的路径创建父级目录, 权限只应用于新创建的目录(参见 `create_with_mode`)
+///
+/// # 参数
+///
+/// * `path` - 路径
+/// * `mode` - unix目录权限, 例如 `0o750`
+///
+/// # 示例
+///
+/// ```
+/// use x_io::directory;
+///
+/// fn main() {
+///     let path = "./target/__test/directory_test/create_parent_with_mode/keys/server.key";
+///     directory::create_parent_with_mode(path, 0o700).unwrap();
+///     assert!(std::path::Path::new("./target/__test/directory_test/create_parent_with_mode/keys").is_dir());
+/// }
+/// ```
+pub fn create_parent_with_mode<T: AsPath + ?Sized>(path: &T, mode: u32) -> FsIOResult<()> {
+    match parent_directory(path) {
+        Some(directory) => create_with_mode(&directory, mode),
+        None => Ok(()),
+    }
+}
+
+#[cfg(unix)]
+fn create_dir_with_mode(path: &Path, mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    std::fs::DirBuilder::new().mode(mode).create(path)
+}
+
+#[cfg(not(unix))]
+fn create_dir_with_mode(path: &Path, _mode: u32) -> std::io::Result<()> {
+    create_dir(path)
+}
+
+/// 将目录的修改(创建, 重命名和删除的目录项)同步到磁盘
+///
+/// 崩溃后仍需保留的新建, 重命名或删除的文件需要同步其所在的目录, 同步文件本身只保证文件内容.
+/// Windows不支持打开目录同步(目录项的修改由文件系统日志保证), 只检查目录是否存在.
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+///
+/// fn main() {
+///     file::write_file_atomic("./target/__test/directory_test/sync/state.json", "{}".as_bytes()).unwrap();
+///
+///     directory::sync("./target/__test/directory_test/sync").unwrap();
+///     assert!(directory::sync("./target/__test/directory_test/sync/state.json").is_err());
+/// }
+/// ```
+pub fn sync<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
+    let directory_path = path.as_path();
+
+    match metadata(directory_path) {
+        Ok(metadata) if metadata.is_dir() => {}
+        Ok(_) => {
+            return Err(FsIOError::NotFile(
+                format!("Path: {:?} is not a directory.", &directory_path).to_string(),
+            ))
+        }
+        Err(error) => return Err(FsIOError::io(Operation::Metadata, &directory_path, error)),
+    }
+
+    match sync_entries(directory_path) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::Sync, &directory_path, error)),
+    }
+}
+
+/// 同步路径所在的父级目录(路径没有父级目录时同步当前目录)
+pub(crate) fn sync_parent(path: &Path) -> FsIOResult<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => sync(parent),
+        _ => sync("."),
+    }
+}
+
+#[cfg(unix)]
+fn sync_entries(path: &Path) -> std::io::Result<()> {
+    std::fs::File::open(path)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_entries(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
 /// 删除该目录和任何子文件目录
 ///
 /// # 参数
@@ -96,9 +337,10 @@ pub fn delete<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
         if directory_path.is_dir() {
             match remove_dir_all(directory_path) {
                 Ok(_) => Ok(()),
-                Err(error) => Err(FsIOError::IOError(
-                    format!("Unable to delete directory: {:?}", &directory_path).to_string(),
-                    Some(error),
+                Err(error) => Err(FsIOError::io(
+                    Operation::DeleteDirectory,
+                    &directory_path,
+                    error,
                 )),
             }
         } else {
@@ -109,4 +351,1836 @@ pub fn delete<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
     } else {
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// 将所有文件写入同级临时目录, 然后用该目录替换目标目录(通过原子交换),
+/// 因此使用者看到的要么是旧目录, 要么是完整的新目录
+///
+/// 新目录只包含提供的文件, 目标目录中原有的其他文件会被删除.
+/// 写入失败时目标目录保持不变.
+/// 平台或文件系统不支持原子交换时(Linux的 `renameat2` 和macOS的 `renamex_np` 之外),
+/// 先将旧目录重命名再移入新目录, 两次重命名之间目标目录短暂不存在.
+///
+/// # 参数
+///
+/// * `path` - 目标目录路径
+/// * `files` - 文件列表(相对路径, 内容), 相对路径不能包含 `..` 或者是绝对路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::{directory, file};
+///
+/// fn main() {
+///     let directory_path = "./target/__test/directory_test/apply_write_set/site";
+///     file::write_text_file("./target/__test/directory_test/apply_write_set/site/old.html", "old").unwrap();
+///
+///     let result = directory::apply_write_set(
+///         directory_path,
+///         vec![
+///             ("index.html", "index".as_bytes().to_vec()),
+///             ("css/site.css", "body {}".as_bytes().to_vec()),
+///         ],
+///     );
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file("./target/__test/directory_test/apply_write_set/site/css/site.css").unwrap();
+///     assert_eq!(text, "body {}");
+///     assert!(!std::path::Path::new("./target/__test/directory_test/apply_write_set/site/old.html").exists());
+/// }
+/// ```
+pub fn apply_write_set<T, P, I, B>(path: &T, files: I) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    P: AsPath,
+    I: IntoIterator<Item = (P, B)>,
+    B: AsRef<[u8]>,
+{
+    let directory_path = path.as_path();
+    let staging_path = unique_sibling(directory_path, "staging");
+
+    let result = write_staging(&staging_path, files);
+    if let Err(error) = result {
+        let _ = remove_dir_all(&staging_path);
+        return Err(error);
+    }
+
+    let has_old = directory_path.exists();
+    if has_old {
+        match file::rename_native(&staging_path, directory_path, file::NativeRename::Exchange) {
+            // the staging path now holds the old tree
+            Ok(true) => return delete(&staging_path),
+            Ok(false) => {}
+            Err(error) => {
+                let _ = remove_dir_all(&staging_path);
+                return Err(FsIOError::io(
+                    Operation::Rename(directory_path.to_path_buf()),
+                    &staging_path,
+                    error,
+                ));
+            }
+        }
+    }
+
+    let old_path = unique_sibling(directory_path, "old");
+    if has_old {
+        if let Err(error) = rename(directory_path, &old_path) {
+            let _ = remove_dir_all(&staging_path);
+            return Err(FsIOError::io(
+                Operation::Rename(old_path.clone()),
+                &directory_path,
+                error,
+            ));
+        }
+    }
+
+    if let Err(error) = rename(&staging_path, directory_path) {
+        if has_old {
+            let _ = rename(&old_path, directory_path);
+        }
+        let _ = remove_dir_all(&staging_path);
+        return Err(FsIOError::io(
+            Operation::Rename(directory_path.to_path_buf()),
+            &staging_path,
+            error,
+        ));
+    }
+
+    if has_old {
+        delete(&old_path)?;
+    }
+
+    Ok(())
+}
+
+fn write_staging<P, I, B>(staging_path: &Path, files: I) -> FsIOResult<()>
+where
+    P: AsPath,
+    I: IntoIterator<Item = (P, B)>,
+    B: AsRef<[u8]>,
+{
+    create(&staging_path)?;
+
+    for (relative_path, data) in files {
+        let file_path = join_relative(staging_path, relative_path.as_path())?;
+        file::write_file(&file_path, data.as_ref())?;
+    }
+
+    Ok(())
+}
+
+/// 在目录中查找名称相同(忽略大小写)的条目, 返回磁盘上实际大小写的路径, 没有找到时返回None
+///
+/// 存在完全相同的名称时优先返回, 否则返回按字典序的第一个匹配条目.
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+/// * `name` - 条目名称
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/find_case_insensitive/Config.TOML", "").unwrap();
+///
+///     let result = directory::find_case_insensitive("./target/__test/directory_test/find_case_insensitive", "config.toml");
+///     let found = result.unwrap().unwrap();
+///     assert_eq!(found.file_name().unwrap(), "Config.TOML");
+/// }
+/// ```
+pub fn find_case_insensitive<T: AsPath + ?Sized>(
+    path: &T,
+    name: &str,
+) -> FsIOResult<Option<PathBuf>> {
+    let directory_path = path.as_path();
+
+    let entries = match read_dir(directory_path) {
+        Ok(entries) => entries,
+        Err(error) => {
+            return Err(FsIOError::io(
+                Operation::ReadDirectory,
+                &directory_path,
+                error,
+            ))
+        }
+    };
+
+    let mut matches = vec![];
+    for entry in entries {
+        match entry {
+            Ok(entry) => {
+                // the file system may itself be case insensitive, so the casing is taken from the entry
+                if entry.file_name() == name {
+                    return Ok(Some(entry.path()));
+                }
+                if name_eq_ignore_case(&entry.file_name(), name.as_ref()) {
+                    matches.push(entry.path());
+                }
+            }
+            Err(error) => {
+                return Err(FsIOError::io(
+                    Operation::ReadDirectory,
+                    &directory_path,
+                    error,
+                ))
+            }
+        }
+    }
+    matches.sort();
+
+    Ok(matches.into_iter().next())
+}
+
+/// 递归复制选项
+#[derive(Debug, Clone)]
+pub struct CopyOptions {
+    /// 是否覆盖目标目录中已存在的文件, 不覆盖时跳过该文件
+    pub overwrite: bool,
+    /// 单个文件或目录失败时是否继续复制其余文件, 继续时所有失败在结束后以 `FsIOError::Multiple` 返回
+    pub keep_going: bool,
+    /// 忽略规则(路径相对于源目录), 被忽略的文件和目录不会被复制
+    pub ignore: Option<IgnoreSet>,
+    /// 路径过滤器(路径相对于源目录), 被排除的文件和目录不会被复制
+    pub filter: Option<PathFilter>,
+    /// 符号链接的处理方式, 默认复制其指向的内容(`SymlinkPolicy::Follow`)
+    pub symlinks: SymlinkPolicy,
+    /// 是否使用硬链接代替复制文件内容, 跨文件系统或文件系统不支持硬链接时复制文件, 其他错误按失败处理
+    pub hard_link: bool,
+    /// 带宽限制, 所有文件共用同一个限制(例如限制备份任务的磁盘或网络带宽)
+    pub throttle: Option<Throttle>,
+    /// 是否使用直接IO复制文件(不经过页缓存, 参见 `file::supports_direct_io`), 文件系统不支持时使用普通IO
+    pub direct_io: bool,
+    /// 是否复制文件和目录的扩展属性(Linux/macOS, 需要 `xattr` 功能)
+    #[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+    pub preserve_xattrs: bool,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        CopyOptions {
+            overwrite: true,
+            keep_going: false,
+            ignore: None,
+            filter: None,
+            symlinks: SymlinkPolicy::Follow,
+            hard_link: false,
+            throttle: None,
+            direct_io: false,
+            #[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+            preserve_xattrs: false,
+        }
+    }
+}
+
+/// 递归复制的统计结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyReport {
+    /// 复制的文件数量
+    pub files: u64,
+    /// 创建的目录数量(包括目标目录)
+    pub directories: u64,
+    /// 复制的字节数
+    pub bytes: u64,
+    /// 因已存在而跳过的文件数量
+    pub skipped: u64,
+    /// 重新创建的符号链接数量(`SymlinkPolicy::Preserve`)
+    pub symlinks: u64,
+    /// 创建的硬链接数量(`CopyOptions::hard_link`, 不计入 `files`)
+    pub linked: u64,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CopyReport {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("CopyReport", 6)?;
+        state.serialize_field("files", &self.files)?;
+        state.serialize_field("directories", &self.directories)?;
+        state.serialize_field("bytes", &self.bytes)?;
+        state.serialize_field("skipped", &self.skipped)?;
+        state.serialize_field("symlinks", &self.symlinks)?;
+        state.serialize_field("linked", &self.linked)?;
+        state.end()
+    }
+}
+
+/// 将目录(包括所有子文件目录)递归复制到目标目录, 返回复制的统计结果
+///
+/// 符号链接按 `CopyOptions::symlinks` 处理(默认复制其指向的内容, 链接形成循环时返回 `FsIOError::SymlinkLoop`).
+/// 目标目录不能位于源目录之内.
+/// 启用 `uring` 功能时(Linux)同一目录中的文件使用io_uring批量复制, 内核不支持时逐个复制.
+///
+/// # 参数
+///
+/// * `src` - 源目录
+/// * `dst` - 目标目录
+/// * `options` - 复制选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+/// use x_io::directory::CopyOptions;
+/// use x_io::ignore::IgnoreSet;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/copy/src/file.txt", "some content").unwrap();
+///     file::write_text_file("./target/__test/directory_test/copy/src/dir1/file.txt", "more").unwrap();
+///     directory::delete("./target/__test/directory_test/copy/dst").unwrap();
+///
+///     let report = directory::copy(
+///         "./target/__test/directory_test/copy/src",
+///         "./target/__test/directory_test/copy/dst",
+///         CopyOptions::default(),
+///     )
+///     .unwrap();
+///     assert_eq!(report.files, 2);
+///     assert_eq!(report.directories, 2);
+///     assert_eq!(report.bytes, 16);
+///
+///     let text = file::read_text_file("./target/__test/directory_test/copy/dst/dir1/file.txt").unwrap();
+///     assert_eq!(text, "more");
+///
+///     let options = CopyOptions { overwrite: false, ..CopyOptions::default() };
+///     let report = directory::copy(
+///         "./target/__test/directory_test/copy/src",
+///         "./target/__test/directory_test/copy/dst",
+///         options,
+///     )
+///     .unwrap();
+///     assert_eq!(report.skipped, 2);
+///
+///     let options = CopyOptions {
+///         ignore: Some(IgnoreSet::from_patterns(["dir1/"]).unwrap()),
+///         ..CopyOptions::default()
+///     };
+///     directory::delete("./target/__test/directory_test/copy/filtered").unwrap();
+///     let report = directory::copy(
+///         "./target/__test/directory_test/copy/src",
+///         "./target/__test/directory_test/copy/filtered",
+///         options,
+///     )
+///     .unwrap();
+///     assert_eq!(report.files, 1);
+///
+///     // large backups can bypass the page cache
+///     let options = CopyOptions { direct_io: true, ..CopyOptions::default() };
+///     directory::delete("./target/__test/directory_test/copy/direct").unwrap();
+///     let report = directory::copy(
+///         "./target/__test/directory_test/copy/src",
+///         "./target/__test/directory_test/copy/direct",
+///         options,
+///     )
+///     .unwrap();
+///     assert_eq!(report.bytes, 16);
+///     let text = file::read_text_file("./target/__test/directory_test/copy/direct/file.txt").unwrap();
+///     assert_eq!(text, "some content");
+/// }
+/// ```
+///
+/// `keep_going` 时收集所有失败:
+///
+/// ```
+/// use x_io::{directory, file};
+/// use x_io::directory::CopyOptions;
+/// use x_io::error::FsIOError;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/copy_keep_going/src/a.txt", "a").unwrap();
+///     file::write_text_file("./target/__test/directory_test/copy_keep_going/src/b.txt", "b").unwrap();
+///     directory::delete("./target/__test/directory_test/copy_keep_going/dst").unwrap();
+///     // a directory in the way of a file makes that single copy fail
+///     directory::create("./target/__test/directory_test/copy_keep_going/dst/a.txt").unwrap();
+///
+///     let options = CopyOptions { keep_going: true, ..CopyOptions::default() };
+///     let result = directory::copy(
+///         "./target/__test/directory_test/copy_keep_going/src",
+///         "./target/__test/directory_test/copy_keep_going/dst",
+///         options,
+///     );
+///
+///     match result {
+///         Err(FsIOError::Multiple(errors)) => {
+///             assert_eq!(errors.len(), 1);
+///             assert!(errors[0].0.ends_with("a.txt"));
+///         }
+///         _ => panic!("expected multiple errors"),
+///     }
+///     let text = file::read_text_file("./target/__test/directory_test/copy_keep_going/dst/b.txt").unwrap();
+///     assert_eq!(text, "b");
+/// }
+/// ```
+///
+/// 符号链接的处理方式:
+///
+/// ```
+/// use x_io::{directory, file};
+/// use x_io::directory::CopyOptions;
+/// use x_io::error::FsIOError;
+/// use x_io::walk::SymlinkPolicy;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/copy_symlinks/src/v1.2.3/app.txt", "app").unwrap();
+///
+///     #[cfg(unix)]
+///     {
+///         let link_path = "./target/__test/directory_test/copy_symlinks/src/current";
+///         let _ = std::fs::remove_file(link_path);
+///         std::os::unix::fs::symlink("v1.2.3", link_path).unwrap();
+///
+///         directory::delete("./target/__test/directory_test/copy_symlinks/dst").unwrap();
+///         let options = CopyOptions { symlinks: SymlinkPolicy::Preserve, ..CopyOptions::default() };
+///         let report = directory::copy(
+///             "./target/__test/directory_test/copy_symlinks/src",
+///             "./target/__test/directory_test/copy_symlinks/dst",
+///             options,
+///         )
+///         .unwrap();
+///         assert_eq!(report.symlinks, 1);
+///         let target = std::fs::read_link("./target/__test/directory_test/copy_symlinks/dst/current").unwrap();
+///         assert_eq!(target, std::path::PathBuf::from("v1.2.3"));
+///
+///         // following a link back to an ancestor is reported instead of recursing forever
+///         let loop_path = "./target/__test/directory_test/copy_symlinks/src/v1.2.3/root";
+///         let _ = std::fs::remove_file(loop_path);
+///         std::os::unix::fs::symlink("..", loop_path).unwrap();
+///         directory::delete("./target/__test/directory_test/copy_symlinks/followed").unwrap();
+///         let result = directory::copy(
+///             "./target/__test/directory_test/copy_symlinks/src",
+///             "./target/__test/directory_test/copy_symlinks/followed",
+///             CopyOptions::default(),
+///         );
+///         assert!(matches!(result, Err(FsIOError::SymlinkLoop(_))));
+///         std::fs::remove_file(loop_path).unwrap();
+///     }
+/// }
+/// ```
+pub fn copy<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    src: &S,
+    dst: &T,
+    options: CopyOptions,
+) -> FsIOResult<CopyReport> {
+    copy_with_progress(src, dst, options, |_: &ProgressEvent| {})
+}
+
+/// 与 `copy` 相同, 每创建一个目录或复制一个文件后调用 `on_event`
+/// (`DirCreated`, 以及 `FileCopied` 和 `BytesWritten`)
+///
+/// # 参数
+///
+/// * `src` - 源目录
+/// * `dst` - 目标目录
+/// * `options` - 复制选项
+/// * `on_event` - 进度事件回调
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+/// use x_io::directory::CopyOptions;
+/// use x_io::progress::ProgressEvent;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/copy_with_progress/src/file.txt", "some content").unwrap();
+///     directory::delete("./target/__test/directory_test/copy_with_progress/dst").unwrap();
+///
+///     let mut copied_bytes = 0;
+///     let result = directory::copy_with_progress(
+///         "./target/__test/directory_test/copy_with_progress/src",
+///         "./target/__test/directory_test/copy_with_progress/dst",
+///         CopyOptions::default(),
+///         |event| {
+///             if let ProgressEvent::BytesWritten(_, bytes) = event {
+///                 copied_bytes += bytes;
+///             }
+///         },
+///     );
+///     assert!(result.is_ok());
+///     assert_eq!(copied_bytes, 12);
+/// }
+/// ```
+pub fn copy_with_progress<S, T, F>(
+    src: &S,
+    dst: &T,
+    options: CopyOptions,
+    mut on_event: F,
+) -> FsIOResult<CopyReport>
+where
+    S: AsPath + ?Sized,
+    T: AsPath + ?Sized,
+    F: FnMut(&ProgressEvent),
+{
+    let src_path = src.as_path();
+    let dst_path = dst.as_path();
+
+    if src_path.exists() && !src_path.is_dir() {
+        return Err(FsIOError::NotFile(
+            format!("Path: {:?} is not a directory.", &src_path).to_string(),
+        ));
+    }
+    if is_descendant_of(dst_path, src_path) || is_same_file(src_path, dst_path).unwrap_or(false) {
+        return Err(FsIOError::io(
+            Operation::Copy(dst_path.to_path_buf()),
+            &src_path,
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "destination is inside the source directory",
+            ),
+        ));
+    }
+
+    let mut state = CopyState {
+        src_root: src_path,
+        options: &options,
+        on_event: &mut on_event,
+        report: CopyReport::default(),
+        failures: vec![],
+        ancestors: vec![],
+        throttle: options.throttle.clone(),
+    };
+    copy_recursive(src_path, dst_path, &mut state)?;
+
+    if state.failures.is_empty() {
+        Ok(state.report)
+    } else {
+        Err(FsIOError::Multiple(state.failures))
+    }
+}
+
+/// 递归复制的状态
+struct CopyState<'a> {
+    src_root: &'a Path,
+    options: &'a CopyOptions,
+    on_event: &'a mut dyn FnMut(&ProgressEvent),
+    report: CopyReport,
+    failures: Vec<(PathBuf, FsIOError)>,
+    /// 正在复制的源目录及其祖先目录的文件标识(跟随符号链接时用于检测循环)
+    ancestors: Vec<FileId>,
+    /// 带宽限制的状态(在所有文件之间共享)
+    throttle: Option<Throttle>,
+}
+
+impl CopyState<'_> {
+    /// keep_going时记录失败并继续, 否则返回错误
+    fn record_failure(&mut self, path: &Path, error: FsIOError) -> FsIOResult<()> {
+        if self.options.keep_going {
+            self.failures.push((path.to_path_buf(), error));
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    /// 返回源目录中的路径是否被忽略或被过滤器排除
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = match path.strip_prefix(self.src_root) {
+            Ok(relative) => relative,
+            Err(_) => return false,
+        };
+
+        if let Some(ref ignore) = self.options.ignore {
+            if ignore.is_ignored_with(relative, is_dir) {
+                return true;
+            }
+        }
+        match self.options.filter {
+            Some(ref filter) => !filter.matches_entry(relative, is_dir, path),
+            None => false,
+        }
+    }
+}
+
+fn copy_recursive(src: &Path, dst: &Path, state: &mut CopyState) -> FsIOResult<()> {
+    if state.options.symlinks != SymlinkPolicy::Follow {
+        return copy_directory(src, dst, state);
+    }
+
+    let id = match file_id(src) {
+        Ok(id) => id,
+        Err(error) => return state.record_failure(src, error),
+    };
+    if state.ancestors.contains(&id) {
+        let error = FsIOError::SymlinkLoop(
+            format!("Symbolic link cycle detected at: {:?}", src).to_string(),
+        );
+        return state.record_failure(src, error);
+    }
+
+    state.ancestors.push(id);
+    let result = copy_directory(src, dst, state);
+    state.ancestors.pop();
+    result
+}
+
+fn copy_directory(src: &Path, dst: &Path, state: &mut CopyState) -> FsIOResult<()> {
+    let mut entries = vec![];
+    match read_dir(src) {
+        Ok(read_entries) => {
+            for entry in read_entries {
+                match entry {
+                    Ok(entry) => entries.push(entry.path()),
+                    Err(error) => {
+                        let error = FsIOError::io(Operation::ReadDirectory, &src, error);
+                        return state.record_failure(src, error);
+                    }
+                }
+            }
+        }
+        Err(error) => {
+            let error = FsIOError::io(Operation::ReadDirectory, &src, error);
+            return state.record_failure(src, error);
+        }
+    }
+    entries.sort();
+
+    if !dst.is_dir() {
+        if let Err(error) = create_dir_all(dst) {
+            let error = FsIOError::io(Operation::CreateDirectory, &dst, error);
+            return state.record_failure(dst, error);
+        }
+        state.report.directories += 1;
+        (state.on_event)(&ProgressEvent::DirCreated(dst.to_path_buf()));
+    }
+
+    #[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+    if state.options.preserve_xattrs {
+        if let Err(error) = file::copy_xattrs(src, dst) {
+            state.record_failure(src, error)?;
+        }
+    }
+
+    // plain file copies are collected and copied in batches (io_uring with the `uring` feature)
+    let mut pending = vec![];
+    for entry_path in entries {
+        let target_path = match entry_path.file_name() {
+            Some(name) => dst.join(name),
+            None => continue,
+        };
+
+        let is_symlink = match symlink_metadata(&entry_path) {
+            Ok(metadata) => metadata.file_type().is_symlink(),
+            Err(error) => {
+                let error = FsIOError::io(Operation::Metadata, &entry_path, error);
+                state.record_failure(&entry_path, error)?;
+                continue;
+            }
+        };
+        if is_symlink && state.options.symlinks == SymlinkPolicy::Skip {
+            continue;
+        }
+
+        let is_dir = !(is_symlink && state.options.symlinks == SymlinkPolicy::Preserve)
+            && entry_path.is_dir();
+        if state.is_ignored(&entry_path, is_dir) {
+            continue;
+        }
+
+        if is_symlink && state.options.symlinks == SymlinkPolicy::Preserve {
+            copy_pending(&mut pending, state)?;
+            copy_symlink(&entry_path, &target_path, state)?;
+        } else if is_dir {
+            copy_pending(&mut pending, state)?;
+            copy_recursive(&entry_path, &target_path, state)?;
+        } else if target_path.exists() && !state.options.overwrite {
+            state.report.skipped += 1;
+        } else if !detach_target(&entry_path, &target_path, state)?
+            || (state.options.hard_link && link_file(&entry_path, &target_path, state)?)
+        {
+            continue;
+        } else if state.options.direct_io {
+            let result = file::copy_with_direct(&entry_path, &target_path, state.throttle.as_mut());
+            copied(entry_path, target_path, result, state)?;
+        } else if let Some(ref mut throttle) = state.throttle {
+            let result = file::copy_with_throttle(&entry_path, &target_path, throttle);
+            copied(entry_path, target_path, result, state)?;
+        } else {
+            pending.push((entry_path, target_path));
+        }
+    }
+    copy_pending(&mut pending, state)?;
+
+    Ok(())
+}
+
+/// 复制等待中的文件
+fn copy_pending(pending: &mut Vec<(PathBuf, PathBuf)>, state: &mut CopyState) -> FsIOResult<()> {
+    // failed batched copies are retried to report the same errors
+    let mut batched = copy_batch(pending).into_iter();
+    for (src, dst) in pending.drain(..) {
+        let result = match batched.next() {
+            Some(Ok(bytes)) => Ok(bytes),
+            _ => copy_file(&src, &dst),
+        };
+        copied(src, dst, result, state)?;
+    }
+    Ok(())
+}
+
+/// 使用io_uring批量复制文件(`uring` 功能), 返回空列表时逐个复制
+#[cfg(all(feature = "uring", target_os = "linux"))]
+fn copy_batch(files: &[(PathBuf, PathBuf)]) -> Vec<std::io::Result<u64>> {
+    if files.len() < 2 {
+        return vec![];
+    }
+    crate::uring::copy_files(files).unwrap_or_default()
+}
+
+/// 使用io_uring批量复制文件(`uring` 功能), 返回空列表时逐个复制
+#[cfg(not(all(feature = "uring", target_os = "linux")))]
+fn copy_batch(_files: &[(PathBuf, PathBuf)]) -> Vec<std::io::Result<u64>> {
+    vec![]
+}
+
+/// 记录复制一个文件的结果
+fn copied(
+    entry_path: PathBuf,
+    target_path: PathBuf,
+    result: std::io::Result<u64>,
+    state: &mut CopyState,
+) -> FsIOResult<()> {
+    match result {
+        Ok(bytes) => {
+            state.report.files += 1;
+            state.report.bytes += bytes;
+            (state.on_event)(&ProgressEvent::FileCopied(
+                entry_path.clone(),
+                target_path.clone(),
+            ));
+            #[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+            if state.options.preserve_xattrs {
+                if let Err(error) = file::copy_xattrs(&entry_path, &target_path) {
+                    state.record_failure(&entry_path, error)?;
+                }
+            }
+            (state.on_event)(&ProgressEvent::BytesWritten(target_path, bytes));
+        }
+        Err(error) => {
+            let error = FsIOError::io(Operation::Copy(target_path.clone()), &entry_path, error);
+            state.record_failure(&entry_path, error)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 目标文件是源文件的硬链接时先删除目标文件(复制时截断目标会清空源文件), 删除失败时记录错误并返回 `false`
+fn detach_target(src: &Path, dst: &Path, state: &mut CopyState) -> FsIOResult<bool> {
+    if !is_same_file(src, dst).unwrap_or(false) {
+        return Ok(true);
+    }
+
+    match remove_file(dst) {
+        Ok(_) => Ok(true),
+        Err(error) => {
+            let error = FsIOError::io(Operation::Delete, &dst, error);
+            state.record_failure(src, error)?;
+            Ok(false)
+        }
+    }
+}
+
+/// 在目标位置创建源文件的硬链接(先删除已存在的目标文件), 返回 `false` 时由调用方复制文件
+///
+/// 只有跨文件系统或文件系统不支持硬链接时才改为复制, 其他错误按失败处理.
+fn link_file(src: &Path, dst: &Path, state: &mut CopyState) -> FsIOResult<bool> {
+    if symlink_metadata(dst).is_ok() {
+        // copying onto an existing hard link of the source would truncate the source
+        if let Err(error) = remove_file(dst) {
+            let error = FsIOError::io(Operation::Delete, &dst, error);
+            state.record_failure(src, error)?;
+            return Ok(true);
+        }
+    }
+
+    match hard_link(src, dst) {
+        Ok(_) => {
+            state.report.linked += 1;
+            (state.on_event)(&ProgressEvent::FileCopied(
+                src.to_path_buf(),
+                dst.to_path_buf(),
+            ));
+            Ok(true)
+        }
+        Err(ref error) if is_link_unsupported(error) => Ok(false),
+        Err(error) => {
+            let error = FsIOError::io(Operation::Copy(dst.to_path_buf()), &src, error);
+            state.record_failure(src, error)?;
+            Ok(true)
+        }
+    }
+}
+
+/// 返回硬链接错误是否表示无法在两个路径之间创建硬链接(跨文件系统或文件系统不支持)
+#[cfg(unix)]
+fn is_link_unsupported(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::Unsupported
+        || matches!(
+            error.raw_os_error(),
+            Some(libc::EXDEV) | Some(libc::EPERM) | Some(libc::EOPNOTSUPP)
+        )
+}
+
+/// 返回硬链接错误是否表示无法在两个路径之间创建硬链接(跨文件系统或文件系统不支持)
+#[cfg(windows)]
+fn is_link_unsupported(error: &io::Error) -> bool {
+    use windows_sys::Win32::Foundation::{
+        ERROR_INVALID_FUNCTION, ERROR_NOT_SAME_DEVICE, ERROR_NOT_SUPPORTED,
+    };
+
+    error.kind() == io::ErrorKind::Unsupported
+        || matches!(
+            error.raw_os_error().map(|code| code as u32),
+            Some(ERROR_NOT_SAME_DEVICE) | Some(ERROR_INVALID_FUNCTION) | Some(ERROR_NOT_SUPPORTED)
+        )
+}
+
+#[cfg(not(any(unix, windows)))]
+fn is_link_unsupported(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::Unsupported
+}
+
+/// 在目标位置重新创建符号链接(保留链接内容, 相对路径保持相对)
+fn copy_symlink(src: &Path, dst: &Path, state: &mut CopyState) -> FsIOResult<()> {
+    let target = match read_link(src) {
+        Ok(target) => target,
+        Err(error) => {
+            let error = FsIOError::io(Operation::ReadLink, &src, error);
+            return state.record_failure(src, error);
+        }
+    };
+
+    if symlink_metadata(dst).is_ok() {
+        if !state.options.overwrite {
+            state.report.skipped += 1;
+            return Ok(());
+        }
+        let result = if dst.is_dir() && !dst.is_symlink() {
+            remove_dir_all(dst)
+        } else {
+            remove_file(dst)
+        };
+        if let Err(error) = result {
+            let error = FsIOError::io(Operation::Delete, &dst, error);
+            return state.record_failure(src, error);
+        }
+    }
+
+    match create_symlink(&target, dst) {
+        Ok(_) => {
+            state.report.symlinks += 1;
+            (state.on_event)(&ProgressEvent::FileCopied(
+                src.to_path_buf(),
+                dst.to_path_buf(),
+            ));
+            Ok(())
+        }
+        Err(error) => state.record_failure(src, error),
+    }
+}
+
+/// 在目标目录中重新创建源目录的结构, 并使用硬链接代替复制文件内容, 返回统计结果
+///
+/// 无法创建硬链接(例如跨文件系统或文件系统不支持)的文件会被复制. 符号链接会被重新创建(`SymlinkPolicy::Preserve`),
+/// 其他选项参见 `copy` 和 `CopyOptions::hard_link`.
+///
+/// # 参数
+///
+/// * `src` - 源目录
+/// * `dst` - 目标目录
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file, path};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/link_tree/store/pkg/index.js", "module.exports = 1;").unwrap();
+///     file::write_text_file("./target/__test/directory_test/link_tree/store/pkg/lib/util.js", "").unwrap();
+///     directory::delete("./target/__test/directory_test/link_tree/node_modules").unwrap();
+///
+///     let report = directory::link_tree(
+///         "./target/__test/directory_test/link_tree/store/pkg",
+///         "./target/__test/directory_test/link_tree/node_modules/pkg",
+///     )
+///     .unwrap();
+///     assert_eq!(report.linked + report.files, 2);
+///
+///     assert!(path::is_same_file(
+///         "./target/__test/directory_test/link_tree/store/pkg/index.js",
+///         "./target/__test/directory_test/link_tree/node_modules/pkg/index.js",
+///     )
+///     .unwrap());
+///
+///     // linking again replaces the existing links without touching the source
+///     directory::link_tree(
+///         "./target/__test/directory_test/link_tree/store/pkg",
+///         "./target/__test/directory_test/link_tree/node_modules/pkg",
+///     )
+///     .unwrap();
+///     let text = file::read_text_file("./target/__test/directory_test/link_tree/store/pkg/index.js").unwrap();
+///     assert_eq!(text, "module.exports = 1;");
+///
+///     // copying over the links replaces them with independent copies
+///     directory::copy(
+///         "./target/__test/directory_test/link_tree/store/pkg",
+///         "./target/__test/directory_test/link_tree/node_modules/pkg",
+///         directory::CopyOptions::default(),
+///     )
+///     .unwrap();
+///     let text = file::read_text_file("./target/__test/directory_test/link_tree/store/pkg/index.js").unwrap();
+///     assert_eq!(text, "module.exports = 1;");
+///     assert!(!path::is_same_file(
+///         "./target/__test/directory_test/link_tree/store/pkg/index.js",
+///         "./target/__test/directory_test/link_tree/node_modules/pkg/index.js",
+///     )
+///     .unwrap());
+/// }
+/// ```
+pub fn link_tree<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    src: &S,
+    dst: &T,
+) -> FsIOResult<CopyReport> {
+    let options = CopyOptions {
+        symlinks: SymlinkPolicy::Preserve,
+        hard_link: true,
+        ..CopyOptions::default()
+    };
+    copy(src, dst, options)
+}
+
+/// 返回目录下所有文件的总大小(字节), 不跟随符号链接, 目录不存在时返回0
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+///
+/// fn main() {
+///     directory::delete("./target/__test/directory_test/size").unwrap();
+///     file::write_text_file("./target/__test/directory_test/size/file.txt", "some content").unwrap();
+///     file::write_text_file("./target/__test/directory_test/size/dir1/file.txt", "more").unwrap();
+///
+///     assert_eq!(directory::size("./target/__test/directory_test/size").unwrap(), 16);
+///     assert_eq!(directory::size("./target/__test/directory_test/size/missing").unwrap(), 0);
+/// }
+/// ```
+pub fn size<T: AsPath + ?Sized>(path: &T) -> FsIOResult<u64> {
+    let directory_path = path.as_path();
+    if !directory_path.exists() {
+        return Ok(0);
+    }
+
+    let mut files = vec![];
+    collect_files(directory_path, &mut files)?;
+
+    Ok(files.iter().map(|(_, metadata)| metadata.len()).sum())
+}
+
+/// 超出配额时选择删除文件的顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EvictionPolicy {
+    /// 先删除修改时间最早的文件
+    OldestFirst,
+    /// 先删除访问时间最早的文件(文件系统不记录访问时间时使用修改时间)
+    LeastRecentlyUsed,
+    /// 先删除最大的文件
+    LargestFirst,
+}
+
+/// 目录下所有文件的总大小超过 `max_size` 时按策略删除文件, 直到总大小不超过 `max_size`,
+/// 返回删除的文件路径(删除文件后留下的空目录会保留)
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+/// * `max_size` - 最大总大小(字节)
+/// * `policy` - 删除顺序
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+/// use x_io::directory::EvictionPolicy;
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// fn main() {
+///     let directory_path = "./target/__test/directory_test/enforce_quota";
+///     directory::delete(directory_path).unwrap();
+///     file::write_text_file("./target/__test/directory_test/enforce_quota/old.bin", "0123456789").unwrap();
+///     thread::sleep(Duration::from_millis(20));
+///     file::write_text_file("./target/__test/directory_test/enforce_quota/new.bin", "0123456789").unwrap();
+///
+///     let removed = directory::enforce_quota(directory_path, 15, EvictionPolicy::OldestFirst).unwrap();
+///     assert_eq!(removed.len(), 1);
+///     assert!(removed[0].ends_with("old.bin"));
+///     assert_eq!(directory::size(directory_path).unwrap(), 10);
+/// }
+/// ```
+pub fn enforce_quota<T: AsPath + ?Sized>(
+    path: &T,
+    max_size: u64,
+    policy: EvictionPolicy,
+) -> FsIOResult<Vec<PathBuf>> {
+    let directory_path = path.as_path();
+    if !directory_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut files = vec![];
+    collect_files(directory_path, &mut files)?;
+
+    let mut total_size: u64 = files.iter().map(|(_, metadata)| metadata.len()).sum();
+    if total_size <= max_size {
+        return Ok(vec![]);
+    }
+
+    match policy {
+        EvictionPolicy::OldestFirst => {
+            files.sort_by_key(|(path, metadata)| (metadata.modified().ok(), path.clone()))
+        }
+        EvictionPolicy::LeastRecentlyUsed => files.sort_by_key(|(path, metadata)| {
+            let used = metadata.accessed().or_else(|_| metadata.modified()).ok();
+            (used, path.clone())
+        }),
+        EvictionPolicy::LargestFirst => files.sort_by(|(path1, metadata1), (path2, metadata2)| {
+            metadata2
+                .len()
+                .cmp(&metadata1.len())
+                .then_with(|| path1.cmp(path2))
+        }),
+    }
+
+    let mut removed = vec![];
+    for (file_path, metadata) in files {
+        if total_size <= max_size {
+            break;
+        }
+
+        if let Err(error) = remove_file(&file_path) {
+            return Err(FsIOError::io(Operation::Delete, &file_path, error));
+        }
+        total_size -= metadata.len();
+        removed.push(file_path);
+    }
+
+    Ok(removed)
+}
+
+/// 递归收集目录下的文件和元数据(不跟随符号链接)
+fn collect_files(path: &Path, files: &mut Vec<(PathBuf, Metadata)>) -> FsIOResult<()> {
+    let entries = match read_dir(path) {
+        Ok(entries) => entries,
+        Err(error) => return Err(FsIOError::io(Operation::ReadDirectory, &path, error)),
+    };
+
+    for entry in entries {
+        let entry_path = match entry {
+            Ok(entry) => entry.path(),
+            Err(error) => return Err(FsIOError::io(Operation::ReadDirectory, &path, error)),
+        };
+        let metadata = match symlink_metadata(&entry_path) {
+            Ok(metadata) => metadata,
+            Err(error) => return Err(FsIOError::io(Operation::Metadata, &entry_path, error)),
+        };
+
+        if metadata.is_dir() {
+            collect_files(&entry_path, files)?;
+        } else {
+            files.push((entry_path, metadata));
+        }
+    }
+
+    Ok(())
+}
+
+/// 递归遍历目录下指定扩展名的文本文件(按路径字典序), 读取内容并调用 `callback`,
+/// 读取失败或 `callback` 返回错误时立即返回该错误
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+/// * `extension` - 扩展名(忽略大小写, 可以包括开头的 `.`)
+/// * `callback` - 参数为文件路径和内容
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/for_each_text_file/README.md", "# readme").unwrap();
+///     file::write_text_file("./target/__test/directory_test/for_each_text_file/docs/guide.MD", "# guide").unwrap();
+///     file::write_text_file("./target/__test/directory_test/for_each_text_file/docs/notes.txt", "notes").unwrap();
+///
+///     let mut titles = vec![];
+///     let result = directory::for_each_text_file("./target/__test/directory_test/for_each_text_file", "md", |_, content| {
+///         titles.push(content.to_string());
+///         Ok(())
+///     });
+///     assert!(result.is_ok());
+///     assert_eq!(titles, vec!["# readme", "# guide"]);
+/// }
+/// ```
+pub fn for_each_text_file<T, F>(path: &T, extension: &str, mut callback: F) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    F: FnMut(&Path, &str) -> FsIOResult<()>,
+{
+    for file_path in files_with_extension(path.as_path(), extension)? {
+        let content = file::read_text_file(&file_path)?;
+        callback(&file_path, &content)?;
+    }
+
+    Ok(())
+}
+
+/// 递归遍历目录下指定扩展名的文件(按路径字典序), 读取内容并调用 `callback`,
+/// 读取失败或 `callback` 返回错误时立即返回该错误
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+/// * `extension` - 扩展名(忽略大小写, 可以包括开头的 `.`)
+/// * `callback` - 参数为文件路径和内容
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+///
+/// fn main() {
+///     file::write_file("./target/__test/directory_test/for_each_file/a.bin", &[1, 2]).unwrap();
+///     file::write_file("./target/__test/directory_test/for_each_file/b.bin", &[3]).unwrap();
+///
+///     let mut size = 0;
+///     let result = directory::for_each_file("./target/__test/directory_test/for_each_file", ".bin", |_, data| {
+///         size += data.len();
+///         Ok(())
+///     });
+///     assert!(result.is_ok());
+///     assert_eq!(size, 3);
+/// }
+/// ```
+pub fn for_each_file<T, F>(path: &T, extension: &str, mut callback: F) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    F: FnMut(&Path, &[u8]) -> FsIOResult<()>,
+{
+    for file_path in files_with_extension(path.as_path(), extension)? {
+        let data = file::read_file(&file_path)?;
+        callback(&file_path, &data)?;
+    }
+
+    Ok(())
+}
+
+/// 返回目录下指定扩展名的文件(按路径字典序)
+fn files_with_extension(path: &Path, extension: &str) -> FsIOResult<Vec<PathBuf>> {
+    let extension = extension.strip_prefix('.').unwrap_or(extension);
+
+    let mut files = vec![];
+    collect_files(path, &mut files)?;
+
+    let mut paths: Vec<PathBuf> = files
+        .into_iter()
+        .map(|(file_path, _)| file_path)
+        .filter(|file_path| {
+            file_path.extension().is_some_and(|file_extension| {
+                file_extension
+                    .to_string_lossy()
+                    .eq_ignore_ascii_case(extension)
+            })
+        })
+        .collect();
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// 递归遍历源目录的文件(按路径字典序), 将 `callback` 返回的内容写入目标目录,
+/// `callback` 返回None时跳过该文件
+///
+/// # 参数
+///
+/// * `src` - 源目录
+/// * `dst` - 目标目录
+/// * `callback` - 参数为相对于源目录的路径和文件内容, 返回相对于目标目录的输出路径(不能包含 `..` 或者是绝对路径)和输出内容
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+/// use std::path::Path;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/transform/src/index.md", "# index").unwrap();
+///     file::write_text_file("./target/__test/directory_test/transform/src/blog/post.md", "# post").unwrap();
+///     file::write_text_file("./target/__test/directory_test/transform/src/draft.txt", "draft").unwrap();
+///
+///     let result = directory::transform(
+///         "./target/__test/directory_test/transform/src",
+///         "./target/__test/directory_test/transform/dst",
+///         |relative_path, data| {
+///             if relative_path.extension()? != "md" {
+///                 return None;
+///             }
+///             let html = format!("<h1>{}</h1>", String::from_utf8_lossy(&data).trim_start_matches("# "));
+///             Some((relative_path.with_extension("html"), html.into_bytes()))
+///         },
+///     );
+///     assert!(result.is_ok());
+///
+///     let html = file::read_text_file("./target/__test/directory_test/transform/dst/blog/post.html").unwrap();
+///     assert_eq!(html, "<h1>post</h1>");
+///     assert!(!Path::new("./target/__test/directory_test/transform/dst/draft.txt").exists());
+/// }
+/// ```
+pub fn transform<S, T, F>(src: &S, dst: &T, mut callback: F) -> FsIOResult<()>
+where
+    S: AsPath + ?Sized,
+    T: AsPath + ?Sized,
+    F: FnMut(&Path, Vec<u8>) -> Option<(PathBuf, Vec<u8>)>,
+{
+    let src_path = src.as_path();
+    let dst_path = dst.as_path();
+
+    let mut files = vec![];
+    collect_files(src_path, &mut files)?;
+    let mut paths: Vec<PathBuf> = files.into_iter().map(|(file_path, _)| file_path).collect();
+    paths.sort();
+
+    for file_path in paths {
+        let relative_path = match file_path.strip_prefix(src_path) {
+            Ok(relative_path) => relative_path,
+            Err(_) => continue,
+        };
+
+        let data = file::read_file(&file_path)?;
+        if let Some((output_path, output)) = callback(relative_path, data) {
+            let target_path = join_relative(dst_path, &output_path)?;
+            file::write_file(&target_path, &output)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 目录快照选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SnapshotOptions {
+    /// 是否计算每个文件内容的SHA-256摘要(需要读取所有文件)
+    pub digests: bool,
+}
+
+/// 目录快照中的一个文件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotEntry {
+    /// 文件大小
+    pub size: u64,
+    /// 修改时间(平台不支持时为None)
+    pub modified: Option<SystemTime>,
+    /// 内容的摘要(`SnapshotOptions::digests` 为true时)
+    pub digest: Option<Digest>,
+}
+
+impl SnapshotEntry {
+    /// 返回与另一个快照中的同一文件相比是否已修改
+    ///
+    /// 两者都有摘要时比较大小和摘要(只修改了时间不算修改), 否则比较大小和修改时间.
+    ///
+    /// # 参数
+    ///
+    /// * `other` - 另一个快照中的同一文件
+    pub fn is_modified(&self, other: &SnapshotEntry) -> bool {
+        if self.size != other.size {
+            return true;
+        }
+
+        match (self.digest, other.digest) {
+            (Some(digest), Some(other_digest)) => digest != other_digest,
+            _ => self.modified != other.modified,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SnapshotEntry {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("SnapshotEntry", 3)?;
+        state.serialize_field("size", &self.size)?;
+        state.serialize_field("modified", &self.modified)?;
+        state.serialize_field("digest", &self.digest.map(|digest| digest.to_string()))?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SnapshotEntry {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{Error, MapAccess, SeqAccess, Visitor};
+
+        const FIELDS: &[&str] = &["size", "modified", "digest"];
+
+        fn parse_digest<E: Error>(hex: Option<String>) -> Result<Option<Digest>, E> {
+            match hex {
+                Some(hex) => match Digest::from_hex(&hex) {
+                    Ok(digest) => Ok(Some(digest)),
+                    Err(error) => Err(E::custom(error)),
+                },
+                None => Ok(None),
+            }
+        }
+
+        struct EntryVisitor;
+
+        impl<'de> Visitor<'de> for EntryVisitor {
+            type Value = SnapshotEntry;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a snapshot entry")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<SnapshotEntry, A::Error> {
+                let size = match seq.next_element()? {
+                    Some(size) => size,
+                    None => return Err(A::Error::invalid_length(0, &self)),
+                };
+                let modified = seq.next_element()?.unwrap_or(None);
+                let digest = seq.next_element()?.unwrap_or(None);
+
+                Ok(SnapshotEntry {
+                    size,
+                    modified,
+                    digest: parse_digest(digest)?,
+                })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<SnapshotEntry, A::Error> {
+                let mut size = None;
+                let mut modified = None;
+                let mut digest = None;
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "size" => size = Some(map.next_value()?),
+                        "modified" => modified = map.next_value()?,
+                        "digest" => digest = map.next_value()?,
+                        _ => return Err(A::Error::unknown_field(&key, FIELDS)),
+                    }
+                }
+
+                match size {
+                    Some(size) => Ok(SnapshotEntry {
+                        size,
+                        modified,
+                        digest: parse_digest(digest)?,
+                    }),
+                    None => Err(A::Error::missing_field("size")),
+                }
+            }
+        }
+
+        deserializer.deserialize_struct("SnapshotEntry", FIELDS, EntryVisitor)
+    }
+}
+
+/// 目录快照, 保存目录下所有文件的相对路径, 大小, 修改时间和可选的摘要
+///
+/// 启用 `serde` 功能时可以序列化保存(例如 `file::write_json`), 下次运行时读取并比较以检测修改.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/dir_snapshot/src/a.txt", "a").unwrap();
+///     let snapshot = directory::snapshot("./target/__test/directory_test/dir_snapshot/src").unwrap();
+///
+///     #[cfg(feature = "serde")]
+///     {
+///         let file_path = "./target/__test/directory_test/dir_snapshot/snapshot.json";
+///         file::write_json(file_path, &snapshot).unwrap();
+///         let loaded: directory::DirSnapshot = file::read_json(file_path).unwrap();
+///         assert_eq!(loaded, snapshot);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirSnapshot {
+    entries: BTreeMap<PathBuf, SnapshotEntry>,
+}
+
+impl DirSnapshot {
+    /// 返回所有文件(按相对路径排序)
+    pub fn entries(&self) -> &BTreeMap<PathBuf, SnapshotEntry> {
+        &self.entries
+    }
+
+    /// 返回相对路径对应的文件
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 相对于快照目录的路径
+    pub fn get<T: AsPath + ?Sized>(&self, path: &T) -> Option<&SnapshotEntry> {
+        self.entries.get(path.as_path())
+    }
+
+    /// 返回文件数量
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 返回是否没有文件
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 比较两个快照, 返回从当前快照到 `other` 新增, 删除和修改的文件
+    ///
+    /// # 参数
+    ///
+    /// * `other` - 较新的快照
+    pub fn diff(&self, other: &DirSnapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+
+        for (path, entry) in &self.entries {
+            match other.entries.get(path) {
+                Some(other_entry) if entry.is_modified(other_entry) => {
+                    diff.modified.push(path.clone())
+                }
+                Some(_) => {}
+                None => diff.removed.push(path.clone()),
+            }
+        }
+        for path in other.entries.keys() {
+            if !self.entries.contains_key(path) {
+                diff.added.push(path.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for DirSnapshot {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for DirSnapshot {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(DirSnapshot {
+            entries: BTreeMap::deserialize(deserializer)?,
+        })
+    }
+}
+
+/// 两个目录快照之间的差异(相对路径, 按顺序)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// 新增的文件
+    pub added: Vec<PathBuf>,
+    /// 删除的文件
+    pub removed: Vec<PathBuf>,
+    /// 修改的文件
+    pub modified: Vec<PathBuf>,
+}
+
+impl SnapshotDiff {
+    /// 返回是否没有差异
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// 创建目录快照(不计算摘要), 符号链接按链接本身记录
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     directory::delete("./target/__test/directory_test/snapshot").unwrap();
+///     file::write_text_file("./target/__test/directory_test/snapshot/a.txt", "a").unwrap();
+///     file::write_text_file("./target/__test/directory_test/snapshot/dir/b.txt", "b").unwrap();
+///
+///     let before = directory::snapshot("./target/__test/directory_test/snapshot").unwrap();
+///     assert_eq!(before.len(), 2);
+///     assert_eq!(before.get("dir/b.txt").unwrap().size, 1);
+///
+///     file::write_text_file("./target/__test/directory_test/snapshot/a.txt", "changed").unwrap();
+///     file::write_text_file("./target/__test/directory_test/snapshot/c.txt", "c").unwrap();
+///     file::delete("./target/__test/directory_test/snapshot/dir/b.txt").unwrap();
+///
+///     let after = directory::snapshot("./target/__test/directory_test/snapshot").unwrap();
+///     let diff = before.diff(&after);
+///     assert_eq!(diff.added, vec![PathBuf::from("c.txt")]);
+///     assert_eq!(diff.removed, vec![PathBuf::from("dir/b.txt")]);
+///     assert_eq!(diff.modified, vec![PathBuf::from("a.txt")]);
+/// }
+/// ```
+pub fn snapshot<T: AsPath + ?Sized>(path: &T) -> FsIOResult<DirSnapshot> {
+    snapshot_with(path, SnapshotOptions::default())
+}
+
+/// 按选项创建目录快照
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+/// * `options` - 快照选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::directory::{self, SnapshotOptions};
+/// use x_io::file;
+///
+/// fn main() {
+///     directory::delete("./target/__test/directory_test/snapshot_with").unwrap();
+///     file::write_text_file("./target/__test/directory_test/snapshot_with/a.txt", "same").unwrap();
+///
+///     let options = SnapshotOptions { digests: true };
+///     let before = directory::snapshot_with("./target/__test/directory_test/snapshot_with", options).unwrap();
+///     assert!(before.get("a.txt").unwrap().digest.is_some());
+///
+///     // rewriting identical content is not a modification when digests are compared
+///     file::write_text_file("./target/__test/directory_test/snapshot_with/a.txt", "same").unwrap();
+///     let after = directory::snapshot_with("./target/__test/directory_test/snapshot_with", options).unwrap();
+///     assert!(before.diff(&after).is_empty());
+/// }
+/// ```
+pub fn snapshot_with<T: AsPath + ?Sized>(
+    path: &T,
+    options: SnapshotOptions,
+) -> FsIOResult<DirSnapshot> {
+    let root_path = path.as_path();
+
+    let mut files = vec![];
+    collect_files(root_path, &mut files)?;
+
+    let mut entries = BTreeMap::new();
+    for (file_path, metadata) in files {
+        let relative_path = match file_path.strip_prefix(root_path) {
+            Ok(relative_path) => relative_path.to_path_buf(),
+            Err(_) => continue,
+        };
+
+        let digest = if options.digests && metadata.is_file() {
+            Some(Digest::of(&file::read_file(&file_path)?))
+        } else {
+            None
+        };
+
+        entries.insert(
+            relative_path,
+            SnapshotEntry {
+                size: metadata.len(),
+                modified: metadata.modified().ok(),
+                digest,
+            },
+        );
+    }
+
+    Ok(DirSnapshot { entries })
+}
+
+/// 返回目录下的条目路径, 按名称的字典序排序(与平台的目录读取顺序无关)
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+///
+/// fn main() {
+///     for name in ["b.txt", "a.txt", "c/d.txt"] {
+///         file::write_text_file(&format!("./target/__test/directory_test/list_sorted/{}", name), "").unwrap();
+///     }
+///
+///     let entries = directory::list_sorted("./target/__test/directory_test/list_sorted").unwrap();
+///     let names: Vec<_> = entries.iter().map(|entry| entry.file_name().unwrap().to_str().unwrap()).collect();
+///     assert_eq!(names, vec!["a.txt", "b.txt", "c"]);
+/// }
+/// ```
+pub fn list_sorted<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Vec<PathBuf>> {
+    let directory_path = path.as_path();
+
+    let entries = match read_dir(directory_path) {
+        Ok(entries) => entries,
+        Err(error) => {
+            return Err(FsIOError::io(
+                Operation::ReadDirectory,
+                &directory_path,
+                error,
+            ))
+        }
+    };
+
+    let mut paths = vec![];
+    for entry in entries {
+        match entry {
+            Ok(entry) => paths.push(entry.path()),
+            Err(error) => {
+                return Err(FsIOError::io(
+                    Operation::ReadDirectory,
+                    &directory_path,
+                    error,
+                ))
+            }
+        }
+    }
+    paths.sort_by(|left, right| left.file_name().cmp(&right.file_name()));
+
+    Ok(paths)
+}
+
+/// 递归返回目录下的所有条目路径(不包括目录本身)
+///
+/// 目录在其子条目之前返回, 同一目录下的条目按名称的字典序排序, 因此结果与平台无关.
+/// 不进入符号链接指向的目录.
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+/// use std::path::{Path, PathBuf};
+///
+/// fn main() {
+///     let root = Path::new("./target/__test/directory_test/walk_sorted");
+///     for name in ["b.txt", "a/z.txt", "a-b.txt", "a/c/d.txt"] {
+///         file::write_text_file(&root.join(name), "").unwrap();
+///     }
+///
+///     let entries = directory::walk_sorted(root).unwrap();
+///     let relative: Vec<PathBuf> = entries.iter().map(|entry| entry.strip_prefix(root).unwrap().to_path_buf()).collect();
+///     assert_eq!(
+///         relative,
+///         ["a", "a/c", "a/c/d.txt", "a/z.txt", "a-b.txt", "b.txt"].iter().map(PathBuf::from).collect::<Vec<_>>()
+///     );
+/// }
+/// ```
+pub fn walk_sorted<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Vec<PathBuf>> {
+    Walk::new(path, WalkOptions::default())
+        .map(|entry| entry.map(DirEntryInfo::into_path))
+        .collect()
+}
+
+/// 批量删除选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeleteOptions {
+    /// 单个文件失败时是否继续删除其余文件, 继续时所有失败在结束后以 `FsIOError::Multiple` 返回
+    pub keep_going: bool,
+}
+
+/// 递归删除目录下通过过滤器的文件(包括符号链接本身, 不跟随符号链接), 保留所有目录
+///
+/// 返回被删除的文件路径(按遍历顺序).
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+/// * `filter` - 路径过滤器(路径相对于目录)
+/// * `options` - 删除选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+/// use x_io::directory::DeleteOptions;
+/// use x_io::filter::PathFilter;
+/// use std::path::Path;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/directory_test/delete_filtered/app.log", "log").unwrap();
+///     file::write_text_file("./target/__test/directory_test/delete_filtered/logs/old.log", "log").unwrap();
+///     file::write_text_file("./target/__test/directory_test/delete_filtered/keep/new.log", "log").unwrap();
+///     file::write_text_file("./target/__test/directory_test/delete_filtered/config.toml", "").unwrap();
+///
+///     let filter = PathFilter::new().exclude("keep").unwrap().extensions(["log"]);
+///     let options = DeleteOptions { keep_going: true };
+///     let deleted = directory::delete_filtered("./target/__test/directory_test/delete_filtered", &filter, options).unwrap();
+///     assert_eq!(deleted.len(), 2);
+///
+///     assert!(!Path::new("./target/__test/directory_test/delete_filtered/logs/old.log").exists());
+///     assert!(Path::new("./target/__test/directory_test/delete_filtered/logs").is_dir());
+///     assert!(Path::new("./target/__test/directory_test/delete_filtered/keep/new.log").exists());
+///     assert!(Path::new("./target/__test/directory_test/delete_filtered/config.toml").exists());
+/// }
+/// ```
+pub fn delete_filtered<T: AsPath + ?Sized>(
+    path: &T,
+    filter: &PathFilter,
+    options: DeleteOptions,
+) -> FsIOResult<Vec<PathBuf>> {
+    let directory_path = path.as_path();
+
+    let mut failures = vec![];
+    let mut files = vec![];
+    for entry in Walk::new(directory_path, WalkOptions::default()).with_filter(filter.clone()) {
+        match entry {
+            Ok(entry) => {
+                if !entry.is_dir() {
+                    files.push(entry.into_path());
+                }
+            }
+            Err(error) if options.keep_going => {
+                // walk errors carry the path of the entry that failed
+                let error_path = match error {
+                    FsIOError::Io { ref path, .. } if !path.as_os_str().is_empty() => path.clone(),
+                    FsIOError::NotFound(ref path, _) | FsIOError::PermissionDenied(ref path, _) => {
+                        path.clone()
+                    }
+                    _ => directory_path.to_path_buf(),
+                };
+                failures.push((error_path, error))
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    let mut deleted = vec![];
+    for file_path in files {
+        match remove_file(&file_path) {
+            Ok(_) => deleted.push(file_path),
+            Err(error) => {
+                let error = FsIOError::io(Operation::Delete, &file_path, error);
+                if !options.keep_going {
+                    return Err(error);
+                }
+                failures.push((file_path, error));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(deleted)
+    } else {
+        Err(FsIOError::Multiple(failures))
+    }
+}
+
+/// 切换当前工作目录的守卫, 在析构时(包括panic展开时)恢复之前的工作目录
+///
+/// 工作目录是进程级的状态, 多线程同时切换工作目录时结果不确定.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::directory;
+/// use x_io::directory::PushDir;
+///
+/// fn main() {
+///     directory::create("./target/__test/directory_test/push_dir/build").unwrap();
+///     let previous = std::env::current_dir().unwrap();
+///     {
+///         let guard = PushDir::new("./target/__test/directory_test/push_dir/build").unwrap();
+///         assert!(std::env::current_dir().unwrap().ends_with("push_dir/build"));
+///         assert_eq!(guard.previous(), previous.as_path());
+///     }
+///     assert_eq!(std::env::current_dir().unwrap(), previous);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PushDir {
+    previous: PathBuf,
+}
+
+impl PushDir {
+    /// 将当前工作目录切换到 `path`, 并记录之前的工作目录
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 新的工作目录
+    pub fn new<T: AsPath + ?Sized>(path: &T) -> FsIOResult<PushDir> {
+        let directory_path = path.as_path();
+
+        let previous = match std::env::current_dir() {
+            Ok(value) => value,
+            Err(error) => return Err(FsIOError::io(Operation::Canonicalize, &".", error)),
+        };
+
+        match std::env::set_current_dir(directory_path) {
+            Ok(_) => Ok(PushDir { previous }),
+            Err(error) => Err(FsIOError::io(Operation::ChangeDirectory, &directory_path, error)),
+        }
+    }
+
+    /// 返回之前的工作目录
+    pub fn previous(&self) -> &Path {
+        &self.previous
+    }
+}
+
+impl Drop for PushDir {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.previous);
+    }
+}
+
+/// 切换到 `path` 执行闭包, 然后恢复之前的工作目录(闭包panic时也会恢复), 返回闭包的返回值
+///
+/// # 参数
+///
+/// * `path` - 执行闭包时的工作目录
+/// * `function` - 闭包
+///
+/// # 示例
+///
+/// ```
+/// use x_io::directory;
+///
+/// fn main() {
+///     directory::create("./target/__test/directory_test/with_current_dir/out").unwrap();
+///     let previous = std::env::current_dir().unwrap();
+///
+///     let inside = directory::with_current_dir("./target/__test/directory_test/with_current_dir/out", || {
+///         std::env::current_dir().unwrap()
+///     })
+///     .unwrap();
+///     assert!(inside.ends_with("with_current_dir/out"));
+///     assert_eq!(std::env::current_dir().unwrap(), previous);
+///
+///     let result = std::panic::catch_unwind(|| {
+///         directory::with_current_dir("./target/__test/directory_test/with_current_dir/out", || panic!("build failed"))
+///     });
+///     assert!(result.is_err());
+///     assert_eq!(std::env::current_dir().unwrap(), previous);
+/// }
+/// ```
+pub fn with_current_dir<T, F, R>(path: &T, function: F) -> FsIOResult<R>
+where
+    T: AsPath + ?Sized,
+    F: FnOnce() -> R,
+{
+    let _guard = PushDir::new(path)?;
+    Ok(function())
+}
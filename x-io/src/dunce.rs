@@ -93,7 +93,6 @@ fn windows_char_len(s: &OsStr) -> usize {
 
 #[cfg(any(windows, test))]
 fn is_valid_filename(file_name: &OsStr) -> bool {
-    let file_name = file_name.as_ref();
     if windows_char_len(file_name) > 255 {
         return false;
     }
@@ -126,8 +125,7 @@ fn is_valid_filename(file_name: &OsStr) -> bool {
     true
 }
 
-#[cfg(any(windows, test))]
-const RESERVED_NAMES: [&'static str; 22] = [
+pub(crate) const RESERVED_NAMES: [&str; 22] = [
     "AUX", "NUL", "PRN", "CON", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
     "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
 ];
@@ -192,7 +190,7 @@ fn is_safe_to_strip_unc(path: &Path) -> bool {
 /// Trim '.' and ' '
 #[cfg(any(windows, test))]
 fn right_trim(mut s: &str) -> &str {
-    while s.len() > 0 {
+    while !s.is_empty() {
         let last = s.len() - 1;
         unsafe {
             if s.as_bytes()[last] == b'.' || s.as_bytes()[last] == b' ' {
@@ -275,9 +273,9 @@ fn valid() {
     assert!(!is_valid_filename("?x".as_ref()));
     assert!(!is_valid_filename("a\0a".as_ref()));
     assert!(!is_valid_filename("\x1f".as_ref()));
-    assert!(!is_valid_filename(::std::iter::repeat("a").take(257).collect::<String>().as_ref()));
+    assert!(!is_valid_filename("a".repeat(257).as_ref()));
 
-    assert!(is_valid_filename(::std::iter::repeat("®").take(254).collect::<String>().as_ref()));
+    assert!(is_valid_filename("®".repeat(254).as_ref()));
     assert!(is_valid_filename("ファイル".as_ref()));
     assert!(is_valid_filename("a".as_ref()));
     assert!(is_valid_filename("a.aaaaaaaa".as_ref()));
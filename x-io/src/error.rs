@@ -7,7 +7,107 @@ use std::error::Error;
 use std::fmt;
 use std::fmt::Display;
 use std::io;
-use std::time::SystemTimeError;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTimeError};
+
+use crate::path::as_path::AsPath;
+
+/// 文件系统操作类型
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operation {
+    /// 读取文件
+    Read,
+    /// 写入文件
+    Write,
+    /// 追加文件
+    Append,
+    /// 创建或打开文件
+    Create,
+    /// 删除文件
+    Delete,
+    /// 复制文件(目标路径)
+    Copy(PathBuf),
+    /// 移动或重命名(目标路径)
+    Rename(PathBuf),
+    /// 读取路径的元数据
+    Metadata,
+    /// 读取目录
+    ReadDirectory,
+    /// 创建目录
+    CreateDirectory,
+    /// 删除目录
+    DeleteDirectory,
+    /// 规范路径
+    Canonicalize,
+    /// 读取符号链接
+    ReadLink,
+    /// 其他操作
+    Other,
+    /// 读取压缩文件
+    ReadArchive,
+    /// 写入压缩文件
+    WriteArchive,
+    /// 解压文件
+    Extract,
+    /// 同步到磁盘
+    Sync,
+    /// 创建或打开文件用于写入
+    OpenForWrite,
+    /// 写入文件内容
+    WriteContent,
+    /// 完成写入(同步到磁盘)
+    FinishWrite,
+    /// 写入文件的十六进制转储
+    HexDump,
+    /// 写入标准输出
+    WriteStdout,
+    /// 读取标准输入
+    ReadStdin,
+    /// 展开路径中的 `~`
+    ExpandHome,
+    /// 监听路径
+    Watch,
+    /// 切换当前目录
+    ChangeDirectory,
+    /// 拼接路径
+    Join,
+}
+
+impl Display for Operation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let name = match self {
+            Self::Read => "read file",
+            Self::Write => "write file",
+            Self::Append => "append file",
+            Self::Create => "create file",
+            Self::Delete => "delete file",
+            Self::Copy(_) => "copy file",
+            Self::Rename(_) => "rename",
+            Self::Metadata => "extract metadata for path",
+            Self::ReadDirectory => "read directory",
+            Self::CreateDirectory => "create directory",
+            Self::DeleteDirectory => "delete directory",
+            Self::Canonicalize => "canonicalize path",
+            Self::ReadLink => "read symbolic link",
+            Self::Other => "access",
+            Self::ReadArchive => "read archive",
+            Self::WriteArchive => "write archive",
+            Self::Extract => "extract file",
+            Self::Sync => "sync to disk",
+            Self::OpenForWrite => "create/open file",
+            Self::WriteContent => "write to file",
+            Self::FinishWrite => "finish up writing to file",
+            Self::HexDump => "write hex dump of file",
+            Self::WriteStdout => "write to standard output",
+            Self::ReadStdin => "read from standard input",
+            Self::ExpandHome => "expand path",
+            Self::Watch => "watch path",
+            Self::ChangeDirectory => "change directory",
+            Self::Join => "join path",
+        };
+        write!(formatter, "{}", name)
+    }
+}
 
 /// 保存错误信息
 #[derive(Debug)]
@@ -16,10 +116,53 @@ pub enum FsIOError {
     AlreadyExist(String),
     /// 不是文件
     NotFile(String),
+    /// 路径不存在(路径, 原因)
+    NotFound(PathBuf, Option<io::Error>),
+    /// 权限不足(路径, 原因)
+    PermissionDenied(PathBuf, Option<io::Error>),
     /// IO错误
     IOError(String, Option<io::Error>),
+    /// 文件系统操作失败(操作类型, 路径, 原因)
+    Io {
+        /// 操作类型
+        operation: Operation,
+        /// 路径
+        path: PathBuf,
+        /// 原因
+        source: io::Error,
+    },
     /// 系统时间错误
     SystemTimeError(String, Option<SystemTimeError>),
+    /// 解析或序列化错误
+    Parse(String, Option<Box<dyn Error + Send + Sync>>),
+    /// 符号链接循环(或层级过多)
+    SymlinkLoop(String),
+    /// 附加了上下文信息的错误(上下文, 原始错误)
+    Context(String, Box<FsIOError>),
+    /// 批量操作中多个路径的错误(路径, 错误)
+    Multiple(Vec<(PathBuf, FsIOError)>),
+    /// 文件内容与校验和不一致
+    ChecksumMismatch {
+        /// 文件路径
+        path: PathBuf,
+        /// 校验和文件中的校验和
+        expected: String,
+        /// 文件内容的校验和
+        actual: String,
+    },
+    /// 只读文件系统拒绝修改路径
+    ReadOnly(PathBuf),
+    /// 操作没有在期限内完成(期限)
+    TimedOut(Duration),
+    /// 写入文件后目录的总大小超过配额
+    QuotaExceeded {
+        /// 文件路径
+        path: PathBuf,
+        /// 目录的最大总大小(字节)
+        limit: u64,
+        /// 写入后目录的总大小(字节)
+        size: u64,
+    },
 }
 
 impl Display for FsIOError {
@@ -28,6 +171,60 @@ impl Display for FsIOError {
         match self {
             Self::AlreadyExist(ref message) => write!(formatter, "{}", message),
             Self::NotFile(ref message) => write!(formatter, "{}", message),
+            Self::NotFound(ref path, ref cause) => {
+                write!(formatter, "Path: {:?} not found.", path)?;
+                match cause {
+                    Some(cause_err) => write!(formatter, "\n{}", cause_err),
+                    None => Ok(()),
+                }
+            }
+            Self::PermissionDenied(ref path, ref cause) => {
+                write!(formatter, "Permission denied for path: {:?}", path)?;
+                match cause {
+                    Some(cause_err) => write!(formatter, "\n{}", cause_err),
+                    None => Ok(()),
+                }
+            }
+            Self::SymlinkLoop(ref message) => write!(formatter, "{}", message),
+            Self::Context(ref context, ref cause) => {
+                writeln!(formatter, "{}", context)?;
+                cause.fmt(formatter)
+            }
+            Self::Multiple(ref errors) => {
+                write!(formatter, "{} operation(s) failed:", errors.len())?;
+                for (path, error) in errors {
+                    write!(formatter, "\n{:?}: {}", path, error)?;
+                }
+                Ok(())
+            }
+            Self::ChecksumMismatch {
+                ref path,
+                ref expected,
+                ref actual,
+            } => write!(
+                formatter,
+                "Checksum mismatch for file: {:?}, expected: {}, actual: {}",
+                path, expected, actual
+            ),
+            Self::ReadOnly(ref path) => {
+                write!(
+                    formatter,
+                    "Read-only file system, unable to modify: {:?}",
+                    path
+                )
+            }
+            Self::TimedOut(ref duration) => {
+                write!(formatter, "Operation timed out after: {:?}", duration)
+            }
+            Self::QuotaExceeded {
+                ref path,
+                limit,
+                size,
+            } => write!(
+                formatter,
+                "Writing file: {:?} exceeds the directory quota of {} bytes ({} bytes).",
+                path, limit, size
+            ),
             Self::IOError(ref message, ref cause) => {
                 writeln!(formatter, "{}", message)?;
                 match cause {
@@ -35,6 +232,32 @@ impl Display for FsIOError {
                     None => Ok(()),
                 }
             }
+            Self::Io {
+                ref operation,
+                ref path,
+                ref source,
+            } => {
+                match operation {
+                    Operation::WriteContent => {
+                        write!(formatter, "Error while writing to file: {:?}", path)?
+                    }
+                    Operation::FinishWrite => {
+                        write!(formatter, "Error finish up writing to file: {:?}", path)?
+                    }
+                    _ if path.as_os_str().is_empty() => {
+                        write!(formatter, "Unable to {}: unknown path", operation)?
+                    }
+                    _ => write!(formatter, "Unable to {}: {:?}", operation, path)?,
+                }
+                match operation {
+                    Operation::Copy(target) | Operation::Rename(target) => {
+                        writeln!(formatter, " to: {:?}", target)?
+                    }
+                    Operation::OpenForWrite => writeln!(formatter, " for writing.")?,
+                    _ => writeln!(formatter)?,
+                }
+                source.fmt(formatter)
+            }
             Self::SystemTimeError(ref message, ref cause) => {
                 writeln!(formatter, "{}", message)?;
                 match cause {
@@ -42,6 +265,13 @@ impl Display for FsIOError {
                     None => Ok(()),
                 }
             }
+            Self::Parse(ref message, ref cause) => {
+                writeln!(formatter, "{}", message)?;
+                match cause {
+                    Some(cause_err) => cause_err.fmt(formatter),
+                    None => Ok(()),
+                }
+            }
         }
     }
 }
@@ -51,14 +281,458 @@ impl Error for FsIOError {
         match self {
             Self::AlreadyExist(_) => None,
             Self::NotFile(_) => None,
+            Self::NotFound(_, error) | Self::PermissionDenied(_, error) => {
+                error.as_ref().map(|io_error| {
+                    let std_error: &dyn Error = io_error;
+                    std_error
+                })
+            }
+            Self::SymlinkLoop(_) => None,
+            Self::Context(_, error) => Some(error.as_ref()),
+            Self::ChecksumMismatch { .. } => None,
+            Self::ReadOnly(_) => None,
+            Self::TimedOut(_) => None,
+            Self::QuotaExceeded { .. } => None,
+            Self::Multiple(errors) => errors.first().map(|(_, error)| {
+                let std_error: &dyn Error = error;
+                std_error
+            }),
             Self::IOError(_, error) => error.as_ref().map(|io_error| {
                 let std_error: &dyn Error = io_error;
                 std_error
             }),
+            Self::Io { source, .. } => Some(source),
             Self::SystemTimeError(_, error) => error.as_ref().map(|system_time_error| {
                 let std_error: &dyn Error = system_time_error;
                 std_error
             }),
+            Self::Parse(_, error) => error.as_ref().map(|parse_error| {
+                let std_error: &dyn Error = parse_error.as_ref();
+                std_error
+            }),
         }
     }
-}
\ No newline at end of file
+}
+
+impl FsIOError {
+    /// 返回错误对应的 `io::ErrorKind`, 包含io::Error时返回其类型
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::file;
+    /// use std::io;
+    ///
+    /// fn main() {
+    ///     let error = file::read_file("./target/__test/error_test/kind/missing.txt").unwrap_err();
+    ///     assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    /// }
+    /// ```
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Self::AlreadyExist(_) => io::ErrorKind::AlreadyExists,
+            Self::NotFile(_) => io::ErrorKind::InvalidInput,
+            Self::NotFound(_, _) => io::ErrorKind::NotFound,
+            Self::PermissionDenied(_, _) => io::ErrorKind::PermissionDenied,
+            Self::IOError(_, Some(error)) => error.kind(),
+            Self::IOError(_, None) => io::ErrorKind::Other,
+            Self::Io { source, .. } => source.kind(),
+            Self::SystemTimeError(_, _) => io::ErrorKind::Other,
+            Self::Parse(_, _) => io::ErrorKind::InvalidData,
+            Self::SymlinkLoop(_) => io::ErrorKind::Other,
+            Self::Context(_, error) => error.kind(),
+            Self::Multiple(_) => io::ErrorKind::Other,
+            Self::ChecksumMismatch { .. } => io::ErrorKind::InvalidData,
+            Self::ReadOnly(_) => io::ErrorKind::PermissionDenied,
+            Self::TimedOut(_) => io::ErrorKind::TimedOut,
+            Self::QuotaExceeded { .. } => io::ErrorKind::Other,
+        }
+    }
+
+    /// 返回是否为路径不存在错误
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::file;
+    ///
+    /// fn main() {
+    ///     let error = file::read_text_file("./target/__test/error_test/is_not_found/missing.txt").unwrap_err();
+    ///     assert!(error.is_not_found());
+    ///     assert!(!error.is_permission_denied());
+    /// }
+    /// ```
+    pub fn is_not_found(&self) -> bool {
+        self.kind() == io::ErrorKind::NotFound
+    }
+
+    /// 返回是否为权限不足错误
+    pub fn is_permission_denied(&self) -> bool {
+        self.kind() == io::ErrorKind::PermissionDenied
+    }
+
+    /// 返回是否为路径已存在错误
+    pub fn is_already_exists(&self) -> bool {
+        self.kind() == io::ErrorKind::AlreadyExists
+    }
+
+    /// 返回是否为超时错误
+    pub fn is_timed_out(&self) -> bool {
+        self.kind() == io::ErrorKind::TimedOut
+    }
+
+    /// 创建文件系统操作错误, 读取/删除/元数据等操作的路径不存在或权限不足时返回 `NotFound` 或 `PermissionDenied`(保留原因)
+    ///
+    /// # 参数
+    ///
+    /// * `operation` - 操作类型
+    /// * `path` - 路径
+    /// * `source` - 原因
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::error::{FsIOError, Operation};
+    /// use std::io;
+    ///
+    /// fn main() {
+    ///     let error = FsIOError::io(Operation::Read, "./config.toml", io::Error::from(io::ErrorKind::InvalidData));
+    ///     assert!(error.to_string().starts_with("Unable to read file: \"./config.toml\"\n"));
+    ///
+    ///     let create_error = FsIOError::io(Operation::CreateDirectory, "./data", io::Error::from(io::ErrorKind::Other));
+    ///     assert!(create_error.to_string().starts_with("Unable to create directory: \"./data\"\n"));
+    ///
+    ///     let open_error = FsIOError::io(Operation::OpenForWrite, "./data.txt", io::Error::from(io::ErrorKind::Other));
+    ///     assert!(open_error.to_string().starts_with("Unable to create/open file: \"./data.txt\" for writing.\n"));
+    ///
+    ///     let write_error = FsIOError::io(Operation::WriteContent, "./data.txt", io::Error::from(io::ErrorKind::Other));
+    ///     assert!(write_error.to_string().starts_with("Error while writing to file: \"./data.txt\"\n"));
+    ///
+    ///     match error {
+    ///         FsIOError::Io { operation, path, .. } => {
+    ///             assert_eq!(operation, Operation::Read);
+    ///             assert_eq!(path, std::path::PathBuf::from("./config.toml"));
+    ///         }
+    ///         _ => panic!("unexpected error"),
+    ///     }
+    ///
+    ///     let error = FsIOError::io(Operation::Read, "./config.toml", io::Error::from(io::ErrorKind::NotFound));
+    ///     assert!(error.is_not_found());
+    ///     assert!(std::error::Error::source(&error).is_some());
+    ///     assert!(matches!(error, FsIOError::NotFound(_, Some(_))));
+    ///
+    ///     // operations that create paths keep the operation type
+    ///     let error = FsIOError::io(Operation::Create, "./data/config.toml", io::Error::from(io::ErrorKind::NotFound));
+    ///     assert!(error.is_not_found());
+    ///     assert!(matches!(error, FsIOError::Io { operation: Operation::Create, .. }));
+    /// }
+    /// ```
+    pub fn io<T: AsPath + ?Sized>(operation: Operation, path: &T, source: io::Error) -> FsIOError {
+        let path = path.as_path().to_path_buf();
+
+        let is_lookup = matches!(
+            operation,
+            Operation::Read
+                | Operation::Delete
+                | Operation::Metadata
+                | Operation::ReadDirectory
+                | Operation::DeleteDirectory
+                | Operation::Canonicalize
+                | Operation::ReadLink
+                | Operation::ReadArchive
+        );
+        if is_lookup {
+            match source.kind() {
+                io::ErrorKind::NotFound => return FsIOError::NotFound(path, Some(source)),
+                io::ErrorKind::PermissionDenied => {
+                    return FsIOError::PermissionDenied(path, Some(source))
+                }
+                _ => {}
+            }
+        }
+
+        FsIOError::Io {
+            operation,
+            path,
+            source,
+        }
+    }
+}
+
+/// 将io::Error转换为路径未知的文件系统操作错误, 用于在返回 `FsIOResult` 的函数中使用 `?`
+///
+/// # 示例
+///
+/// ```
+/// use x_io::error::FsIOError;
+/// use x_io::result::FsIOResult;
+/// use std::fs::File;
+/// use std::io::Read;
+///
+/// fn read_header() -> FsIOResult<Vec<u8>> {
+///     let mut header = vec![0u8; 4];
+///     let mut file = File::open("./src/lib.rs")?;
+///     file.read_exact(&mut header)?;
+///     Ok(header)
+/// }
+///
+/// fn main() {
+///     assert_eq!(read_header().unwrap().len(), 4);
+///
+///     let error: FsIOError = std::io::Error::from(std::io::ErrorKind::NotFound).into();
+///     assert!(error.is_not_found());
+/// }
+/// ```
+impl From<io::Error> for FsIOError {
+    fn from(error: io::Error) -> FsIOError {
+        FsIOError::Io {
+            operation: Operation::Other,
+            path: PathBuf::new(),
+            source: error,
+        }
+    }
+}
+
+/// 将文件系统错误转换为io::Error(保留错误类型和信息), 用于在返回 `io::Result` 的函数中使用 `?`
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use std::io;
+///
+/// fn load() -> io::Result<String> {
+///     let text = file::read_text_file("./target/__test/error_test/into_io_error/missing.txt")?;
+///     Ok(text)
+/// }
+///
+/// fn main() {
+///     let error = load().unwrap_err();
+///     assert_eq!(error.kind(), io::ErrorKind::NotFound);
+/// }
+/// ```
+impl From<FsIOError> for io::Error {
+    fn from(error: FsIOError) -> io::Error {
+        io::Error::new(error.kind(), error)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Operation {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Read => serializer.serialize_unit_variant("Operation", 0, "Read"),
+            Self::Write => serializer.serialize_unit_variant("Operation", 1, "Write"),
+            Self::Append => serializer.serialize_unit_variant("Operation", 2, "Append"),
+            Self::Create => serializer.serialize_unit_variant("Operation", 3, "Create"),
+            Self::Delete => serializer.serialize_unit_variant("Operation", 4, "Delete"),
+            Self::Copy(target) => {
+                serializer.serialize_newtype_variant("Operation", 5, "Copy", target)
+            }
+            Self::Rename(target) => {
+                serializer.serialize_newtype_variant("Operation", 6, "Rename", target)
+            }
+            Self::Metadata => serializer.serialize_unit_variant("Operation", 7, "Metadata"),
+            Self::ReadDirectory => {
+                serializer.serialize_unit_variant("Operation", 8, "ReadDirectory")
+            }
+            Self::CreateDirectory => {
+                serializer.serialize_unit_variant("Operation", 9, "CreateDirectory")
+            }
+            Self::DeleteDirectory => {
+                serializer.serialize_unit_variant("Operation", 10, "DeleteDirectory")
+            }
+            Self::Canonicalize => {
+                serializer.serialize_unit_variant("Operation", 11, "Canonicalize")
+            }
+            Self::ReadLink => serializer.serialize_unit_variant("Operation", 12, "ReadLink"),
+            Self::Other => serializer.serialize_unit_variant("Operation", 13, "Other"),
+            Self::ReadArchive => serializer.serialize_unit_variant("Operation", 14, "ReadArchive"),
+            Self::WriteArchive => {
+                serializer.serialize_unit_variant("Operation", 15, "WriteArchive")
+            }
+            Self::Extract => serializer.serialize_unit_variant("Operation", 16, "Extract"),
+            Self::Sync => serializer.serialize_unit_variant("Operation", 17, "Sync"),
+            Self::OpenForWrite => {
+                serializer.serialize_unit_variant("Operation", 18, "OpenForWrite")
+            }
+            Self::WriteContent => {
+                serializer.serialize_unit_variant("Operation", 19, "WriteContent")
+            }
+            Self::FinishWrite => serializer.serialize_unit_variant("Operation", 20, "FinishWrite"),
+            Self::HexDump => serializer.serialize_unit_variant("Operation", 21, "HexDump"),
+            Self::WriteStdout => serializer.serialize_unit_variant("Operation", 22, "WriteStdout"),
+            Self::ReadStdin => serializer.serialize_unit_variant("Operation", 23, "ReadStdin"),
+            Self::ExpandHome => serializer.serialize_unit_variant("Operation", 24, "ExpandHome"),
+            Self::Watch => serializer.serialize_unit_variant("Operation", 25, "Watch"),
+            Self::ChangeDirectory => {
+                serializer.serialize_unit_variant("Operation", 26, "ChangeDirectory")
+            }
+            Self::Join => serializer.serialize_unit_variant("Operation", 27, "Join"),
+        }
+    }
+}
+
+/// 序列化错误的原因(类型和信息)
+#[cfg(feature = "serde")]
+struct SerializeCause<'a> {
+    kind: Option<io::ErrorKind>,
+    message: &'a dyn Display,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SerializeCause<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let kind = self.kind.map(|kind| format!("{:?}", kind));
+        let mut state = serializer.serialize_struct("Cause", 2)?;
+        state.serialize_field("kind", &kind)?;
+        state.serialize_field("message", &self.message.to_string())?;
+        state.end()
+    }
+}
+
+/// 序列化错误(需要启用 `serde` feature), 格式与派生的 `Serialize` 相同(外部标记的枚举),
+/// io::Error等原因序列化为 `{ "kind": .., "message": .. }`
+///
+/// # 示例
+///
+/// ```
+/// use x_io::error::{FsIOError, Operation};
+/// use std::io;
+///
+/// fn main() {
+///     let error = FsIOError::io(Operation::Write, "./report.json", io::Error::new(io::ErrorKind::Other, "disk full"));
+///     let json = serde_json::to_string(&error).unwrap();
+///     assert_eq!(
+///         json,
+///         r#"{"Io":{"operation":"Write","path":"./report.json","source":{"kind":"Other","message":"disk full"}}}"#
+///     );
+///
+///     let error = FsIOError::NotFound("./missing.txt".into(), None);
+///     assert_eq!(serde_json::to_string(&error).unwrap(), r#"{"NotFound":["./missing.txt",null]}"#);
+/// }
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for FsIOError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::{SerializeStructVariant, SerializeTupleVariant};
+
+        match self {
+            Self::AlreadyExist(message) => {
+                serializer.serialize_newtype_variant("FsIOError", 0, "AlreadyExist", message)
+            }
+            Self::NotFile(message) => {
+                serializer.serialize_newtype_variant("FsIOError", 1, "NotFile", message)
+            }
+            Self::NotFound(path, cause) => {
+                let cause = cause.as_ref().map(|error| SerializeCause {
+                    kind: Some(error.kind()),
+                    message: error,
+                });
+                let mut state = serializer.serialize_tuple_variant("FsIOError", 2, "NotFound", 2)?;
+                state.serialize_field(path)?;
+                state.serialize_field(&cause)?;
+                state.end()
+            }
+            Self::PermissionDenied(path, cause) => {
+                let cause = cause.as_ref().map(|error| SerializeCause {
+                    kind: Some(error.kind()),
+                    message: error,
+                });
+                let mut state =
+                    serializer.serialize_tuple_variant("FsIOError", 3, "PermissionDenied", 2)?;
+                state.serialize_field(path)?;
+                state.serialize_field(&cause)?;
+                state.end()
+            }
+            Self::IOError(message, cause) => {
+                let cause = cause.as_ref().map(|error| SerializeCause {
+                    kind: Some(error.kind()),
+                    message: error,
+                });
+                let mut state = serializer.serialize_tuple_variant("FsIOError", 4, "IOError", 2)?;
+                state.serialize_field(message)?;
+                state.serialize_field(&cause)?;
+                state.end()
+            }
+            Self::Io {
+                operation,
+                path,
+                source,
+            } => {
+                let mut state = serializer.serialize_struct_variant("FsIOError", 5, "Io", 3)?;
+                state.serialize_field("operation", operation)?;
+                state.serialize_field("path", path)?;
+                state.serialize_field(
+                    "source",
+                    &SerializeCause {
+                        kind: Some(source.kind()),
+                        message: source,
+                    },
+                )?;
+                state.end()
+            }
+            Self::SystemTimeError(message, cause) => {
+                let cause = cause.as_ref().map(|error| SerializeCause {
+                    kind: None,
+                    message: error,
+                });
+                let mut state =
+                    serializer.serialize_tuple_variant("FsIOError", 6, "SystemTimeError", 2)?;
+                state.serialize_field(message)?;
+                state.serialize_field(&cause)?;
+                state.end()
+            }
+            Self::Parse(message, cause) => {
+                let cause = cause.as_ref().map(|error| SerializeCause {
+                    kind: None,
+                    message: error,
+                });
+                let mut state = serializer.serialize_tuple_variant("FsIOError", 7, "Parse", 2)?;
+                state.serialize_field(message)?;
+                state.serialize_field(&cause)?;
+                state.end()
+            }
+            Self::SymlinkLoop(message) => {
+                serializer.serialize_newtype_variant("FsIOError", 8, "SymlinkLoop", message)
+            }
+            Self::Context(context, error) => {
+                let mut state = serializer.serialize_tuple_variant("FsIOError", 9, "Context", 2)?;
+                state.serialize_field(context)?;
+                state.serialize_field(error.as_ref())?;
+                state.end()
+            }
+            Self::Multiple(errors) => {
+                serializer.serialize_newtype_variant("FsIOError", 10, "Multiple", errors)
+            }
+            Self::ChecksumMismatch {
+                path,
+                expected,
+                actual,
+            } => {
+                let mut state =
+                    serializer.serialize_struct_variant("FsIOError", 11, "ChecksumMismatch", 3)?;
+                state.serialize_field("path", path)?;
+                state.serialize_field("expected", expected)?;
+                state.serialize_field("actual", actual)?;
+                state.end()
+            }
+            Self::ReadOnly(path) => {
+                serializer.serialize_newtype_variant("FsIOError", 12, "ReadOnly", path)
+            }
+            Self::TimedOut(duration) => {
+                serializer.serialize_newtype_variant("FsIOError", 13, "TimedOut", duration)
+            }
+            Self::QuotaExceeded { path, limit, size } => {
+                let mut state =
+                    serializer.serialize_struct_variant("FsIOError", 14, "QuotaExceeded", 3)?;
+                state.serialize_field("path", path)?;
+                state.serialize_field("limit", limit)?;
+                state.serialize_field("size", size)?;
+                state.end()
+            }
+        }
+    }
+}
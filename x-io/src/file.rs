@@ -1,11 +1,21 @@
-use std::fs::{File, OpenOptions, read, read_to_string, remove_file};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::fs::{File, Metadata, OpenOptions, copy, metadata, read, read_to_string, remove_file};
 use std::io;
-use std::io::Write;
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use x_hash::{sha256, Digest, DigestFmt, Md5, Sha1, Sha256, Sha512};
 
 use crate::directory;
-use crate::error::FsIOError;
+use crate::directory::Created;
+use crate::error::{FsIOError, Operation};
 use crate::path::as_path::AsPath;
+use crate::path::{is_same_file, unique_sibling};
 use crate::result::FsIOResult;
+use crate::throttle::Throttle;
 
 /// 保证文件存在(文件不存在会创建一个空的文件)
 ///
@@ -28,11 +38,36 @@ use crate::result::FsIOResult;
 /// }
 /// ```
 pub fn ensure_exists<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
+    ensure_exists_reporting(path).map(|_| ())
+}
+
+/// 保证文件存在(文件不存在会创建一个空的文件), 返回文件是新创建的还是已经存在
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::directory::Created;
+/// use x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/ensure_exists_reporting/state.db";
+///     let _ = std::fs::remove_file(file_path);
+///
+///     assert_eq!(file::ensure_exists_reporting(file_path).unwrap(), Created::New);
+///     assert_eq!(file::ensure_exists_reporting(file_path).unwrap(), Created::AlreadyExisted);
+///     assert!(file::ensure_exists_reporting("./target/__test/file_test/ensure_exists_reporting").is_err());
+/// }
+/// ```
+pub fn ensure_exists_reporting<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Created> {
     let file_path = path.as_path();
 
     if file_path.exists() {
         if file_path.is_file() {
-            Ok(())
+            Ok(Created::AlreadyExisted)
         } else {
             Err(FsIOError::AlreadyExist(
                 format!("Unable to create file: {:?}", &file_path).to_string(),
@@ -41,16 +76,81 @@ pub fn ensure_exists<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
     } else {
         directory::create_parent(path)?;
 
-        match File::create(&file_path) {
-            Ok(_) => Ok(()),
-            Err(error) => Err(FsIOError::IOError(
-                format!("Unable to create file: {:?}", &file_path).to_string(),
-                Some(error),
-            )),
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(file_path)
+        {
+            Ok(_) => Ok(Created::New),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists && file_path.is_file() => {
+                Ok(Created::AlreadyExisted)
+            }
+            Err(error) => Err(FsIOError::io(Operation::Create, &file_path, error)),
         }
     }
 }
 
+/// 保证文件存在, 文件不存在时创建文件(包括父级目录)并写入默认内容, 已存在的文件不会修改
+///
+/// 使用 `create_new` 创建文件, 多个进程同时调用时只有一个会写入默认内容.
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `default_content` - 文件不存在时写入的内容
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/ensure_exists_with/config.toml";
+///     let _ = std::fs::remove_file(file_path);
+///
+///     file::ensure_exists_with(file_path, "debug = false\n").unwrap();
+///     assert_eq!(file::read_text_file(file_path).unwrap(), "debug = false\n");
+///
+///     file::write_text_file(file_path, "debug = true\n").unwrap();
+///     file::ensure_exists_with(file_path, "debug = false\n").unwrap();
+///     assert_eq!(file::read_text_file(file_path).unwrap(), "debug = true\n");
+/// }
+/// ```
+pub fn ensure_exists_with<T, D>(path: &T, default_content: D) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    D: AsRef<[u8]>,
+{
+    let file_path = path.as_path();
+
+    if file_path.is_file() {
+        return Ok(());
+    }
+    if file_path.exists() {
+        return Err(FsIOError::AlreadyExist(
+            format!("Unable to create file: {:?}", &file_path).to_string(),
+        ));
+    }
+
+    directory::create_parent(path)?;
+
+    match OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(file_path)
+    {
+        Ok(mut file) => match file
+            .write_all(default_content.as_ref())
+            .and_then(|_| file.sync_all())
+        {
+            Ok(_) => Ok(()),
+            Err(error) => Err(FsIOError::io(Operation::Write, &file_path, error)),
+        },
+        Err(error) if error.kind() == io::ErrorKind::AlreadyExists && file_path.is_file() => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::Create, &file_path, error)),
+    }
+}
+
 /// 创建文本文件, 如果存在则覆盖文件
 ///
 /// # 参数
@@ -95,7 +195,7 @@ pub fn write_text_file<T: AsPath + ?Sized>(path: &T, text: &str) -> FsIOResult<(
 ///     let file_path = "./target/__test/file_test/append_text_file/file.txt";
 ///     let mut result = file::write_text_file(file_path, "some content");
 ///     assert!(result.is_ok());
-///     result = file::append_text_file(file_path, "more content");
+///     result = file::append_text_file(file_path, " more content");
 ///     assert!(result.is_ok());
 ///
 ///     let text = file::read_text_file(file_path).unwrap();
@@ -137,6 +237,178 @@ pub fn write_file<T: AsPath + ?Sized>(path: &T, data: &[u8]) -> FsIOResult<()> {
     modify_file(path, &move |file: &mut File| file.write_all(data), false)
 }
 
+/// 创建文件(包括父级目录, 如果存在则覆盖文件), 按写入选项同步文件和父级目录, 或使用直接IO写入
+///
+/// 忽略 `WriteOptions::threads`. `sync` 为true时同步文件内容, `sync_parent` 为true时同步父级目录(`directory::sync`).
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `data` - 文件内容
+/// * `options` - 写入选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::file::WriteOptions;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_file_with/journal.log";
+///     let options = WriteOptions { sync_parent: true, ..WriteOptions::default() };
+///     file::write_file_with(file_path, "entry".as_bytes(), options).unwrap();
+///
+///     assert_eq!(file::read_text_file(file_path).unwrap(), "entry");
+/// }
+/// ```
+pub fn write_file_with<T: AsPath + ?Sized>(
+    path: &T,
+    data: &[u8],
+    options: WriteOptions,
+) -> FsIOResult<()> {
+    let file_path = path.as_path();
+
+    directory::create_parent(file_path)?;
+
+    let created = if options.direct_io {
+        create_direct(file_path)
+    } else {
+        File::create(file_path)
+    };
+    let mut file = match created {
+        Ok(file) => file,
+        Err(error) => return Err(FsIOError::io(Operation::Create, &file_path, error)),
+    };
+
+    let written = if options.direct_io {
+        write_direct(&mut &data[..], &mut file, None).map(|_| ())
+    } else {
+        file.write_all(data)
+    };
+    let result = written.and_then(|_| {
+        if options.sync {
+            file.sync_all()
+        } else {
+            Ok(())
+        }
+    });
+    if let Err(error) = result {
+        return Err(FsIOError::io(Operation::Write, &file_path, error));
+    }
+
+    if options.sync_parent {
+        directory::sync_parent(file_path)?;
+    }
+    Ok(())
+}
+
+/// 创建文件(如果存在则覆盖文件), 写入后父级目录中所有文件的总大小超过 `max_dir_size` 时不写入并返回
+/// `FsIOError::QuotaExceeded`
+///
+/// 被覆盖文件的原有大小不计入总大小. 需要删除旧文件腾出空间时使用 `directory::enforce_quota`.
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `data` - 文件内容
+/// * `max_dir_size` - 父级目录的最大总大小(字节)
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::{directory, file};
+/// use crate::x_io::error::FsIOError;
+///
+/// fn main() {
+///     directory::delete("./target/__test/file_test/write_file_within_quota").unwrap();
+///     let file_path = "./target/__test/file_test/write_file_within_quota/file1.bin";
+///     let result = file::write_file_within_quota(file_path, &[0u8; 10], 15);
+///     assert!(result.is_ok());
+///
+///     // overwriting the same file does not count its old size
+///     let result = file::write_file_within_quota(file_path, &[0u8; 12], 15);
+///     assert!(result.is_ok());
+///
+///     let result = file::write_file_within_quota("./target/__test/file_test/write_file_within_quota/file2.bin", &[0u8; 10], 15);
+///     assert!(matches!(result, Err(FsIOError::QuotaExceeded { limit: 15, size: 22, .. })));
+/// }
+/// ```
+pub fn write_file_within_quota<T: AsPath + ?Sized>(
+    path: &T,
+    data: &[u8],
+    max_dir_size: u64,
+) -> FsIOResult<()> {
+    let file_path = path.as_path();
+    let directory_size = match file_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => directory::size(&parent)?,
+        _ => directory::size(".")?,
+    };
+    let existing_size = match metadata(file_path) {
+        Ok(file_metadata) if file_metadata.is_file() => file_metadata.len(),
+        _ => 0,
+    };
+
+    let new_size = directory_size.saturating_sub(existing_size) + data.len() as u64;
+    if new_size > max_dir_size {
+        return Err(FsIOError::QuotaExceeded {
+            path: file_path.to_path_buf(),
+            limit: max_dir_size,
+            size: new_size,
+        });
+    }
+
+    write_file(path, data)
+}
+
+/// 原子地创建文件(如果存在则覆盖文件): 先写入同目录的临时文件并同步到磁盘, 再重命名为目标文件,
+/// 因此读取者看到的要么是旧内容, 要么是完整的新内容
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `data` - 文件内容
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_file_atomic/settings.txt";
+///     let result = file::write_file_atomic(file_path, "some content".as_bytes());
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file(file_path).unwrap();
+///     assert_eq!(text, "some content");
+/// }
+/// ```
+pub fn write_file_atomic<T: AsPath + ?Sized>(path: &T, data: &[u8]) -> FsIOResult<()> {
+    let file_path = path.as_path();
+    directory::create_parent(&file_path)?;
+
+    let temp_path = unique_sibling(file_path, "tmp");
+    let result = match File::create(&temp_path) {
+        Ok(mut file) => file.write_all(data).and_then(|_| file.sync_all()),
+        Err(error) => Err(error),
+    };
+    if let Err(error) = result {
+        let _ = remove_file(&temp_path);
+        return Err(FsIOError::io(Operation::Write, &temp_path, error));
+    }
+
+    match fs::rename(&temp_path, file_path) {
+        Ok(_) => Ok(()),
+        Err(error) => {
+            let _ = remove_file(&temp_path);
+            Err(FsIOError::io(
+                Operation::Rename(file_path.to_path_buf()),
+                &temp_path,
+                error,
+            ))
+        }
+    }
+}
+
 /// 追加原始数据, 如果存在则追加文件
 ///
 /// # 参数
@@ -216,27 +488,18 @@ pub fn modify_file<T: AsPath + ?Sized>(
     let result = if append && file_path.exists() {
         OpenOptions::new().append(true).open(file_path)
     } else {
-        File::create(&file_path)
+        File::create(file_path)
     };
 
     match result {
         Ok(mut fd) => match write_content(&mut fd) {
             Ok(_) => match fd.sync_all() {
                 Ok(_) => Ok(()),
-                Err(error) => Err(FsIOError::IOError(
-                    format!("Error finish up writing to file: {:?}", &file_path).to_string(),
-                    Some(error),
-                )),
+                Err(error) => Err(FsIOError::io(Operation::FinishWrite, &file_path, error)),
             },
-            Err(error) => Err(FsIOError::IOError(
-                format!("Error while writing to file: {:?}", &file_path).to_string(),
-                Some(error),
-            )),
+            Err(error) => Err(FsIOError::io(Operation::WriteContent, &file_path, error)),
         },
-        Err(error) => Err(FsIOError::IOError(
-            format!("Unable to create/open file: {:?} for writing.", &file_path).to_string(),
-            Some(error),
-        )),
+        Err(error) => Err(FsIOError::io(Operation::OpenForWrite, &file_path, error)),
     }
 }
 
@@ -265,12 +528,9 @@ pub fn modify_file<T: AsPath + ?Sized>(
 pub fn read_text_file<T: AsPath + ?Sized>(path: &T) -> FsIOResult<String> {
     let file_path = path.as_path();
 
-    match read_to_string(&file_path) {
+    match read_to_string(file_path) {
         Ok(content) => Ok(content),
-        Err(error) => Err(FsIOError::IOError(
-            format!("Unable to read file: {:?}", &file_path).to_string(),
-            Some(error),
-        )),
+        Err(error) => Err(FsIOError::io(Operation::Read, &file_path, error)),
     }
 }
 
@@ -291,7 +551,7 @@ pub fn read_text_file<T: AsPath + ?Sized>(path: &T) -> FsIOResult<String> {
 ///     let file_path = "./target/__test/file_test/read_file/file.txt";
 ///     let mut result = file::write_file(file_path, "some content".as_bytes());
 ///     assert!(result.is_ok());
-///     result = file::append_file(file_path, "more content".as_bytes());
+///     result = file::append_file(file_path, " more content".as_bytes());
 ///     assert!(result.is_ok());
 ///
 ///     let data = file::read_file(file_path).unwrap();
@@ -302,13 +562,150 @@ pub fn read_text_file<T: AsPath + ?Sized>(path: &T) -> FsIOResult<String> {
 pub fn read_file<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Vec<u8>> {
     let file_path = path.as_path();
 
-    match read(&file_path) {
+    match read(file_path) {
         Ok(content) => Ok(content),
-        Err(error) => Err(FsIOError::IOError(
-            format!("Unable to read file: {:?}", &file_path).to_string(),
-            Some(error),
-        )),
+        Err(error) => Err(FsIOError::io(Operation::Read, &file_path, error)),
+    }
+}
+
+/// 校验和算法, 用于 `write_file_with_checksum` 和 `read_file_verified`
+pub trait ChecksumAlgorithm: Digest {
+    /// 校验和文件的扩展名(不包括 `.`), 例如 `sha256`
+    const EXTENSION: &'static str;
+}
+
+impl ChecksumAlgorithm for Md5 {
+    const EXTENSION: &'static str = "md5";
+}
+
+impl ChecksumAlgorithm for Sha1 {
+    const EXTENSION: &'static str = "sha1";
+}
+
+impl ChecksumAlgorithm for Sha256 {
+    const EXTENSION: &'static str = "sha256";
+}
+
+impl ChecksumAlgorithm for Sha512 {
+    const EXTENSION: &'static str = "sha512";
+}
+
+/// 返回文件的校验和文件路径, 例如 `file.tar.gz.sha256`
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+pub fn checksum_path<D: ChecksumAlgorithm, T: AsPath + ?Sized>(path: &T) -> PathBuf {
+    let mut checksum_path = path.as_path().as_os_str().to_os_string();
+    checksum_path.push(".");
+    checksum_path.push(D::EXTENSION);
+    PathBuf::from(checksum_path)
+}
+
+/// 原子地创建文件(如果存在则覆盖文件), 并在旁边写入校验和文件(例如 `file.bin.sha256`)
+///
+/// 校验和文件使用 `sha256sum` 等工具的格式(`<十六进制校验和>  <文件名>`).
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `data` - 文件内容
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use x_hash::Sha256;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_file_with_checksum/file.bin";
+///     let result = file::write_file_with_checksum::<Sha256, _>(file_path, "abc".as_bytes());
+///     assert!(result.is_ok());
+///
+///     let checksum = file::read_text_file("./target/__test/file_test/write_file_with_checksum/file.bin.sha256").unwrap();
+///     assert_eq!(checksum, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  file.bin\n");
+/// }
+/// ```
+pub fn write_file_with_checksum<D, T>(path: &T, data: &[u8]) -> FsIOResult<()>
+where
+    D: ChecksumAlgorithm,
+    T: AsPath + ?Sized,
+{
+    let file_path = path.as_path();
+    let file_name = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    write_file_atomic(file_path, data)?;
+
+    let checksum = format!("{}  {}\n", checksum_hex::<D>(data), file_name);
+    write_file_atomic(&checksum_path::<D, _>(file_path), checksum.as_bytes())
+}
+
+/// 读取文件内容, 并使用旁边的校验和文件(参见 `write_file_with_checksum`)验证,
+/// 不一致时返回 `FsIOError::ChecksumMismatch`
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use x_io::error::FsIOError;
+/// use x_hash::Sha256;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/read_file_verified/file.bin";
+///     file::write_file_with_checksum::<Sha256, _>(file_path, "some content".as_bytes()).unwrap();
+///
+///     let data = file::read_file_verified::<Sha256, _>(file_path).unwrap();
+///     assert_eq!(data, "some content".as_bytes());
+///
+///     // corrupt the file
+///     file::write_text_file(file_path, "some c0ntent").unwrap();
+///     let result = file::read_file_verified::<Sha256, _>(file_path);
+///     assert!(matches!(result, Err(FsIOError::ChecksumMismatch { .. })));
+/// }
+/// ```
+pub fn read_file_verified<D, T>(path: &T) -> FsIOResult<Vec<u8>>
+where
+    D: ChecksumAlgorithm,
+    T: AsPath + ?Sized,
+{
+    let file_path = path.as_path();
+    let checksum_file_path = checksum_path::<D, _>(file_path);
+
+    let checksum_text = read_text_file(&checksum_file_path)?;
+    let expected = match checksum_text.split_whitespace().next() {
+        Some(expected) => expected.to_ascii_lowercase(),
+        None => {
+            return Err(FsIOError::Parse(
+                format!("Checksum file: {:?} is empty.", &checksum_file_path).to_string(),
+                None,
+            ))
+        }
+    };
+
+    let data = read_file(file_path)?;
+    let actual = checksum_hex::<D>(&data);
+    if actual != expected {
+        return Err(FsIOError::ChecksumMismatch {
+            path: file_path.to_path_buf(),
+            expected,
+            actual,
+        });
     }
+
+    Ok(data)
+}
+
+fn checksum_hex<D: ChecksumAlgorithm>(data: &[u8]) -> String {
+    let mut hasher = D::new();
+    hasher.update(data);
+    DigestFmt(hasher.result()).to_string()
 }
 
 /// 删除文件, 如果文件不存在返回Ok
@@ -345,10 +742,7 @@ pub fn delete<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
         if file_path.is_file() {
             match remove_file(file_path) {
                 Ok(_) => Ok(()),
-                Err(error) => Err(FsIOError::IOError(
-                    format!("Unable to delete file: {:?}", &file_path).to_string(),
-                    Some(error),
-                )),
+                Err(error) => Err(FsIOError::io(Operation::Delete, &file_path, error)),
             }
         } else {
             Err(FsIOError::NotFile(
@@ -360,6 +754,38 @@ pub fn delete<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
     }
 }
 
+/// 与 `delete` 相同, 删除文件后将父级目录同步到磁盘(`directory::sync`), 崩溃后文件不会重新出现
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+///
+/// # 示例
+///
+/// ```
+/// use std::path::Path;
+/// use x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/delete_synced/app.lock";
+///     file::ensure_exists(file_path).unwrap();
+///
+///     file::delete_synced(file_path).unwrap();
+///     assert!(!Path::new(file_path).exists());
+/// }
+/// ```
+pub fn delete_synced<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
+    let file_path = path.as_path();
+    let existed = file_path.exists();
+
+    delete(file_path)?;
+
+    if existed {
+        directory::sync_parent(file_path)?;
+    }
+    Ok(())
+}
+
 /// 删除文件, 如果文件不存在返回true
 ///
 /// # 参数
@@ -388,8 +814,3922 @@ pub fn delete<T: AsPath + ?Sized>(path: &T) -> FsIOResult<()> {
 /// }
 /// ```
 pub fn delete_ignore_error<T: AsPath + ?Sized>(path: &T) -> bool {
-    match delete(path) {
-        Ok(_) => true,
-        Err(_) => false,
-    }
-}
\ No newline at end of file
+    delete(path).is_ok()
+}
+
+/// 阻塞直到文件存在, 超时返回 `FsIOError::TimedOut`
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `timeout` - 超时时间
+/// * `poll_interval` - 轮询间隔
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::thread;
+/// use std::time::Duration;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/wait_for/file.txt";
+///     file::delete(file_path).unwrap();
+///
+///     let writer = thread::spawn(move || {
+///         thread::sleep(Duration::from_millis(50));
+///         file::write_text_file(file_path, "some content").unwrap();
+///     });
+///
+///     let result = file::wait_for(file_path, Duration::from_secs(5), Duration::from_millis(10));
+///     assert!(result.is_ok());
+///     writer.join().unwrap();
+///
+///     let result = file::wait_for("./target/__test/file_test/wait_for/missing.txt", Duration::from_millis(50), Duration::from_millis(10));
+///     assert!(result.unwrap_err().is_timed_out());
+/// }
+/// ```
+pub fn wait_for<T: AsPath + ?Sized>(
+    path: &T,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> FsIOResult<()> {
+    wait_until(path, &|_: &Metadata| true, timeout, poll_interval)
+}
+
+/// 阻塞直到文件存在并且满足提供的条件, 超时返回 `FsIOError::TimedOut`
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `condition` - 文件元数据的判断条件
+/// * `timeout` - 超时时间
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::time::Duration;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/wait_for_condition/file.txt";
+///     file::write_text_file(file_path, "some content").unwrap();
+///
+///     let result = file::wait_for_condition(file_path, |metadata| metadata.len() > 0, Duration::from_secs(5));
+///     assert!(result.is_ok());
+///
+///     let result = file::wait_for_condition(file_path, |metadata| metadata.len() > 1024, Duration::from_millis(50));
+///     assert!(result.is_err());
+///
+///     // `Duration::MAX` waits without a deadline
+///     let result = file::wait_for_condition(file_path, |metadata| metadata.len() > 0, Duration::MAX);
+///     assert!(result.is_ok());
+/// }
+/// ```
+pub fn wait_for_condition<T, F>(path: &T, condition: F, timeout: Duration) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    F: Fn(&Metadata) -> bool,
+{
+    wait_until(path, &condition, timeout, WAIT_POLL_INTERVAL)
+}
+
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+fn wait_until<T: AsPath + ?Sized>(
+    path: &T,
+    condition: &dyn Fn(&Metadata) -> bool,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> FsIOResult<()> {
+    let file_path = path.as_path();
+    // no deadline when the timeout is too large to represent (`Duration::MAX` waits forever)
+    let deadline = Instant::now().checked_add(timeout);
+
+    loop {
+        match metadata(file_path) {
+            Ok(file_metadata) => {
+                if condition(&file_metadata) {
+                    return Ok(());
+                }
+            }
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(FsIOError::io(Operation::Metadata, &file_path, error)),
+        }
+
+        let sleep = match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return Err(FsIOError::TimedOut(timeout));
+                }
+                poll_interval.min(deadline - now)
+            }
+            None => poll_interval,
+        };
+        thread::sleep(sleep);
+    }
+}
+
+/// 读取gzip压缩文件并返回解压后的内容
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/read_gzip/file.txt.gz";
+///     let result = file::write_gzip(file_path, "some content".as_bytes(), 6);
+///     assert!(result.is_ok());
+///
+///     let data = file::read_gzip(file_path).unwrap();
+///
+///     assert_eq!(data, "some content".as_bytes());
+/// }
+/// ```
+#[cfg(feature = "compress")]
+pub fn read_gzip<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Vec<u8>> {
+    let mut data = vec![];
+    read_gzip_with(path, &mut |reader: &mut dyn io::Read| {
+        reader.read_to_end(&mut data).map(|_| ())
+    })?;
+    Ok(data)
+}
+
+/// 打开gzip压缩文件, 并触发提供的 read_content 函数以启用自定义(流式)读取
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `read_content` - 自定义读取方法, 读取的是解压后的内容
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::io::{BufRead, BufReader, Read};
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/read_gzip_with/file.txt.gz";
+///     file::write_gzip(file_path, "line 1\nline 2\n".as_bytes(), 6).unwrap();
+///
+///     let mut count = 0;
+///     let result = file::read_gzip_with(file_path, &mut |reader: &mut dyn Read| {
+///         count = BufReader::new(reader).lines().count();
+///         Ok(())
+///     });
+///     assert!(result.is_ok());
+///
+///     assert_eq!(count, 2);
+/// }
+/// ```
+#[cfg(feature = "compress")]
+pub fn read_gzip_with<T: AsPath + ?Sized>(
+    path: &T,
+    read_content: &mut dyn FnMut(&mut dyn io::Read) -> io::Result<()>,
+) -> FsIOResult<()> {
+    let file_path = path.as_path();
+
+    match File::open(file_path) {
+        Ok(fd) => {
+            let mut decoder = flate2::read::MultiGzDecoder::new(io::BufReader::new(fd));
+            match read_content(&mut decoder) {
+                Ok(_) => Ok(()),
+                Err(error) => Err(FsIOError::io(Operation::Read, &file_path, error)),
+            }
+        }
+        Err(error) => Err(FsIOError::io(Operation::Read, &file_path, error)),
+    }
+}
+
+/// 创建gzip压缩文件, 如果存在则覆盖文件
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `data` - 原始数据
+/// * `level` - 压缩级别(0-9)
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_gzip/file.txt.gz";
+///     let result = file::write_gzip(file_path, "some content".as_bytes(), 9);
+///     assert!(result.is_ok());
+///
+///     let data = file::read_gzip(file_path).unwrap();
+///
+///     assert_eq!(data, "some content".as_bytes());
+/// }
+/// ```
+#[cfg(feature = "compress")]
+pub fn write_gzip<T: AsPath + ?Sized>(path: &T, data: &[u8], level: u32) -> FsIOResult<()> {
+    write_gzip_with(
+        path,
+        &move |writer: &mut dyn Write| writer.write_all(data),
+        level,
+    )
+}
+
+/// 创建gzip压缩文件, 并触发提供的 write_content 函数以启用自定义(流式)写入
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `write_content` - 自定义写入方法, 写入的内容会被压缩
+/// * `level` - 压缩级别(0-9)
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::io::Write;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_gzip_with/file.txt.gz";
+///     let result = file::write_gzip_with(
+///         file_path,
+///         &move |writer: &mut dyn Write| {
+///             writer.write_all("some content".as_bytes())?;
+///             writer.write_all("\nmore content".as_bytes())
+///         },
+///         6,
+///     );
+///     assert!(result.is_ok());
+///
+///     let data = file::read_gzip(file_path).unwrap();
+///
+///     assert_eq!(data, "some content\nmore content".as_bytes());
+/// }
+/// ```
+#[cfg(feature = "compress")]
+pub fn write_gzip_with<T: AsPath + ?Sized>(
+    path: &T,
+    write_content: &dyn Fn(&mut dyn Write) -> io::Result<()>,
+    level: u32,
+) -> FsIOResult<()> {
+    modify_file(
+        path,
+        &move |file: &mut File| {
+            let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::new(level));
+            write_content(&mut encoder)?;
+            encoder.finish().map(|_| ())
+        },
+        false,
+    )
+}
+
+/// 读取JSON文件并反序列化
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::collections::HashMap;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/read_json/file.json";
+///     file::write_text_file(file_path, r#"{"name": "x-io"}"#).unwrap();
+///
+///     let value: HashMap<String, String> = file::read_json(file_path).unwrap();
+///     assert_eq!(value["name"], "x-io");
+///
+///     file::write_text_file(file_path, "{").unwrap();
+///     let result: Result<HashMap<String, String>, _> = file::read_json(file_path);
+///     assert!(result.is_err());
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn read_json<T: AsPath + ?Sized, V: serde::de::DeserializeOwned>(path: &T) -> FsIOResult<V> {
+    let text = read_text_file(path)?;
+
+    match serde_json::from_str(&text) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(FsIOError::Parse(
+            format!("Unable to parse JSON file: {:?}", path.as_path()).to_string(),
+            Some(Box::new(error)),
+        )),
+    }
+}
+
+/// 序列化为JSON并写入文件, 如果存在则覆盖文件
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `value` - 值
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::collections::HashMap;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_json/file.json";
+///     let value = HashMap::from([("name".to_string(), "x-io".to_string())]);
+///     let result = file::write_json(file_path, &value);
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file(file_path).unwrap();
+///
+///     assert_eq!(text, r#"{"name":"x-io"}"#);
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn write_json<T, V>(path: &T, value: &V) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    V: serde::Serialize + ?Sized,
+{
+    match serde_json::to_string(value) {
+        Ok(text) => write_text_file(path, &text),
+        Err(error) => Err(FsIOError::Parse(
+            format!("Unable to serialize JSON for file: {:?}", path.as_path()).to_string(),
+            Some(Box::new(error)),
+        )),
+    }
+}
+
+/// 序列化为格式化的JSON并写入文件, 如果存在则覆盖文件
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `value` - 值
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::collections::HashMap;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_json_pretty/file.json";
+///     let value = HashMap::from([("name".to_string(), "x-io".to_string())]);
+///     let result = file::write_json_pretty(file_path, &value);
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file(file_path).unwrap();
+///
+///     assert_eq!(text, "{\n  \"name\": \"x-io\"\n}");
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn write_json_pretty<T, V>(path: &T, value: &V) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    V: serde::Serialize + ?Sized,
+{
+    match serde_json::to_string_pretty(value) {
+        Ok(text) => write_text_file(path, &text),
+        Err(error) => Err(FsIOError::Parse(
+            format!("Unable to serialize JSON for file: {:?}", path.as_path()).to_string(),
+            Some(Box::new(error)),
+        )),
+    }
+}
+
+/// 读取TOML文件并反序列化
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::collections::HashMap;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/read_toml/file.toml";
+///     file::write_text_file(file_path, "name = \"x-io\"").unwrap();
+///
+///     let value: HashMap<String, String> = file::read_toml(file_path).unwrap();
+///     assert_eq!(value["name"], "x-io");
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn read_toml<T: AsPath + ?Sized, V: serde::de::DeserializeOwned>(path: &T) -> FsIOResult<V> {
+    let text = read_text_file(path)?;
+
+    match toml::from_str(&text) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(FsIOError::Parse(
+            format!("Unable to parse TOML file: {:?}", path.as_path()).to_string(),
+            Some(Box::new(error)),
+        )),
+    }
+}
+
+/// 序列化为TOML并写入文件, 如果存在则覆盖文件
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `value` - 值
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::collections::HashMap;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_toml/file.toml";
+///     let value = HashMap::from([("name".to_string(), "x-io".to_string())]);
+///     let result = file::write_toml(file_path, &value);
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file(file_path).unwrap();
+///
+///     assert_eq!(text, "name = \"x-io\"\n");
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn write_toml<T, V>(path: &T, value: &V) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    V: serde::Serialize + ?Sized,
+{
+    match toml::to_string_pretty(value) {
+        Ok(text) => write_text_file(path, &text),
+        Err(error) => Err(FsIOError::Parse(
+            format!("Unable to serialize TOML for file: {:?}", path.as_path()).to_string(),
+            Some(Box::new(error)),
+        )),
+    }
+}
+
+/// 读取YAML文件并反序列化
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::collections::HashMap;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/read_yaml/file.yaml";
+///     file::write_text_file(file_path, "name: x-io").unwrap();
+///
+///     let value: HashMap<String, String> = file::read_yaml(file_path).unwrap();
+///     assert_eq!(value["name"], "x-io");
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn read_yaml<T: AsPath + ?Sized, V: serde::de::DeserializeOwned>(path: &T) -> FsIOResult<V> {
+    let text = read_text_file(path)?;
+
+    match serde_yaml::from_str(&text) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(FsIOError::Parse(
+            format!("Unable to parse YAML file: {:?}", path.as_path()).to_string(),
+            Some(Box::new(error)),
+        )),
+    }
+}
+
+/// 序列化为YAML并写入文件, 如果存在则覆盖文件
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `value` - 值
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::collections::HashMap;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_yaml/file.yaml";
+///     let value = HashMap::from([("name".to_string(), "x-io".to_string())]);
+///     let result = file::write_yaml(file_path, &value);
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file(file_path).unwrap();
+///
+///     assert_eq!(text, "name: x-io\n");
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub fn write_yaml<T, V>(path: &T, value: &V) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    V: serde::Serialize + ?Sized,
+{
+    match serde_yaml::to_string(value) {
+        Ok(text) => write_text_file(path, &text),
+        Err(error) => Err(FsIOError::Parse(
+            format!("Unable to serialize YAML for file: {:?}", path.as_path()).to_string(),
+            Some(Box::new(error)),
+        )),
+    }
+}
+
+/// CSV选项
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// 字段分隔符
+    pub delimiter: char,
+    /// 引号字符
+    pub quote: char,
+    /// 表头, 写入时作为首行写入, 读取时首行必须与表头相同(不传给行处理方法), 否则返回 `FsIOError::Parse`
+    pub headers: Option<Vec<String>>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            quote: '"',
+            headers: None,
+        }
+    }
+}
+
+/// 读取CSV文件的所有行
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/read_csv/file.csv";
+///     file::write_text_file(file_path, "a,b\n\"c,1\",\"d \"\"2\"\"\"\n").unwrap();
+///
+///     let rows = file::read_csv(file_path).unwrap();
+///
+///     assert_eq!(rows, vec![vec!["a", "b"], vec!["c,1", "d \"2\""]]);
+/// }
+/// ```
+pub fn read_csv<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Vec<Vec<String>>> {
+    let mut rows = vec![];
+    read_csv_with(path, &CsvOptions::default(), &mut |row: Vec<String>| {
+        rows.push(row);
+        Ok(())
+    })?;
+    Ok(rows)
+}
+
+/// 逐行读取CSV文件, 每读取一行触发提供的 handle_row 函数, 函数返回错误时停止读取
+///
+/// 设置了 `CsvOptions::headers` 时首行是表头, 与表头不同时返回 `FsIOError::Parse`(避免把数据行当作表头丢弃).
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `options` - CSV选项
+/// * `handle_row` - 行处理方法
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use x_io::file::CsvOptions;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/read_csv_with/file.csv";
+///     file::write_text_file(file_path, "name;size\na;1\nb;2\n").unwrap();
+///
+///     let options = CsvOptions {
+///         delimiter: ';',
+///         headers: Some(vec!["name".to_string(), "size".to_string()]),
+///         ..CsvOptions::default()
+///     };
+///     let mut total = 0;
+///     let result = file::read_csv_with(file_path, &options, &mut |row: Vec<String>| {
+///         total += row[1].parse::<u32>().unwrap();
+///         Ok(())
+///     });
+///     assert!(result.is_ok());
+///
+///     assert_eq!(total, 3);
+///
+///     // a file without the header row is rejected instead of losing its first row
+///     file::write_text_file(file_path, "a;1\nb;2\n").unwrap();
+///     let result = file::read_csv_with(file_path, &options, &mut |_: Vec<String>| Ok(()));
+///     assert!(result.is_err());
+/// }
+/// ```
+pub fn read_csv_with<T: AsPath + ?Sized>(
+    path: &T,
+    options: &CsvOptions,
+    handle_row: &mut dyn FnMut(Vec<String>) -> FsIOResult<()>,
+) -> FsIOResult<()> {
+    let file_path = path.as_path();
+
+    let mut reader = match File::open(file_path) {
+        Ok(fd) => BufReader::new(fd),
+        Err(error) => return Err(FsIOError::io(Operation::Read, &file_path, error)),
+    };
+
+    let mut skip_header = options.headers.is_some();
+    let mut line = String::new();
+    let mut row = vec![];
+    let mut field = String::new();
+    let mut in_quotes = false;
+
+    loop {
+        line.clear();
+        let size = match reader.read_line(&mut line) {
+            Ok(size) => size,
+            Err(error) => return Err(FsIOError::io(Operation::Read, &file_path, error)),
+        };
+
+        if size == 0 {
+            if in_quotes {
+                return Err(FsIOError::Parse(
+                    format!("Unterminated quoted field in CSV file: {:?}", &file_path).to_string(),
+                    None,
+                ));
+            }
+            if !field.is_empty() || !row.is_empty() {
+                row.push(std::mem::take(&mut field));
+                if skip_header {
+                    check_csv_header(file_path, options, &row)?;
+                } else {
+                    handle_row(std::mem::take(&mut row))?;
+                }
+            }
+            return Ok(());
+        }
+
+        // empty lines outside quoted fields are skipped
+        if !in_quotes && line.trim_end_matches(['\r', '\n']).is_empty() {
+            continue;
+        }
+
+        let mut chars = line.chars().peekable();
+        while let Some(value) = chars.next() {
+            if in_quotes {
+                if value == options.quote {
+                    if chars.peek() == Some(&options.quote) {
+                        field.push(value);
+                        chars.next();
+                    } else {
+                        in_quotes = false;
+                    }
+                } else {
+                    field.push(value);
+                }
+            } else if value == options.quote {
+                in_quotes = true;
+            } else if value == options.delimiter {
+                row.push(std::mem::take(&mut field));
+            } else if value == '\r' && chars.peek() == Some(&'\n') {
+                // handled by the line feed
+            } else if value == '\n' {
+                row.push(std::mem::take(&mut field));
+                if skip_header {
+                    check_csv_header(file_path, options, &row)?;
+                    skip_header = false;
+                    row.clear();
+                } else {
+                    handle_row(std::mem::take(&mut row))?;
+                }
+            } else {
+                field.push(value);
+            }
+        }
+    }
+}
+
+/// 检查CSV文件的首行是否与选项中的表头相同
+fn check_csv_header(file_path: &Path, options: &CsvOptions, row: &[String]) -> FsIOResult<()> {
+    match options.headers {
+        Some(ref headers) if headers.as_slice() != row => Err(FsIOError::Parse(
+            format!(
+                "CSV header row: {:?} does not match the expected headers: {:?} in file: {:?}",
+                row, headers, &file_path
+            )
+            .to_string(),
+            None,
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 创建CSV文件, 如果存在则覆盖文件
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `rows` - 所有行
+/// * `options` - CSV选项
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use x_io::file::CsvOptions;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_csv/file.csv";
+///     let options = CsvOptions {
+///         headers: Some(vec!["name".to_string(), "note".to_string()]),
+///         ..CsvOptions::default()
+///     };
+///     let result = file::write_csv(file_path, &[["a", "1,2"], ["b", "say \"hi\""]], &options);
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file(file_path).unwrap();
+///
+///     assert_eq!(text, "name,note\na,\"1,2\"\nb,\"say \"\"hi\"\"\"\n");
+/// }
+/// ```
+pub fn write_csv<T, R, S>(path: &T, rows: &[R], options: &CsvOptions) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    R: AsRef<[S]>,
+    S: AsRef<str>,
+{
+    let mut text = String::new();
+
+    if let Some(ref headers) = options.headers {
+        append_csv_row(&mut text, headers, options);
+    }
+    for row in rows {
+        append_csv_row(&mut text, row.as_ref(), options);
+    }
+
+    write_text_file(path, &text)
+}
+
+fn append_csv_row<S: AsRef<str>>(text: &mut String, row: &[S], options: &CsvOptions) {
+    for (index, value) in row.iter().enumerate() {
+        if index > 0 {
+            text.push(options.delimiter);
+        }
+
+        let value = value.as_ref();
+        let needs_quotes = value.contains(options.delimiter)
+            || value.contains(options.quote)
+            || value.contains('\n')
+            || value.contains('\r');
+        if needs_quotes {
+            text.push(options.quote);
+            for character in value.chars() {
+                if character == options.quote {
+                    text.push(options.quote);
+                }
+                text.push(character);
+            }
+            text.push(options.quote);
+        } else {
+            text.push_str(value);
+        }
+    }
+    text.push('\n');
+}
+
+/// 复制文件, 文件系统支持时使用写时复制克隆(btrfs/XFS上的FICLONE, APFS上的clonefile),
+/// 不支持时回退为普通复制. 如果目标存在则覆盖文件
+///
+/// 返回true表示使用了写时复制克隆, false表示使用了普通复制.
+///
+/// # 参数
+///
+/// * `src` - 源文件路径
+/// * `dst` - 目标文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/clone_cow/file.txt";
+///     file::write_text_file(file_path, "some content").unwrap();
+///
+///     let result = file::clone_cow(file_path, "./target/__test/file_test/clone_cow/clone.txt");
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file("./target/__test/file_test/clone_cow/clone.txt").unwrap();
+///     assert_eq!(text, "some content");
+///
+///     let result = file::clone_cow(file_path, file_path);
+///     assert!(result.is_err());
+///     assert_eq!(file::read_text_file(file_path).unwrap(), "some content");
+/// }
+/// ```
+pub fn clone_cow<S: AsPath + ?Sized, T: AsPath + ?Sized>(src: &S, dst: &T) -> FsIOResult<bool> {
+    let source_path = src.as_path();
+    let target_path = dst.as_path();
+
+    if !source_path.is_file() {
+        return Err(FsIOError::NotFile(
+            format!("Path: {:?} is not a file.", &source_path).to_string(),
+        ));
+    }
+
+    if is_same_file(source_path, target_path).unwrap_or(false) {
+        return Err(FsIOError::io(
+            Operation::Copy(target_path.to_path_buf()),
+            &source_path,
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "source and destination are the same file",
+            ),
+        ));
+    }
+
+    directory::create_parent(dst)?;
+
+    if reflink(source_path, target_path).is_ok() {
+        return Ok(true);
+    }
+
+    match copy(source_path, target_path) {
+        Ok(_) => Ok(false),
+        Err(error) => Err(FsIOError::io(
+            Operation::Copy(target_path.to_path_buf()),
+            &source_path,
+            error,
+        )),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let source = File::open(src)?;
+
+    // the clone is created next to the target and renamed into place, so a failed
+    // clone never truncates an existing target
+    let temp_path = unique_sibling(dst, "clone");
+    let target = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&temp_path)?;
+
+    let result = unsafe { libc::ioctl(target.as_raw_fd(), libc::FICLONE, source.as_raw_fd()) };
+    let result = if result == -1 {
+        Err(io::Error::last_os_error())
+    } else {
+        source
+            .metadata()
+            .and_then(|metadata| target.set_permissions(metadata.permissions()))
+            .and_then(|_| fs::rename(&temp_path, dst))
+    };
+    if result.is_err() {
+        let _ = remove_file(&temp_path);
+    }
+    result
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    // clonefile does not replace an existing path, the clone is created next to the
+    // target and renamed into place like on linux
+    let temp_path = unique_sibling(dst, "clone");
+    let source = CString::new(src.as_os_str().as_bytes())?;
+    let target = CString::new(temp_path.as_os_str().as_bytes())?;
+
+    let result = unsafe { libc::clonefile(source.as_ptr(), target.as_ptr(), 0) };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let result = fs::rename(&temp_path, dst);
+    if result.is_err() {
+        let _ = remove_file(&temp_path);
+    }
+    result
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_src: &Path, _dst: &Path) -> io::Result<()> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
+
+/// 复制文件并保留空洞(稀疏文件), 只写入有数据的区域, 如果目标存在则覆盖文件
+///
+/// 支持SEEK_DATA/SEEK_HOLE的系统直接跳过空洞, 其他系统跳过全为0的数据块.
+///
+/// # 参数
+///
+/// * `src` - 源文件路径
+/// * `dst` - 目标文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use std::fs::OpenOptions;
+/// use std::io::{Seek, SeekFrom, Write};
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/copy_sparse/image.img";
+///     file::write_text_file(file_path, "header").unwrap();
+///     let mut image = OpenOptions::new().write(true).open(file_path).unwrap();
+///     image.seek(SeekFrom::Start(1024 * 1024)).unwrap();
+///     image.write_all("footer".as_bytes()).unwrap();
+///
+///     let result = file::copy_sparse(file_path, "./target/__test/file_test/copy_sparse/copy.img");
+///     assert!(result.is_ok());
+///
+///     let source = file::read_file(file_path).unwrap();
+///     let copy = file::read_file("./target/__test/file_test/copy_sparse/copy.img").unwrap();
+///     assert_eq!(source, copy);
+///
+///     let result = file::copy_sparse(file_path, file_path);
+///     assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+///     assert_eq!(file::read_file(file_path).unwrap(), source);
+/// }
+/// ```
+pub fn copy_sparse<S: AsPath + ?Sized, T: AsPath + ?Sized>(src: &S, dst: &T) -> FsIOResult<()> {
+    let source_path = src.as_path();
+    let target_path = dst.as_path();
+
+    if !source_path.is_file() {
+        return Err(FsIOError::NotFile(
+            format!("Path: {:?} is not a file.", &source_path).to_string(),
+        ));
+    }
+
+    if is_same_file(source_path, target_path).unwrap_or(false) {
+        return Err(FsIOError::io(
+            Operation::Copy(target_path.to_path_buf()),
+            &source_path,
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "source and destination are the same file",
+            ),
+        ));
+    }
+
+    directory::create_parent(dst)?;
+
+    let result = (|| -> io::Result<()> {
+        let mut source = File::open(source_path)?;
+        let mut target = File::create(target_path)?;
+        let source_metadata = source.metadata()?;
+        let length = source_metadata.len();
+
+        // the whole target starts as a hole, only data regions are written
+        target.set_len(length)?;
+        for (start, end) in data_regions(&source, length) {
+            copy_region(&mut source, &mut target, start, end)?;
+        }
+
+        target.set_permissions(source_metadata.permissions())?;
+        target.sync_all()
+    })();
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(
+            Operation::Copy(target_path.to_path_buf()),
+            &source_path,
+            error,
+        )),
+    }
+}
+
+/// 限制带宽复制文件, 如果目标存在则覆盖文件, 返回复制的字节数
+///
+/// 多个文件共用同一个 `Throttle` 时共享带宽限制.
+///
+/// # 参数
+///
+/// * `src` - 源文件路径
+/// * `dst` - 目标文件路径
+/// * `throttle` - 带宽限制
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use x_io::throttle::Throttle;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/copy_throttled/backup.bin";
+///     file::write_file(file_path, &vec![7u8; 64 * 1024]).unwrap();
+///
+///     let mut throttle = Throttle::new(1024 * 1024);
+///     let bytes = file::copy_throttled(file_path, "./target/__test/file_test/copy_throttled/copy.bin", &mut throttle).unwrap();
+///     assert_eq!(bytes, 64 * 1024);
+///
+///     let copy = file::read_file("./target/__test/file_test/copy_throttled/copy.bin").unwrap();
+///     assert_eq!(copy, vec![7u8; 64 * 1024]);
+///
+///     let result = file::copy_throttled(file_path, file_path, &mut throttle);
+///     assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+///     assert_eq!(file::read_file(file_path).unwrap().len(), 64 * 1024);
+/// }
+/// ```
+pub fn copy_throttled<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    src: &S,
+    dst: &T,
+    throttle: &mut Throttle,
+) -> FsIOResult<u64> {
+    let source_path = src.as_path();
+    let target_path = dst.as_path();
+
+    if !source_path.is_file() {
+        return Err(FsIOError::NotFile(
+            format!("Path: {:?} is not a file.", &source_path).to_string(),
+        ));
+    }
+
+    directory::create_parent(dst)?;
+
+    match copy_with_throttle(source_path, target_path, throttle) {
+        Ok(bytes) => Ok(bytes),
+        Err(error) => Err(FsIOError::io(
+            Operation::Copy(target_path.to_path_buf()),
+            &source_path,
+            error,
+        )),
+    }
+}
+
+/// 限制带宽复制文件内容和权限, 源文件和目标文件是同一个文件时返回 `InvalidInput` 错误
+pub(crate) fn copy_with_throttle(
+    src: &Path,
+    dst: &Path,
+    throttle: &mut Throttle,
+) -> io::Result<u64> {
+    // creating the target truncates it before the source is read
+    if is_same_file(src, dst).unwrap_or(false) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "source and destination are the same file",
+        ));
+    }
+
+    let source = File::open(src)?;
+    let permissions = source.metadata()?.permissions();
+    let mut target = File::create(dst)?;
+
+    let bytes = io::copy(&mut throttle.reader(source), &mut target)?;
+    target.set_permissions(permissions)?;
+    Ok(bytes)
+}
+
+const SPARSE_BLOCK_SIZE: usize = 64 * 1024;
+
+fn copy_region(source: &mut File, target: &mut File, start: u64, end: u64) -> io::Result<()> {
+    source.seek(SeekFrom::Start(start))?;
+    target.seek(SeekFrom::Start(start))?;
+
+    let mut buffer = vec![0u8; SPARSE_BLOCK_SIZE];
+    let mut remaining = end - start;
+    while remaining > 0 {
+        let size = remaining.min(SPARSE_BLOCK_SIZE as u64) as usize;
+        source.read_exact(&mut buffer[..size])?;
+
+        if buffer[..size].iter().all(|value| *value == 0) {
+            target.seek(SeekFrom::Current(size as i64))?;
+        } else {
+            target.write_all(&buffer[..size])?;
+        }
+        remaining -= size as u64;
+    }
+
+    Ok(())
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "freebsd"
+))]
+fn data_regions(source: &File, length: u64) -> Vec<(u64, u64)> {
+    use std::os::unix::io::AsRawFd;
+
+    let fd = source.as_raw_fd();
+    let mut regions = vec![];
+    let mut position = 0u64;
+
+    while position < length {
+        let start = unsafe { libc::lseek(fd, position as libc::off_t, libc::SEEK_DATA) };
+        if start < 0 {
+            if io::Error::last_os_error().raw_os_error() == Some(libc::ENXIO) {
+                // no more data after position
+                break;
+            }
+            return vec![(0, length)];
+        }
+
+        let end = unsafe { libc::lseek(fd, start, libc::SEEK_HOLE) };
+        if end < 0 {
+            return vec![(0, length)];
+        }
+
+        regions.push((start as u64, (end as u64).min(length)));
+        position = end as u64;
+    }
+
+    regions
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "freebsd"
+)))]
+fn data_regions(_source: &File, length: u64) -> Vec<(u64, u64)> {
+    vec![(0, length)]
+}
+
+/// 十六进制转储选项
+#[derive(Debug, Clone, Copy)]
+pub struct HexDumpOptions {
+    /// 每行字节数(0按16处理)
+    pub width: usize,
+    /// 开始转储的文件偏移
+    pub offset: u64,
+    /// 最多转储的字节数, None表示直到文件结束
+    pub limit: Option<u64>,
+}
+
+impl Default for HexDumpOptions {
+    fn default() -> Self {
+        HexDumpOptions {
+            width: 16,
+            offset: 0,
+            limit: None,
+        }
+    }
+}
+
+/// 返回文件内容的十六进制转储(与 `xxd` 相同的格式)
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `options` - 转储选项
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use x_io::file::HexDumpOptions;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/hex_dump/file.bin";
+///     file::write_text_file(file_path, "Hello, world!\nsecond line\n").unwrap();
+///
+///     let dump = file::hex_dump(file_path, HexDumpOptions::default()).unwrap();
+///     assert_eq!(
+///         dump,
+///         "00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 7365  Hello, world!.se\n\
+///          00000010: 636f 6e64 206c 696e 650a                 cond line.\n"
+///     );
+///
+///     let options = HexDumpOptions { width: 4, offset: 7, limit: Some(5) };
+///     let dump = file::hex_dump(file_path, options).unwrap();
+///     assert_eq!(dump, "00000007: 776f 726c  worl\n0000000b: 64         d\n");
+/// }
+/// ```
+pub fn hex_dump<T: AsPath + ?Sized>(path: &T, options: HexDumpOptions) -> FsIOResult<String> {
+    let mut output = vec![];
+    hex_dump_to(path, &mut output, options)?;
+    Ok(String::from_utf8_lossy(&output).into_owned())
+}
+
+/// 将文件内容的十六进制转储(与 `xxd` 相同的格式)逐行写入writer, 适合较大的文件
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `writer` - 输出
+/// * `options` - 转储选项
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use x_io::file::HexDumpOptions;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/hex_dump_to/file.bin";
+///     file::write_file(file_path, &[0, 1, 2, 255]).unwrap();
+///
+///     let mut output = vec![];
+///     file::hex_dump_to(file_path, &mut output, HexDumpOptions::default()).unwrap();
+///     assert!(String::from_utf8(output).unwrap().starts_with("00000000: 0001 02ff"));
+/// }
+/// ```
+pub fn hex_dump_to<T, W>(path: &T, writer: &mut W, options: HexDumpOptions) -> FsIOResult<()>
+where
+    T: AsPath + ?Sized,
+    W: Write + ?Sized,
+{
+    let file_path = path.as_path();
+    let width = if options.width == 0 {
+        16
+    } else {
+        options.width
+    };
+
+    let mut file = match File::open(file_path) {
+        Ok(file) => file,
+        Err(error) => return Err(FsIOError::io(Operation::Read, &file_path, error)),
+    };
+    if options.offset > 0 {
+        if let Err(error) = file.seek(SeekFrom::Start(options.offset)) {
+            return Err(FsIOError::io(Operation::Read, &file_path, error));
+        }
+    }
+    let mut reader = BufReader::new(file).take(options.limit.unwrap_or(u64::MAX));
+
+    // hex digits plus a space after every 2 bytes
+    let hex_width = width * 2 + (width - 1) / 2;
+    let mut line = vec![0u8; width];
+    let mut offset = options.offset;
+    loop {
+        let mut size = 0;
+        while size < width {
+            match reader.read(&mut line[size..]) {
+                Ok(0) => break,
+                Ok(read_size) => size += read_size,
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(FsIOError::io(Operation::Read, &file_path, error)),
+            }
+        }
+        if size == 0 {
+            break;
+        }
+
+        let mut hex = String::with_capacity(hex_width);
+        for (index, byte) in line[..size].iter().enumerate() {
+            if index > 0 && index % 2 == 0 {
+                hex.push(' ');
+            }
+            hex.push_str(&format!("{:02x}", byte));
+        }
+        let text: String = line[..size]
+            .iter()
+            .map(|byte| {
+                if byte.is_ascii_graphic() || *byte == b' ' {
+                    *byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+
+        let result = writeln!(
+            writer,
+            "{:08x}: {:<width$}  {}",
+            offset,
+            hex,
+            text,
+            width = hex_width
+        );
+        if let Err(error) = result {
+            return Err(FsIOError::io(Operation::HexDump, &file_path, error));
+        }
+
+        offset += size as u64;
+        if size < width {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// 二进制差异文件的魔术字节
+const DIFF_MAGIC: &[u8; 8] = b"XIODIFF1";
+
+/// 二进制差异匹配的块大小
+const DIFF_BLOCK_SIZE: usize = 64;
+
+/// 差异指令: 复制旧文件的一段(偏移u64, 长度u32)
+const DIFF_COPY: u8 = 1;
+
+/// 差异指令: 插入新数据(长度u32, 数据)
+const DIFF_DATA: u8 = 2;
+
+/// 计算从旧文件到新文件的二进制差异(补丁), 使用 `binary_patch` 应用
+///
+/// 补丁格式(整数为小端序):
+///
+/// * `XIODIFF1` - 魔术字节
+/// * `u64` - 新文件长度, `[u8; 32]` - 新文件的SHA-256
+/// * 指令序列: `1, u64 偏移, u32 长度` 复制旧文件的一段, `2, u32 长度, 数据` 插入新数据
+///
+/// # 参数
+///
+/// * `old` - 旧文件路径
+/// * `new` - 新文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+///
+/// fn main() {
+///     let old_content = "some content ".repeat(100);
+///     let new_content = format!("header\n{}footer\n", old_content);
+///     file::write_text_file("./target/__test/file_test/binary_diff/old.bin", &old_content).unwrap();
+///     file::write_text_file("./target/__test/file_test/binary_diff/new.bin", &new_content).unwrap();
+///
+///     let patch = file::binary_diff("./target/__test/file_test/binary_diff/old.bin", "./target/__test/file_test/binary_diff/new.bin").unwrap();
+///     assert!(patch.len() < new_content.len());
+/// }
+/// ```
+pub fn binary_diff<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    old: &S,
+    new: &T,
+) -> FsIOResult<Vec<u8>> {
+    let old_data = read_file(old)?;
+    let new_data = read_file(new)?;
+
+    let mut blocks: HashMap<&[u8], usize> = HashMap::new();
+    for (index, block) in old_data.chunks_exact(DIFF_BLOCK_SIZE).enumerate() {
+        blocks.entry(block).or_insert(index * DIFF_BLOCK_SIZE);
+    }
+
+    let mut patch = vec![];
+    patch.extend_from_slice(DIFF_MAGIC);
+    patch.extend_from_slice(&(new_data.len() as u64).to_le_bytes());
+    patch.extend_from_slice(&sha256(&new_data));
+
+    let mut literal_start = 0;
+    let mut position = 0;
+    while position < new_data.len() {
+        let old_offset = match new_data.get(position..position + DIFF_BLOCK_SIZE) {
+            Some(block) => blocks.get(block).copied(),
+            None => None,
+        };
+
+        match old_offset {
+            Some(old_offset) => {
+                let mut length = DIFF_BLOCK_SIZE;
+                while position + length < new_data.len()
+                    && old_offset + length < old_data.len()
+                    && length < u32::MAX as usize
+                    && new_data[position + length] == old_data[old_offset + length]
+                {
+                    length += 1;
+                }
+
+                push_diff_data(&mut patch, &new_data[literal_start..position]);
+                patch.push(DIFF_COPY);
+                patch.extend_from_slice(&(old_offset as u64).to_le_bytes());
+                patch.extend_from_slice(&(length as u32).to_le_bytes());
+
+                position += length;
+                literal_start = position;
+            }
+            None => position += 1,
+        }
+    }
+    push_diff_data(&mut patch, &new_data[literal_start..]);
+
+    Ok(patch)
+}
+
+/// 将 `binary_diff` 生成的补丁应用到旧文件, 将结果原子地写入输出文件
+///
+/// 补丁无效时返回 `FsIOError::Parse`, 结果与补丁记录的SHA-256不一致(例如旧文件不同)时返回
+/// `FsIOError::ChecksumMismatch`, 此时不会写入输出文件.
+///
+/// # 参数
+///
+/// * `old` - 旧文件路径
+/// * `patch` - 补丁文件路径
+/// * `out` - 输出文件路径
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+///
+/// fn main() {
+///     let old_content = "0123456789abcdef".repeat(64);
+///     let new_content = old_content.replace("789a", "XYZ");
+///     file::write_text_file("./target/__test/file_test/binary_patch/old.bin", &old_content).unwrap();
+///     file::write_text_file("./target/__test/file_test/binary_patch/new.bin", &new_content).unwrap();
+///
+///     let patch = file::binary_diff("./target/__test/file_test/binary_patch/old.bin", "./target/__test/file_test/binary_patch/new.bin").unwrap();
+///     file::write_file("./target/__test/file_test/binary_patch/update.patch", &patch).unwrap();
+///
+///     let result = file::binary_patch(
+///         "./target/__test/file_test/binary_patch/old.bin",
+///         "./target/__test/file_test/binary_patch/update.patch",
+///         "./target/__test/file_test/binary_patch/out.bin",
+///     );
+///     assert!(result.is_ok());
+///     assert_eq!(file::read_text_file("./target/__test/file_test/binary_patch/out.bin").unwrap(), new_content);
+///
+///     // a patch producing more data than its header declares is rejected while it is applied
+///     let mut patch = patch;
+///     patch[8..16].copy_from_slice(&16u64.to_le_bytes());
+///     file::write_file("./target/__test/file_test/binary_patch/invalid.patch", &patch).unwrap();
+///     let result = file::binary_patch(
+///         "./target/__test/file_test/binary_patch/old.bin",
+///         "./target/__test/file_test/binary_patch/invalid.patch",
+///         "./target/__test/file_test/binary_patch/invalid.bin",
+///     );
+///     assert!(matches!(result, Err(x_io::error::FsIOError::Parse(..))));
+/// }
+/// ```
+pub fn binary_patch<S, P, T>(old: &S, patch: &P, out: &T) -> FsIOResult<()>
+where
+    S: AsPath + ?Sized,
+    P: AsPath + ?Sized,
+    T: AsPath + ?Sized,
+{
+    let old_data = read_file(old)?;
+    let patch_data = read_file(patch)?;
+    let patch_path = patch.as_path();
+
+    let invalid = |reason: &str| {
+        FsIOError::Parse(
+            format!("Invalid patch file: {:?}, {}.", patch_path, reason).to_string(),
+            None,
+        )
+    };
+
+    let header_size = DIFF_MAGIC.len() + 8 + 32;
+    if patch_data.len() < header_size || !patch_data.starts_with(DIFF_MAGIC) {
+        return Err(invalid("missing header"));
+    }
+    let new_length = read_u64(&patch_data, DIFF_MAGIC.len()).unwrap_or_default();
+    let expected_digest = &patch_data[DIFF_MAGIC.len() + 8..header_size];
+
+    // the header is untrusted, a valid patch rarely produces more than the old file and the patch together
+    let capacity_limit = old_data.len().saturating_add(patch_data.len()) as u64;
+    let mut new_data = Vec::with_capacity(new_length.min(capacity_limit) as usize);
+    let mut position = header_size;
+    while position < patch_data.len() {
+        let instruction = patch_data[position];
+        position += 1;
+
+        match instruction {
+            DIFF_COPY => {
+                let offset = read_u64(&patch_data, position).ok_or_else(|| invalid("truncated"))?;
+                let length =
+                    read_u32(&patch_data, position + 8).ok_or_else(|| invalid("truncated"))?;
+                position += 12;
+
+                let start = offset as usize;
+                let end = start.saturating_add(length as usize);
+                if new_data.len() as u64 + u64::from(length) > new_length {
+                    return Err(invalid("output longer than the header length"));
+                }
+                match old_data.get(start..end) {
+                    Some(block) => new_data.extend_from_slice(block),
+                    None => return Err(invalid("copy outside of the old file")),
+                }
+            }
+            DIFF_DATA => {
+                let length = read_u32(&patch_data, position).ok_or_else(|| invalid("truncated"))?;
+                position += 4;
+
+                let end = position.saturating_add(length as usize);
+                if new_data.len() as u64 + u64::from(length) > new_length {
+                    return Err(invalid("output longer than the header length"));
+                }
+                match patch_data.get(position..end) {
+                    Some(data) => new_data.extend_from_slice(data),
+                    None => return Err(invalid("truncated")),
+                }
+                position = end;
+            }
+            _ => return Err(invalid("unknown instruction")),
+        }
+    }
+
+    let actual_digest = sha256(&new_data);
+    if new_data.len() as u64 != new_length || actual_digest[..] != expected_digest[..] {
+        return Err(FsIOError::ChecksumMismatch {
+            path: out.as_path().to_path_buf(),
+            expected: DigestFmt(expected_digest).to_string(),
+            actual: DigestFmt(actual_digest).to_string(),
+        });
+    }
+
+    write_file_atomic(out, &new_data)
+}
+
+fn push_diff_data(patch: &mut Vec<u8>, data: &[u8]) {
+    for chunk in data.chunks(u32::MAX as usize) {
+        patch.push(DIFF_DATA);
+        patch.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+        patch.extend_from_slice(chunk);
+    }
+}
+
+fn read_u64(data: &[u8], position: usize) -> Option<u64> {
+    let bytes = data.get(position..position.checked_add(8)?)?;
+    let mut buffer = [0u8; 8];
+    buffer.copy_from_slice(bytes);
+    Some(u64::from_le_bytes(buffer))
+}
+
+fn read_u32(data: &[u8], position: usize) -> Option<u32> {
+    let bytes = data.get(position..position.checked_add(4)?)?;
+    let mut buffer = [0u8; 4];
+    buffer.copy_from_slice(bytes);
+    Some(u32::from_le_bytes(buffer))
+}
+
+/// 合并文件选项
+#[derive(Debug, Clone)]
+pub struct ConcatOptions {
+    /// 写入在相邻两个文件之间的分隔内容
+    pub separator: Vec<u8>,
+    /// 是否创建输出文件的父级目录
+    pub create_parents: bool,
+}
+
+impl Default for ConcatOptions {
+    fn default() -> Self {
+        ConcatOptions {
+            separator: vec![],
+            create_parents: true,
+        }
+    }
+}
+
+/// 按顺序将多个文件的内容(流式)合并到输出文件, 如果存在则覆盖文件
+///
+/// 内容先写入同目录的临时文件再重命名, 因此输出文件也可以是输入文件之一.
+///
+/// # 参数
+///
+/// * `inputs` - 输入文件路径
+/// * `output` - 输出文件路径
+/// * `options` - 合并选项
+///
+/// # 示例
+///
+/// ```
+/// use crate::x_io::file;
+/// use x_io::file::ConcatOptions;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/file_test/concat/license.txt", "/* MIT */").unwrap();
+///     file::write_text_file("./target/__test/file_test/concat/a.js", "let a = 1;").unwrap();
+///     file::write_text_file("./target/__test/file_test/concat/b.js", "let b = 2;").unwrap();
+///
+///     let options = ConcatOptions { separator: b"\n".to_vec(), ..ConcatOptions::default() };
+///     let result = file::concat(
+///         [
+///             "./target/__test/file_test/concat/license.txt",
+///             "./target/__test/file_test/concat/a.js",
+///             "./target/__test/file_test/concat/b.js",
+///         ],
+///         "./target/__test/file_test/concat/dist/bundle.js",
+///         &options,
+///     );
+///     assert!(result.is_ok());
+///
+///     let text = file::read_text_file("./target/__test/file_test/concat/dist/bundle.js").unwrap();
+///     assert_eq!(text, "/* MIT */\nlet a = 1;\nlet b = 2;");
+/// }
+/// ```
+pub fn concat<I, P, T>(inputs: I, output: &T, options: &ConcatOptions) -> FsIOResult<()>
+where
+    I: IntoIterator<Item = P>,
+    P: AsPath,
+    T: AsPath + ?Sized,
+{
+    let output_path = output.as_path();
+    if options.create_parents {
+        directory::create_parent(&output_path)?;
+    }
+
+    let temp_path = unique_sibling(output_path, "concat");
+    let result = concat_into(inputs, &temp_path, options);
+    if let Err(error) = result {
+        let _ = remove_file(&temp_path);
+        return Err(error);
+    }
+
+    match fs::rename(&temp_path, output_path) {
+        Ok(_) => Ok(()),
+        Err(error) => {
+            let _ = remove_file(&temp_path);
+            Err(FsIOError::io(
+                Operation::Rename(output_path.to_path_buf()),
+                &temp_path,
+                error,
+            ))
+        }
+    }
+}
+
+fn concat_into<I, P>(inputs: I, temp_path: &Path, options: &ConcatOptions) -> FsIOResult<()>
+where
+    I: IntoIterator<Item = P>,
+    P: AsPath,
+{
+    let temp_file = match File::create(temp_path) {
+        Ok(file) => file,
+        Err(error) => return Err(FsIOError::io(Operation::Create, &temp_path, error)),
+    };
+    let mut writer = io::BufWriter::new(temp_file);
+
+    for (index, input) in inputs.into_iter().enumerate() {
+        let input_path = input.as_path();
+        let mut input_file = match File::open(input_path) {
+            Ok(file) => file,
+            Err(error) => return Err(FsIOError::io(Operation::Read, &input_path, error)),
+        };
+
+        if index > 0 && !options.separator.is_empty() {
+            if let Err(error) = writer.write_all(&options.separator) {
+                return Err(FsIOError::io(Operation::Write, &temp_path, error));
+            }
+        }
+
+        if let Err(error) = io::copy(&mut input_file, &mut writer) {
+            return Err(FsIOError::io(
+                Operation::Copy(temp_path.to_path_buf()),
+                &input_path,
+                error,
+            ));
+        }
+    }
+
+    match writer.into_inner() {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(
+            Operation::Write,
+            &temp_path,
+            error.into_error(),
+        )),
+    }
+}
+
+/// 读取文件的扩展属性(跟随符号链接), 属性不存在时返回None
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `name` - 属性名称(Linux上需要命名空间前缀, 例如 `user.`)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/get_xattr/file.txt";
+///     file::write_text_file(file_path, "some content").unwrap();
+///
+///     // not every file system supports extended attributes
+///     if file::set_xattr(file_path, "user.checksum", b"abc").is_ok() {
+///         assert_eq!(file::get_xattr(file_path, "user.checksum").unwrap().unwrap(), b"abc");
+///         assert!(file::list_xattrs(file_path).unwrap().contains(&"user.checksum".to_string()));
+///
+///         file::remove_xattr(file_path, "user.checksum").unwrap();
+///         assert!(file::get_xattr(file_path, "user.checksum").unwrap().is_none());
+///     }
+/// }
+/// ```
+#[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+pub fn get_xattr<T: AsPath + ?Sized>(path: &T, name: &str) -> FsIOResult<Option<Vec<u8>>> {
+    let file_path = path.as_path();
+
+    match xattr::get(file_path, name) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(FsIOError::io(Operation::Metadata, &file_path, error)),
+    }
+}
+
+/// 设置文件的扩展属性(跟随符号链接), 如果存在则覆盖
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `name` - 属性名称(Linux上需要命名空间前缀, 例如 `user.`)
+/// * `value` - 属性值
+#[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+pub fn set_xattr<T: AsPath + ?Sized>(path: &T, name: &str, value: &[u8]) -> FsIOResult<()> {
+    let file_path = path.as_path();
+
+    match xattr::set(file_path, name, value) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::Write, &file_path, error)),
+    }
+}
+
+/// 返回文件所有扩展属性的名称(跟随符号链接)
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+#[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+pub fn list_xattrs<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Vec<String>> {
+    let file_path = path.as_path();
+
+    match xattr::list(file_path) {
+        Ok(names) => Ok(names),
+        Err(error) => Err(FsIOError::io(Operation::Metadata, &file_path, error)),
+    }
+}
+
+/// 删除文件的扩展属性(跟随符号链接), 如果属性不存在返回Ok
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `name` - 属性名称
+#[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+pub fn remove_xattr<T: AsPath + ?Sized>(path: &T, name: &str) -> FsIOResult<()> {
+    let file_path = path.as_path();
+
+    match xattr::remove(file_path, name) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::Write, &file_path, error)),
+    }
+}
+
+/// 将源文件的所有扩展属性复制到目标文件
+#[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+pub(crate) fn copy_xattrs(src: &Path, dst: &Path) -> FsIOResult<()> {
+    for name in list_xattrs(&src)? {
+        if let Some(value) = get_xattr(&src, &name)? {
+            set_xattr(&dst, &name, &value)?;
+        }
+    }
+    Ok(())
+}
+
+/// 扩展属性的系统调用
+#[cfg(all(feature = "xattr", any(target_os = "linux", target_os = "macos")))]
+mod xattr {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+    use std::ptr;
+
+    #[cfg(target_os = "linux")]
+    const NO_ATTRIBUTE: libc::c_int = libc::ENODATA;
+
+    #[cfg(target_os = "macos")]
+    const NO_ATTRIBUTE: libc::c_int = libc::ENOATTR;
+
+    fn to_cstring(value: &[u8]) -> io::Result<CString> {
+        match CString::new(value) {
+            Ok(value) => Ok(value),
+            Err(error) => Err(io::Error::new(io::ErrorKind::InvalidInput, error)),
+        }
+    }
+
+    fn is_no_attribute(error: &io::Error) -> bool {
+        error.raw_os_error() == Some(NO_ATTRIBUTE)
+    }
+
+    /// 先查询大小再读取, 读取期间大小变化时重试
+    fn read_sized<F>(mut call: F) -> io::Result<Vec<u8>>
+    where
+        F: FnMut(*mut libc::c_void, usize) -> libc::ssize_t,
+    {
+        loop {
+            let size = call(ptr::null_mut(), 0);
+            if size < 0 {
+                return Err(io::Error::last_os_error());
+            }
+
+            let mut buffer = vec![0u8; size as usize];
+            let read_size = call(buffer.as_mut_ptr() as *mut libc::c_void, buffer.len());
+            if read_size >= 0 {
+                buffer.truncate(read_size as usize);
+                return Ok(buffer);
+            }
+
+            let error = io::Error::last_os_error();
+            if error.raw_os_error() != Some(libc::ERANGE) {
+                return Err(error);
+            }
+        }
+    }
+
+    pub(super) fn get(path: &Path, name: &str) -> io::Result<Option<Vec<u8>>> {
+        let path = to_cstring(path.as_os_str().as_bytes())?;
+        let name = to_cstring(name.as_bytes())?;
+
+        let result = read_sized(|buffer, size| unsafe {
+            #[cfg(target_os = "linux")]
+            {
+                libc::getxattr(path.as_ptr(), name.as_ptr(), buffer, size)
+            }
+            #[cfg(target_os = "macos")]
+            {
+                libc::getxattr(path.as_ptr(), name.as_ptr(), buffer, size, 0, 0)
+            }
+        });
+
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(ref error) if is_no_attribute(error) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+
+    pub(super) fn set(path: &Path, name: &str, value: &[u8]) -> io::Result<()> {
+        let path = to_cstring(path.as_os_str().as_bytes())?;
+        let name = to_cstring(name.as_bytes())?;
+        let value_ptr = value.as_ptr() as *const libc::c_void;
+
+        #[cfg(target_os = "linux")]
+        let result =
+            unsafe { libc::setxattr(path.as_ptr(), name.as_ptr(), value_ptr, value.len(), 0) };
+        #[cfg(target_os = "macos")]
+        let result =
+            unsafe { libc::setxattr(path.as_ptr(), name.as_ptr(), value_ptr, value.len(), 0, 0) };
+
+        if result != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    pub(super) fn list(path: &Path) -> io::Result<Vec<String>> {
+        let path = to_cstring(path.as_os_str().as_bytes())?;
+
+        let names = read_sized(|buffer, size| unsafe {
+            #[cfg(target_os = "linux")]
+            {
+                libc::listxattr(path.as_ptr(), buffer as *mut libc::c_char, size)
+            }
+            #[cfg(target_os = "macos")]
+            {
+                libc::listxattr(path.as_ptr(), buffer as *mut libc::c_char, size, 0)
+            }
+        })?;
+
+        // the names are separated by NUL bytes
+        Ok(names
+            .split(|byte| *byte == 0)
+            .filter(|name| !name.is_empty())
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+            .collect())
+    }
+
+    pub(super) fn remove(path: &Path, name: &str) -> io::Result<()> {
+        let path = to_cstring(path.as_os_str().as_bytes())?;
+        let name = to_cstring(name.as_bytes())?;
+
+        #[cfg(target_os = "linux")]
+        let result = unsafe { libc::removexattr(path.as_ptr(), name.as_ptr()) };
+        #[cfg(target_os = "macos")]
+        let result = unsafe { libc::removexattr(path.as_ptr(), name.as_ptr(), 0) };
+
+        if result != 0 {
+            let error = io::Error::last_os_error();
+            if !is_no_attribute(&error) {
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 创建命名管道(FIFO), 如果父级目录不存在则创建目录, 路径已存在时返回错误
+///
+/// 只支持unix, 其他平台返回 `io::ErrorKind::Unsupported` 类型的错误(Windows的命名管道不在文件系统中).
+///
+/// # 参数
+///
+/// * `path` - 管道路径
+/// * `mode` - 权限(例如 `0o600`, 受umask影响)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let fifo_path = "./target/__test/file_test/create_fifo/events.fifo";
+///     let _ = std::fs::remove_file(fifo_path);
+///
+///     let result = file::create_fifo(fifo_path, 0o600);
+///
+///     #[cfg(unix)]
+///     {
+///         use std::os::unix::fs::FileTypeExt;
+///
+///         assert!(result.is_ok());
+///         let metadata = std::fs::metadata(fifo_path).unwrap();
+///         assert!(metadata.file_type().is_fifo());
+///         assert!(file::create_fifo(fifo_path, 0o600).unwrap_err().is_already_exists());
+///     }
+///     #[cfg(not(unix))]
+///     assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::Unsupported);
+/// }
+/// ```
+pub fn create_fifo<T: AsPath + ?Sized>(path: &T, mode: u32) -> FsIOResult<()> {
+    let fifo_path = path.as_path();
+    directory::create_parent(&fifo_path)?;
+
+    match mkfifo(fifo_path, mode) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::Create, &fifo_path, error)),
+    }
+}
+
+#[cfg(unix)]
+fn mkfifo(path: &Path, mode: u32) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path_string = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(value) => value,
+        Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidInput, error)),
+    };
+
+    if unsafe { libc::mkfifo(path_string.as_ptr(), mode as libc::mode_t) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn mkfifo(_path: &Path, _mode: u32) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "FIFOs are only supported on unix.",
+    ))
+}
+
+/// 按固定大小分块读取文件的迭代器, 最后一块可能小于块大小
+///
+/// 迭代器每次返回新分配的块, 使用 `next_into` 可以重复使用同一个缓冲区. 读取失败后迭代结束.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::file::ChunkReader;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/chunk_reader/file.txt";
+///     file::write_text_file(file_path, "0123456789").unwrap();
+///
+///     let chunks: Vec<Vec<u8>> = ChunkReader::open(file_path, 4).unwrap().map(|chunk| chunk.unwrap()).collect();
+///     assert_eq!(chunks, vec![b"0123".to_vec(), b"4567".to_vec(), b"89".to_vec()]);
+///
+///     let mut reader = ChunkReader::open(file_path, 4).unwrap();
+///     let mut buffer = Vec::new();
+///     let mut total = 0;
+///     while reader.next_into(&mut buffer).unwrap() > 0 {
+///         total += buffer.len();
+///     }
+///     assert_eq!(total, 10);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ChunkReader {
+    path: PathBuf,
+    file: File,
+    chunk_size: usize,
+    done: bool,
+}
+
+impl ChunkReader {
+    /// 打开文件
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件路径
+    /// * `chunk_size` - 块大小(字节), 必须大于0
+    pub fn open<T: AsPath + ?Sized>(path: &T, chunk_size: usize) -> FsIOResult<ChunkReader> {
+        let file_path = path.as_path();
+
+        if chunk_size == 0 {
+            return Err(FsIOError::io(
+                Operation::Read,
+                &file_path,
+                io::Error::new(io::ErrorKind::InvalidInput, "chunk size must be greater than zero"),
+            ));
+        }
+
+        match File::open(file_path) {
+            Ok(file) => Ok(ChunkReader {
+                path: file_path.to_path_buf(),
+                file,
+                chunk_size,
+                done: false,
+            }),
+            Err(error) => Err(FsIOError::io(Operation::Read, &file_path, error)),
+        }
+    }
+
+    /// 返回块大小
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// 读取下一块到缓冲区(替换缓冲区的内容), 返回读取的字节数, 读取完毕时返回0
+    ///
+    /// # 参数
+    ///
+    /// * `buffer` - 缓冲区
+    pub fn next_into(&mut self, buffer: &mut Vec<u8>) -> FsIOResult<usize> {
+        buffer.clear();
+        if self.done {
+            return Ok(0);
+        }
+        buffer.resize(self.chunk_size, 0);
+
+        let mut filled = 0;
+        while filled < self.chunk_size {
+            match self.file.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(count) => filled += count,
+                Err(ref error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => {
+                    buffer.clear();
+                    self.done = true;
+                    return Err(FsIOError::io(Operation::Read, &self.path, error));
+                }
+            }
+        }
+        buffer.truncate(filled);
+
+        if filled == 0 {
+            self.done = true;
+        }
+        Ok(filled)
+    }
+}
+
+impl Iterator for ChunkReader {
+    type Item = FsIOResult<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = Vec::with_capacity(self.chunk_size);
+        match self.next_into(&mut chunk) {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(chunk)),
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+/// 保持打开的追加写入句柄, 写入先进入内部缓冲区, 避免每次追加都重新打开文件
+///
+/// 缓冲区满或调用 `flush` 时写入文件, 被丢弃时写入剩余内容并同步到磁盘(忽略错误, 需要处理错误时先调用 `sync`).
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::file::Appender;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/appender/app.log";
+///     file::write_text_file(file_path, "start\n").unwrap();
+///
+///     let mut appender = Appender::open(file_path).unwrap();
+///     appender.write_line("first").unwrap();
+///     appender.write(b"second\n").unwrap();
+///     appender.flush().unwrap();
+///     assert_eq!(file::read_text_file(file_path).unwrap(), "start\nfirst\nsecond\n");
+///
+///     appender.write_line("third").unwrap();
+///     drop(appender);
+///     assert_eq!(file::read_text_file(file_path).unwrap(), "start\nfirst\nsecond\nthird\n");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Appender {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl Appender {
+    /// 打开文件用于追加, 如果不存在则创建文件(包括父级目录)
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件路径
+    pub fn open<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Appender> {
+        let file_path = path.as_path();
+        directory::create_parent(&file_path)?;
+
+        match OpenOptions::new().append(true).create(true).open(file_path) {
+            Ok(file) => Ok(Appender {
+                path: file_path.to_path_buf(),
+                writer: BufWriter::new(file),
+            }),
+            Err(error) => Err(FsIOError::io(Operation::Append, &file_path, error)),
+        }
+    }
+
+    /// 返回文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 追加数据
+    ///
+    /// # 参数
+    ///
+    /// * `data` - 数据
+    pub fn write(&mut self, data: &[u8]) -> FsIOResult<()> {
+        match self.writer.write_all(data) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(FsIOError::io(Operation::Append, &self.path, error)),
+        }
+    }
+
+    /// 追加一行文本(自动添加 `\n`)
+    ///
+    /// # 参数
+    ///
+    /// * `line` - 文本
+    pub fn write_line(&mut self, line: &str) -> FsIOResult<()> {
+        self.write(line.as_bytes())?;
+        self.write(b"\n")
+    }
+
+    /// 将缓冲区的内容写入文件
+    pub fn flush(&mut self) -> FsIOResult<()> {
+        match self.writer.flush() {
+            Ok(_) => Ok(()),
+            Err(error) => Err(FsIOError::io(Operation::Append, &self.path, error)),
+        }
+    }
+
+    /// 将缓冲区的内容写入文件并同步到磁盘
+    pub fn sync(&mut self) -> FsIOResult<()> {
+        self.flush()?;
+        match self.writer.get_ref().sync_all() {
+            Ok(_) => Ok(()),
+            Err(error) => Err(FsIOError::io(Operation::Sync, &self.path, error)),
+        }
+    }
+}
+
+impl Drop for Appender {
+    fn drop(&mut self) {
+        let _ = self.sync();
+    }
+}
+
+/// 将写入的数据同时写入多个文件
+///
+/// 单个文件打开或写入失败时记录错误并停止写入该文件, 其余文件继续写入, 只有所有文件都失败时写入才返回错误.
+/// 使用 `errors` 查看失败的文件, 或使用 `finish` 在结束时以 `FsIOError::Multiple` 返回所有失败.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::file::TeeWriter;
+/// use std::io::Write;
+///
+/// fn main() {
+///     let mut tee = TeeWriter::new([
+///         "./target/__test/file_test/tee_writer/logs/build.log",
+///         "./target/__test/file_test/tee_writer/tmp/build.log",
+///     ])
+///     .unwrap();
+///     writeln!(tee, "compiling x-io").unwrap();
+///     tee.finish().unwrap();
+///
+///     assert_eq!(file::read_text_file("./target/__test/file_test/tee_writer/logs/build.log").unwrap(), "compiling x-io\n");
+///     assert_eq!(file::read_text_file("./target/__test/file_test/tee_writer/tmp/build.log").unwrap(), "compiling x-io\n");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TeeWriter {
+    /// 目标文件(失败后为None)
+    targets: Vec<(PathBuf, Option<File>)>,
+    /// 失败的文件和错误
+    errors: Vec<(PathBuf, FsIOError)>,
+}
+
+impl TeeWriter {
+    /// 创建或覆盖所有文件(包括父级目录), 所有文件都无法打开时返回 `FsIOError::Multiple`
+    ///
+    /// # 参数
+    ///
+    /// * `paths` - 文件路径
+    pub fn new<I, P>(paths: I) -> FsIOResult<TeeWriter>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsPath,
+    {
+        let mut tee = TeeWriter {
+            targets: vec![],
+            errors: vec![],
+        };
+
+        for path in paths {
+            let file_path = path.as_path().to_path_buf();
+            let file =
+                directory::create_parent(&file_path).and_then(|_| match File::create(&file_path) {
+                    Ok(file) => Ok(file),
+                    Err(error) => Err(FsIOError::io(Operation::Create, &file_path, error)),
+                });
+
+            match file {
+                Ok(file) => tee.targets.push((file_path, Some(file))),
+                Err(error) => {
+                    tee.targets.push((file_path.clone(), None));
+                    tee.errors.push((file_path, error));
+                }
+            }
+        }
+
+        if tee.all_failed() {
+            return Err(FsIOError::Multiple(tee.errors));
+        }
+        Ok(tee)
+    }
+
+    /// 返回失败的文件和错误
+    pub fn errors(&self) -> &[(PathBuf, FsIOError)] {
+        &self.errors
+    }
+
+    /// 结束写入, 有文件失败时以 `FsIOError::Multiple` 返回所有失败
+    pub fn finish(mut self) -> FsIOResult<()> {
+        let _ = self.flush();
+
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(FsIOError::Multiple(self.errors))
+        }
+    }
+
+    fn all_failed(&self) -> bool {
+        !self.targets.is_empty() && self.targets.iter().all(|(_, file)| file.is_none())
+    }
+
+    /// 对每个未失败的文件执行操作, 失败的文件被记录并不再使用
+    fn for_each_target<F>(&mut self, operation: Operation, mut callback: F) -> io::Result<()>
+    where
+        F: FnMut(&mut File) -> io::Result<()>,
+    {
+        for (path, target) in self.targets.iter_mut() {
+            if let Some(file) = target {
+                if let Err(error) = callback(file) {
+                    self.errors
+                        .push((path.clone(), FsIOError::io(operation.clone(), path, error)));
+                    *target = None;
+                }
+            }
+        }
+
+        if self.all_failed() {
+            Err(io::Error::other(
+                "Unable to write to any of the target files.",
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Write for TeeWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.for_each_target(Operation::Write, |file| file.write_all(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.for_each_target(Operation::Write, |file| file.flush())
+    }
+}
+
+/// 写入文件, 路径为 `-` 时写入标准输出(命令行工具的惯例)
+///
+/// # 参数
+///
+/// * `path` - 文件路径或 `-`
+/// * `data` - 数据
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_or_stdout/out.txt";
+///     file::write_or_stdout(file_path, b"some content").unwrap();
+///     assert_eq!(file::read_text_file(file_path).unwrap(), "some content");
+///
+///     file::write_or_stdout("-", b"printed to stdout\n").unwrap();
+/// }
+/// ```
+pub fn write_or_stdout<T: AsPath + ?Sized>(path: &T, data: &[u8]) -> FsIOResult<()> {
+    let file_path = path.as_path();
+    if !is_dash(file_path) {
+        return write_file(file_path, data);
+    }
+
+    let mut stdout = io::stdout().lock();
+    match stdout.write_all(data).and_then(|_| stdout.flush()) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::WriteStdout, &file_path, error)),
+    }
+}
+
+/// 读取文件内容, 路径为 `-` 时读取标准输入的全部内容(命令行工具的惯例)
+///
+/// # 参数
+///
+/// * `path` - 文件路径或 `-`
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/read_or_stdin/in.txt";
+///     file::write_text_file(file_path, "some content").unwrap();
+///
+///     let data = file::read_or_stdin(file_path).unwrap();
+///     assert_eq!(data, b"some content");
+/// }
+/// ```
+pub fn read_or_stdin<T: AsPath + ?Sized>(path: &T) -> FsIOResult<Vec<u8>> {
+    let file_path = path.as_path();
+    if !is_dash(file_path) {
+        return read_file(file_path);
+    }
+
+    let mut data = vec![];
+    match io::stdin().lock().read_to_end(&mut data) {
+        Ok(_) => Ok(data),
+        Err(error) => Err(FsIOError::io(Operation::ReadStdin, &file_path, error)),
+    }
+}
+
+/// 路径是否为 `-`(表示标准输入或标准输出)
+fn is_dash(path: &Path) -> bool {
+    path.as_os_str() == "-"
+}
+
+/// 批量写入选项
+#[derive(Debug, Clone, Copy)]
+pub struct WriteOptions {
+    /// 写入线程数量, 0表示使用可用的并行数量
+    pub threads: usize,
+    /// 写入完成后是否同步到磁盘, 同步每个文件的内容, 然后每个父级目录同步一次
+    pub sync: bool,
+    /// 是否使用直接IO写入(不经过页缓存, 参见 `supports_direct_io`), 文件系统不支持时使用普通IO
+    pub direct_io: bool,
+    /// 写入完成后是否同步父级目录(`directory::sync`), 使新建的文件在崩溃后仍然存在, `sync` 为true时目录已同步
+    pub sync_parent: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        WriteOptions {
+            threads: 1,
+            sync: true,
+            direct_io: false,
+            sync_parent: false,
+        }
+    }
+}
+
+/// 批量创建或覆盖文件, 每个父级目录只创建一次
+///
+/// 启用 `uring` 功能时(Linux)使用io_uring批量提交打开, 写入和关闭操作, 内核不支持时使用普通写入.
+///
+/// 单个文件失败时继续写入其余文件, 所有失败在结束后以 `FsIOError::Multiple` 返回(按路径排序).
+///
+/// # 参数
+///
+/// * `files` - 文件路径和内容
+/// * `options` - 写入选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::file::WriteOptions;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let files: Vec<(PathBuf, Vec<u8>)> = (0..100)
+///         .map(|index| {
+///             let file_path = format!("./target/__test/file_test/write_many/dir{}/file{}.txt", index % 10, index);
+///             (PathBuf::from(file_path), index.to_string().into_bytes())
+///         })
+///         .collect();
+///
+///     let options = WriteOptions { threads: 4, ..WriteOptions::default() };
+///     file::write_many(files, options).unwrap();
+///
+///     let text = file::read_text_file("./target/__test/file_test/write_many/dir3/file53.txt").unwrap();
+///     assert_eq!(text, "53");
+///
+///     // direct IO bypasses the page cache, file systems without support use regular IO
+///     let file_path = "./target/__test/file_test/write_many/direct/backup.bin";
+///     let options = WriteOptions { direct_io: true, ..WriteOptions::default() };
+///     file::write_many(vec![(PathBuf::from(file_path), vec![1u8; 5000])], options).unwrap();
+///     assert_eq!(file::read_file(file_path).unwrap(), vec![1u8; 5000]);
+/// }
+/// ```
+pub fn write_many<I>(files: I, options: WriteOptions) -> FsIOResult<()>
+where
+    I: IntoIterator<Item = (PathBuf, Vec<u8>)>,
+{
+    let files: Vec<(PathBuf, Vec<u8>)> = files.into_iter().collect();
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let mut directories = BTreeSet::new();
+    for (file_path, _) in &files {
+        let parent = match file_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        directories.insert(parent.to_path_buf());
+    }
+
+    let mut failures = vec![];
+    for directory_path in &directories {
+        if let Err(error) = directory::create(directory_path) {
+            failures.push((directory_path.clone(), error));
+        }
+    }
+
+    let threads = match options.threads {
+        0 => thread::available_parallelism().map_or(1, |count| count.get()),
+        count => count,
+    }
+    .min(files.len());
+
+    if threads <= 1 {
+        failures.extend(write_chunk(&files, options.sync, options.direct_io));
+    } else {
+        let chunk_size = files.len().div_ceil(threads);
+        thread::scope(|scope| {
+            let handles: Vec<_> = files
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || write_chunk(chunk, options.sync, options.direct_io))
+                })
+                .collect();
+            for handle in handles {
+                match handle.join() {
+                    Ok(chunk_failures) => failures.extend(chunk_failures),
+                    Err(panic) => std::panic::resume_unwind(panic),
+                }
+            }
+        });
+    }
+
+    if options.sync || options.sync_parent {
+        for directory_path in &directories {
+            if let Err(error) = directory::sync(directory_path) {
+                failures.push((directory_path.clone(), error));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        failures.sort_by(|left, right| left.0.cmp(&right.0));
+        Err(FsIOError::Multiple(failures))
+    }
+}
+
+/// 写入一组文件, 返回失败的文件和错误
+fn write_chunk(
+    files: &[(PathBuf, Vec<u8>)],
+    sync: bool,
+    direct_io: bool,
+) -> Vec<(PathBuf, FsIOError)> {
+    let mut failures = vec![];
+
+    let batched = if direct_io {
+        vec![]
+    } else {
+        write_batch(files, sync)
+    };
+    for (index, (file_path, data)) in files.iter().enumerate() {
+        // failed batched writes are retried to report the same errors
+        if matches!(batched.get(index), Some(Ok(_))) {
+            continue;
+        }
+
+        let created = if direct_io {
+            create_direct(file_path)
+        } else {
+            File::create(file_path)
+        };
+        let mut file = match created {
+            Ok(file) => file,
+            Err(error) => {
+                failures.push((
+                    file_path.clone(),
+                    FsIOError::io(Operation::Create, file_path, error),
+                ));
+                continue;
+            }
+        };
+
+        let written = if direct_io {
+            write_direct(&mut data.as_slice(), &mut file, None).map(|_| ())
+        } else {
+            file.write_all(data)
+        };
+        let result = written.and_then(|_| if sync { file.sync_data() } else { Ok(()) });
+        if let Err(error) = result {
+            failures.push((
+                file_path.clone(),
+                FsIOError::io(Operation::Write, file_path, error),
+            ));
+        }
+    }
+
+    failures
+}
+
+/// 使用io_uring批量写入文件(`uring` 功能, 不单独同步文件时), 返回空列表时逐个写入
+#[cfg(all(feature = "uring", target_os = "linux"))]
+fn write_batch(files: &[(PathBuf, Vec<u8>)], sync: bool) -> Vec<io::Result<()>> {
+    if sync {
+        return vec![];
+    }
+    crate::uring::write_files(files).unwrap_or_default()
+}
+
+/// 使用io_uring批量写入文件(`uring` 功能, 不单独同步文件时), 返回空列表时逐个写入
+#[cfg(not(all(feature = "uring", target_os = "linux")))]
+fn write_batch(_files: &[(PathBuf, Vec<u8>)], _sync: bool) -> Vec<io::Result<()>> {
+    vec![]
+}
+
+/// 直接IO缓冲区的对齐字节数和每次读写的字节数的最小单位(覆盖512字节和4KiB扇区)
+const DIRECT_ALIGNMENT: usize = 4096;
+
+/// 直接IO每次读写的字节数
+const DIRECT_BLOCK_SIZE: usize = 1024 * 1024;
+
+/// 返回目录所在的文件系统是否支持直接IO(不经过页缓存), 在目录中创建并删除一个临时文件进行检测
+///
+/// Linux使用 `O_DIRECT`, macOS使用 `F_NOCACHE`, Windows使用 `FILE_FLAG_NO_BUFFERING`, 其他系统返回false.
+/// 不支持时 `WriteOptions::direct_io` 和 `CopyOptions::direct_io` 使用普通IO.
+///
+/// # 参数
+///
+/// * `path` - 目录路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+///
+/// fn main() {
+///     directory::create("./target/__test/file_test/supports_direct_io").unwrap();
+///
+///     let supported = file::supports_direct_io("./target/__test/file_test/supports_direct_io").unwrap();
+///     if cfg!(not(any(target_os = "linux", target_os = "android", target_os = "macos", windows))) {
+///         assert!(!supported);
+///     }
+/// }
+/// ```
+pub fn supports_direct_io<T: AsPath + ?Sized>(path: &T) -> FsIOResult<bool> {
+    let directory_path = path.as_path();
+    let probe_path = unique_sibling(&directory_path.join("direct-io"), "probe");
+
+    let result = match open_direct(&probe_path, true) {
+        Ok(Some(mut file)) => {
+            let buffer = AlignedBuffer::new(DIRECT_ALIGNMENT);
+            match file.write_all(&buffer) {
+                Ok(_) => Ok(true),
+                Err(error) if is_unsupported_direct_io(&error) => Ok(false),
+                Err(error) => Err(error),
+            }
+        }
+        Ok(None) => Ok(false),
+        Err(error) => Err(error),
+    };
+    let _ = remove_file(&probe_path);
+
+    match result {
+        Ok(supported) => Ok(supported),
+        Err(error) => Err(FsIOError::io(Operation::Create, &probe_path, error)),
+    }
+}
+
+/// 以直接IO方式复制文件内容和权限, 文件系统不支持直接IO时使用普通IO
+pub(crate) fn copy_with_direct(
+    src: &Path,
+    dst: &Path,
+    throttle: Option<&mut Throttle>,
+) -> io::Result<u64> {
+    let mut source = match open_direct(src, false)? {
+        Some(source) => source,
+        None => File::open(src)?,
+    };
+    let permissions = source.metadata()?.permissions();
+    let mut target = create_direct(dst)?;
+
+    let bytes = write_direct(&mut source, &mut target, throttle)?;
+    target.set_permissions(permissions)?;
+    Ok(bytes)
+}
+
+/// 以直接IO方式创建或覆盖文件, 文件系统不支持直接IO时使用普通IO
+fn create_direct(path: &Path) -> io::Result<File> {
+    match open_direct(path, true)? {
+        Some(file) => Ok(file),
+        None => File::create(path),
+    }
+}
+
+/// 以直接IO方式打开文件(`write` 为true时创建或覆盖文件), 不支持直接IO时返回 `None`
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn open_direct(path: &Path, write: bool) -> io::Result<Option<File>> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut options = OpenOptions::new();
+    if write {
+        options.write(true).create(true).truncate(true);
+    } else {
+        options.read(true);
+    }
+    match options.custom_flags(libc::O_DIRECT).open(path) {
+        Ok(file) => Ok(Some(file)),
+        Err(error) if is_unsupported_direct_io(&error) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// 以直接IO方式打开文件(`write` 为true时创建或覆盖文件), 不支持直接IO时返回 `None`
+#[cfg(target_os = "macos")]
+fn open_direct(path: &Path, write: bool) -> io::Result<Option<File>> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = if write {
+        File::create(path)?
+    } else {
+        File::open(path)?
+    };
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_NOCACHE, 1) } == -1 {
+        return Ok(None);
+    }
+    Ok(Some(file))
+}
+
+/// 以直接IO方式打开文件(`write` 为true时创建或覆盖文件), 不支持直接IO时返回 `None`
+#[cfg(windows)]
+fn open_direct(path: &Path, write: bool) -> io::Result<Option<File>> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use windows_sys::Win32::Storage::FileSystem::FILE_FLAG_NO_BUFFERING;
+
+    let mut options = OpenOptions::new();
+    if write {
+        options.write(true).create(true).truncate(true);
+    } else {
+        options.read(true);
+    }
+    match options.custom_flags(FILE_FLAG_NO_BUFFERING).open(path) {
+        Ok(file) => Ok(Some(file)),
+        Err(error) if is_unsupported_direct_io(&error) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// 以直接IO方式打开文件(`write` 为true时创建或覆盖文件), 不支持直接IO时返回 `None`
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    windows
+)))]
+fn open_direct(_path: &Path, _write: bool) -> io::Result<Option<File>> {
+    Ok(None)
+}
+
+/// 错误是否表示文件系统不支持直接IO(Linux的 `EINVAL`, Windows的 `ERROR_INVALID_PARAMETER`)
+fn is_unsupported_direct_io(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::InvalidInput
+}
+
+/// 使用对齐的缓冲区将读取器的全部内容写入文件开头, 返回写入的字节数
+///
+/// 每块数据读取到缓冲区填满或读取器结束为止(中途读取不足不表示结束),
+/// 最后一块数据补齐到对齐字节数后写入, 然后将文件截断到实际长度.
+fn write_direct(
+    reader: &mut dyn Read,
+    target: &mut File,
+    mut throttle: Option<&mut Throttle>,
+) -> io::Result<u64> {
+    let mut buffer = AlignedBuffer::new(DIRECT_BLOCK_SIZE);
+    let mut total = 0;
+
+    loop {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            let size = match reader.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(size) => size,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+            filled += size;
+        }
+        if filled == 0 {
+            break;
+        }
+
+        if let Some(ref mut throttle) = throttle {
+            throttle.consume(filled as u64);
+        }
+        // only the last block is shorter than the buffer, its padding is cut off by `set_len`
+        let padded = filled.div_ceil(DIRECT_ALIGNMENT) * DIRECT_ALIGNMENT;
+        target.write_all(&buffer[..padded])?;
+        total += filled as u64;
+
+        if filled < buffer.len() {
+            break;
+        }
+    }
+
+    target.set_len(total)?;
+    Ok(total)
+}
+
+/// 按 `DIRECT_ALIGNMENT` 对齐的缓冲区(直接IO要求缓冲区地址对齐)
+struct AlignedBuffer {
+    pointer: std::ptr::NonNull<u8>,
+    layout: std::alloc::Layout,
+}
+
+impl AlignedBuffer {
+    fn new(size: usize) -> AlignedBuffer {
+        let layout = match std::alloc::Layout::from_size_align(size, DIRECT_ALIGNMENT) {
+            Ok(layout) => layout,
+            Err(error) => panic!("Invalid buffer size: {} ({})", size, error),
+        };
+        let pointer = unsafe { std::alloc::alloc_zeroed(layout) };
+        match std::ptr::NonNull::new(pointer) {
+            Some(pointer) => AlignedBuffer { pointer, layout },
+            None => std::alloc::handle_alloc_error(layout),
+        }
+    }
+}
+
+impl std::ops::Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.pointer.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl std::ops::DerefMut for AlignedBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.pointer.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        unsafe { std::alloc::dealloc(self.pointer.as_ptr(), self.layout) };
+    }
+}
+
+/// 读取多个文件的内容, 遇到第一个错误时返回该错误
+///
+/// # 参数
+///
+/// * `paths` - 文件路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use std::path::Path;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/file_test/read_many/base.toml", "base").unwrap();
+///     file::write_text_file("./target/__test/file_test/read_many/local.toml", "local").unwrap();
+///
+///     let files = file::read_many([
+///         "./target/__test/file_test/read_many/base.toml",
+///         "./target/__test/file_test/read_many/local.toml",
+///     ])
+///     .unwrap();
+///     assert_eq!(files.len(), 2);
+///     assert_eq!(files[Path::new("./target/__test/file_test/read_many/local.toml")], b"local");
+/// }
+/// ```
+pub fn read_many<I, P>(paths: I) -> FsIOResult<HashMap<PathBuf, Vec<u8>>>
+where
+    I: IntoIterator<Item = P>,
+    P: AsPath,
+{
+    read_many_with(paths, false)
+}
+
+/// 读取多个文件的内容
+///
+/// `continue_on_error` 为true时读取所有文件, 所有失败在结束后以 `FsIOError::Multiple` 返回,
+/// 否则遇到第一个错误时返回该错误.
+///
+/// 启用 `uring` 功能时(Linux)使用io_uring批量提交打开, 读取和关闭操作, 内核不支持时使用普通读取.
+///
+/// # 参数
+///
+/// * `paths` - 文件路径
+/// * `continue_on_error` - 单个文件失败时是否继续读取其余文件
+///
+/// # 示例
+///
+/// ```
+/// use x_io::error::FsIOError;
+/// use x_io::file;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/file_test/read_many_with/base.toml", "base").unwrap();
+///
+///     let result = file::read_many_with(
+///         [
+///             "./target/__test/file_test/read_many_with/base.toml",
+///             "./target/__test/file_test/read_many_with/missing1.toml",
+///             "./target/__test/file_test/read_many_with/missing2.toml",
+///         ],
+///         true,
+///     );
+///     match result {
+///         Err(FsIOError::Multiple(errors)) => {
+///             assert_eq!(errors.len(), 2);
+///             assert!(errors.iter().all(|(_, error)| error.is_not_found()));
+///         }
+///         _ => panic!("expected multiple errors"),
+///     }
+/// }
+/// ```
+pub fn read_many_with<I, P>(
+    paths: I,
+    continue_on_error: bool,
+) -> FsIOResult<HashMap<PathBuf, Vec<u8>>>
+where
+    I: IntoIterator<Item = P>,
+    P: AsPath,
+{
+    let mut files = HashMap::new();
+    let mut failures = vec![];
+
+    let paths: Vec<PathBuf> = paths
+        .into_iter()
+        .map(|path| path.as_path().to_path_buf())
+        .collect();
+    let mut batched = read_batch(&paths).into_iter();
+
+    for file_path in paths {
+        // failed batched reads are retried to report the same errors as read_file
+        let result = match batched.next() {
+            Some(Ok(data)) => Ok(data),
+            _ => read_file(&file_path),
+        };
+        match result {
+            Ok(data) => {
+                files.insert(file_path, data);
+            }
+            Err(error) if continue_on_error => failures.push((file_path, error)),
+            Err(error) => return Err(error),
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(files)
+    } else {
+        Err(FsIOError::Multiple(failures))
+    }
+}
+
+/// 使用io_uring批量读取文件(`uring` 功能), 返回空列表时逐个读取
+#[cfg(all(feature = "uring", target_os = "linux"))]
+fn read_batch(paths: &[PathBuf]) -> Vec<io::Result<Vec<u8>>> {
+    crate::uring::read_files(paths).unwrap_or_default()
+}
+
+/// 使用io_uring批量读取文件(`uring` 功能), 返回空列表时逐个读取
+#[cfg(not(all(feature = "uring", target_os = "linux")))]
+fn read_batch(_paths: &[PathBuf]) -> Vec<io::Result<Vec<u8>>> {
+    vec![]
+}
+
+/// `copy_if_different` 比较源文件和目标文件的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compare {
+    /// 修改时间不同
+    Mtime,
+    /// 大小不同
+    Size,
+    /// 内容的SHA-256摘要不同(大小不同时不读取内容)
+    Digest,
+}
+
+/// 目标文件不存在或源文件的修改时间比目标文件新时复制文件(包括父级目录), 返回是否复制了文件
+///
+/// 复制后目标文件的修改时间设置为源文件的修改时间.
+///
+/// # 参数
+///
+/// * `src` - 源文件路径
+/// * `dst` - 目标文件路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let src_path = "./target/__test/file_test/copy_if_newer/src/app.css";
+///     let dst_path = "./target/__test/file_test/copy_if_newer/dst/app.css";
+///     file::write_text_file(src_path, "body {}").unwrap();
+///     let _ = std::fs::remove_file(dst_path);
+///
+///     assert!(file::copy_if_newer(src_path, dst_path).unwrap());
+///     assert!(!file::copy_if_newer(src_path, dst_path).unwrap());
+///     assert_eq!(file::read_text_file(dst_path).unwrap(), "body {}");
+///
+///     // read-only source files are copied with their modification time
+///     #[cfg(unix)]
+///     {
+///         let readonly_path = "./target/__test/file_test/copy_if_newer/src/readonly.css";
+///         let copied_path = "./target/__test/file_test/copy_if_newer/dst/readonly.css";
+///         let _ = std::fs::remove_file(readonly_path);
+///         let _ = std::fs::remove_file(copied_path);
+///         file::write_text_file(readonly_path, "a {}").unwrap();
+///         let mut permissions = std::fs::metadata(readonly_path).unwrap().permissions();
+///         permissions.set_readonly(true);
+///         std::fs::set_permissions(readonly_path, permissions).unwrap();
+///
+///         assert!(file::copy_if_newer(readonly_path, copied_path).unwrap());
+///         assert_eq!(
+///             std::fs::metadata(copied_path).unwrap().modified().unwrap(),
+///             std::fs::metadata(readonly_path).unwrap().modified().unwrap()
+///         );
+///         assert!(!file::copy_if_newer(readonly_path, copied_path).unwrap());
+///     }
+/// }
+/// ```
+pub fn copy_if_newer<S: AsPath + ?Sized, T: AsPath + ?Sized>(src: &S, dst: &T) -> FsIOResult<bool> {
+    let source_path = src.as_path();
+    let target_path = dst.as_path();
+
+    let source_metadata = metadata_of(source_path)?;
+    if target_path.exists() {
+        let target_metadata = metadata_of(target_path)?;
+        let newer = match (source_metadata.modified(), target_metadata.modified()) {
+            (Ok(source_modified), Ok(target_modified)) => source_modified > target_modified,
+            _ => true,
+        };
+        if !newer {
+            return Ok(false);
+        }
+    }
+
+    copy_with_mtime(source_path, target_path, &source_metadata)?;
+    Ok(true)
+}
+
+/// 目标文件不存在或与源文件不同时复制文件(包括父级目录), 返回是否复制了文件
+///
+/// 复制后目标文件的修改时间设置为源文件的修改时间.
+///
+/// # 参数
+///
+/// * `src` - 源文件路径
+/// * `dst` - 目标文件路径
+/// * `compare` - 比较方式
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::file::Compare;
+///
+/// fn main() {
+///     let src_path = "./target/__test/file_test/copy_if_different/src/logo.svg";
+///     let dst_path = "./target/__test/file_test/copy_if_different/dst/logo.svg";
+///     file::write_text_file(src_path, "<svg/>").unwrap();
+///     let _ = std::fs::remove_file(dst_path);
+///
+///     assert!(file::copy_if_different(src_path, dst_path, Compare::Digest).unwrap());
+///     assert!(!file::copy_if_different(src_path, dst_path, Compare::Digest).unwrap());
+///     assert!(!file::copy_if_different(src_path, dst_path, Compare::Mtime).unwrap());
+///
+///     file::write_text_file(dst_path, "<svg></svg>").unwrap();
+///     assert!(file::copy_if_different(src_path, dst_path, Compare::Size).unwrap());
+///     assert_eq!(file::read_text_file(dst_path).unwrap(), "<svg/>");
+/// }
+/// ```
+pub fn copy_if_different<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    src: &S,
+    dst: &T,
+    compare: Compare,
+) -> FsIOResult<bool> {
+    let source_path = src.as_path();
+    let target_path = dst.as_path();
+
+    let source_metadata = metadata_of(source_path)?;
+    if target_path.exists() {
+        let target_metadata = metadata_of(target_path)?;
+        let different = match compare {
+            Compare::Mtime => match (source_metadata.modified(), target_metadata.modified()) {
+                (Ok(source_modified), Ok(target_modified)) => source_modified != target_modified,
+                _ => true,
+            },
+            Compare::Size => source_metadata.len() != target_metadata.len(),
+            Compare::Digest => {
+                source_metadata.len() != target_metadata.len()
+                    || sha256_of(source_path)? != sha256_of(target_path)?
+            }
+        };
+        if !different {
+            return Ok(false);
+        }
+    }
+
+    copy_with_mtime(source_path, target_path, &source_metadata)?;
+    Ok(true)
+}
+
+fn metadata_of(path: &Path) -> FsIOResult<Metadata> {
+    match metadata(path) {
+        Ok(value) => Ok(value),
+        Err(error) => Err(FsIOError::io(Operation::Metadata, &path, error)),
+    }
+}
+
+/// 复制文件(包括父级目录)并将目标文件的修改时间设置为源文件的修改时间
+fn copy_with_mtime(src: &Path, dst: &Path, source_metadata: &Metadata) -> FsIOResult<()> {
+    directory::create_parent(&dst)?;
+
+    let result = copy(src, dst).and_then(|_| match source_metadata.modified() {
+        // the copy has the permissions of the source, which may be read-only
+        Ok(modified) => open_for_times(dst).and_then(|file| file.set_modified(modified)),
+        Err(_) => Ok(()),
+    });
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(
+            Operation::Copy(dst.to_path_buf()),
+            &src,
+            error,
+        )),
+    }
+}
+
+/// 打开文件用于修改时间(不需要写入权限)
+#[cfg(windows)]
+fn open_for_times(path: &Path) -> io::Result<File> {
+    use std::os::windows::fs::OpenOptionsExt;
+
+    // FILE_WRITE_ATTRIBUTES
+    OpenOptions::new().access_mode(0x100).open(path)
+}
+
+/// 打开文件用于修改时间(不需要写入权限)
+#[cfg(not(windows))]
+fn open_for_times(path: &Path) -> io::Result<File> {
+    File::open(path)
+}
+
+/// 按块计算文件内容的SHA-256摘要
+fn sha256_of(path: &Path) -> FsIOResult<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    let mut reader = ChunkReader::open(path, 64 * 1024)?;
+    let mut buffer = vec![];
+    while reader.next_into(&mut buffer)? > 0 {
+        hasher.update(&buffer);
+    }
+    Ok(hasher.result().as_ref().to_vec())
+}
+
+/// 仅在文件不存在或内容与给定数据不同时写入文件(包括父级目录), 返回是否写入了文件
+///
+/// 内容相同时不修改文件, 文件的修改时间保持不变.
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `data` - 文件内容
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/ensure_content/generated.rs";
+///     let _ = std::fs::remove_file(file_path);
+///
+///     assert!(file::ensure_content(file_path, b"pub const VERSION: u32 = 1;").unwrap());
+///     let modified = std::fs::metadata(file_path).unwrap().modified().unwrap();
+///
+///     assert!(!file::ensure_content(file_path, b"pub const VERSION: u32 = 1;").unwrap());
+///     assert_eq!(std::fs::metadata(file_path).unwrap().modified().unwrap(), modified);
+///
+///     assert!(file::ensure_content(file_path, b"pub const VERSION: u32 = 2;").unwrap());
+///     assert_eq!(file::read_file(file_path).unwrap(), b"pub const VERSION: u32 = 2;");
+/// }
+/// ```
+pub fn ensure_content<T: AsPath + ?Sized>(path: &T, data: &[u8]) -> FsIOResult<bool> {
+    let file_path = path.as_path();
+
+    if file_path.is_file() && content_equals(file_path, data)? {
+        return Ok(false);
+    }
+
+    write_file(file_path, data)?;
+    Ok(true)
+}
+
+/// 按块比较文件内容与给定数据, 大小不同时不读取内容
+fn content_equals(path: &Path, data: &[u8]) -> FsIOResult<bool> {
+    if metadata_of(path)?.len() != data.len() as u64 {
+        return Ok(false);
+    }
+
+    let mut reader = ChunkReader::open(path, 64 * 1024)?;
+    let mut buffer = vec![];
+    let mut offset = 0;
+    loop {
+        let size = reader.next_into(&mut buffer)?;
+        if size == 0 {
+            return Ok(offset == data.len());
+        }
+        if offset + size > data.len() || buffer[..size] != data[offset..offset + size] {
+            return Ok(false);
+        }
+        offset += size;
+    }
+}
+
+/// 使用指定的权限创建文件(如果存在则覆盖文件)并写入内容(包括父级目录)
+///
+/// 在unix上文件在创建时就使用 `mode` 权限, 并在写入内容前通过文件句柄设置权限(不受umask影响, 覆盖已存在的文件时也会生效),
+/// 不存在先写入内容再修改权限的时间窗口. 其他平台上忽略 `mode`.
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `data` - 文件内容
+/// * `mode` - unix文件权限, 例如 `0o600`
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/write_file_with_mode/secret.key";
+///     file::write_file_with_mode(file_path, b"s3cr3t", 0o600).unwrap();
+///     assert_eq!(file::read_file(file_path).unwrap(), b"s3cr3t");
+///
+///     #[cfg(unix)]
+///     {
+///         use std::os::unix::fs::PermissionsExt;
+///
+///         let mode = std::fs::metadata(file_path).unwrap().permissions().mode();
+///         assert_eq!(mode & 0o777, 0o600);
+///     }
+/// }
+/// ```
+pub fn write_file_with_mode<T: AsPath + ?Sized>(
+    path: &T,
+    data: &[u8],
+    mode: u32,
+) -> FsIOResult<()> {
+    let file_path = path.as_path();
+    directory::create_parent(&file_path)?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(mode);
+    }
+
+    match options.open(file_path) {
+        Ok(mut file) => {
+            let result = apply_mode(&file, mode)
+                .and_then(|_| file.write_all(data))
+                .and_then(|_| file.sync_all());
+            match result {
+                Ok(_) => Ok(()),
+                Err(error) => Err(FsIOError::io(Operation::Write, &file_path, error)),
+            }
+        }
+        Err(error) => Err(FsIOError::io(Operation::Create, &file_path, error)),
+    }
+}
+
+#[cfg(unix)]
+fn apply_mode(file: &File, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    file.set_permissions(std::fs::Permissions::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn apply_mode(_file: &File, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
+
+/// `install` 的选项
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallOptions {
+    /// 目标文件的unix权限(其他平台上忽略)
+    pub mode: u32,
+    /// 目标文件的所有者(用户ID, 组ID), 为None时不修改(只在unix上生效, 通常需要root权限)
+    pub owner: Option<(u32, u32)>,
+    /// 目标文件已存在时, 先复制一份到追加该后缀的路径作为备份(例如 `~` 备份为 `app.conf~`), 为None时不备份
+    pub backup: Option<String>,
+    /// 是否先写入同目录下的临时文件再重命名为目标文件, 读取目标文件的进程不会看到写了一半的文件
+    pub atomic: bool,
+}
+
+impl Default for InstallOptions {
+    fn default() -> Self {
+        InstallOptions {
+            mode: 0o755,
+            owner: None,
+            backup: None,
+            atomic: true,
+        }
+    }
+}
+
+/// 安装文件(与unix的 `install` 命令相同): 复制文件(包括父级目录), 设置权限和所有者, 可选备份目标文件并原子地替换
+///
+/// 权限和所有者在写入文件内容之前设置.
+///
+/// # 参数
+///
+/// * `src` - 源文件路径
+/// * `dst` - 目标文件路径
+/// * `options` - 安装选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::file::InstallOptions;
+///
+/// fn main() {
+///     let src_path = "./target/__test/file_test/install/build/app.conf";
+///     let dst_path = "./target/__test/file_test/install/etc/app.conf";
+///     file::write_text_file(src_path, "port = 8080").unwrap();
+///     file::write_text_file(dst_path, "port = 80").unwrap();
+///
+///     let options = InstallOptions {
+///         mode: 0o640,
+///         backup: Some("~".to_string()),
+///         ..InstallOptions::default()
+///     };
+///     file::install(src_path, dst_path, options).unwrap();
+///
+///     assert_eq!(file::read_text_file(dst_path).unwrap(), "port = 8080");
+///     assert_eq!(file::read_text_file("./target/__test/file_test/install/etc/app.conf~").unwrap(), "port = 80");
+///
+///     #[cfg(unix)]
+///     {
+///         use std::os::unix::fs::PermissionsExt;
+///
+///         let mode = std::fs::metadata(dst_path).unwrap().permissions().mode();
+///         assert_eq!(mode & 0o777, 0o640);
+///     }
+/// }
+/// ```
+pub fn install<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    src: &S,
+    dst: &T,
+    options: InstallOptions,
+) -> FsIOResult<()> {
+    let source_path = src.as_path();
+    let target_path = dst.as_path();
+
+    if !source_path.is_file() {
+        return Err(FsIOError::NotFile(
+            format!("Path: {:?} is not a file.", &source_path).to_string(),
+        ));
+    }
+    if is_same_file(source_path, target_path).unwrap_or(false) {
+        return Err(FsIOError::io(
+            Operation::Copy(target_path.to_path_buf()),
+            &source_path,
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "source and destination are the same file",
+            ),
+        ));
+    }
+    directory::create_parent(&target_path)?;
+
+    if let Some(ref suffix) = options.backup {
+        if target_path.is_file() {
+            let mut backup_path = target_path.as_os_str().to_os_string();
+            backup_path.push(suffix);
+            let backup_path = PathBuf::from(backup_path);
+            if let Err(error) = copy(target_path, &backup_path) {
+                return Err(FsIOError::io(
+                    Operation::Copy(backup_path),
+                    &target_path,
+                    error,
+                ));
+            }
+        }
+    }
+
+    let write_path = if options.atomic {
+        unique_sibling(target_path, "install")
+    } else {
+        target_path.to_path_buf()
+    };
+
+    let result = File::open(source_path).and_then(|mut source| {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&write_path)?;
+        apply_mode(&file, options.mode)?;
+        apply_owner(&file, options.owner)?;
+        io::copy(&mut source, &mut file)?;
+        file.sync_all()
+    });
+    if let Err(error) = result {
+        if options.atomic {
+            let _ = remove_file(&write_path);
+        }
+        return Err(FsIOError::io(
+            Operation::Copy(target_path.to_path_buf()),
+            &source_path,
+            error,
+        ));
+    }
+
+    if options.atomic {
+        if let Err(error) = fs::rename(&write_path, target_path) {
+            let _ = remove_file(&write_path);
+            return Err(FsIOError::io(
+                Operation::Rename(target_path.to_path_buf()),
+                &write_path,
+                error,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn apply_owner(file: &File, owner: Option<(u32, u32)>) -> io::Result<()> {
+    match owner {
+        Some((uid, gid)) => std::os::unix::fs::fchown(file, Some(uid), Some(gid)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+fn apply_owner(_file: &File, _owner: Option<(u32, u32)>) -> io::Result<()> {
+    Ok(())
+}
+
+/// 交换两个路径(文件或目录)的内容, 两个路径都必须存在并位于同一个文件系统上
+///
+/// Linux上使用 `renameat2(RENAME_EXCHANGE)`, macOS上使用 `renamex_np(RENAME_SWAP)`, 一次原子地完成交换.
+/// 其他平台或文件系统不支持时, 使用同目录下的临时路径重命名三次: `a` -> 临时路径, `b` -> `a`, 临时路径 -> `b`,
+/// 中间步骤失败时会尝试恢复原状. 这种方式不是原子的: 其他进程可能短暂地看不到 `a`,
+/// 并且进程在中间崩溃时 `a` 原来的内容会留在 `a` 旁边的隐藏临时路径(`.<name>.swap-<pid>-<n>`)中.
+///
+/// # 参数
+///
+/// * `a` - 第一个路径
+/// * `b` - 第二个路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let blue = "./target/__test/file_test/swap/blue.conf";
+///     let green = "./target/__test/file_test/swap/green.conf";
+///     file::write_text_file(blue, "upstream = blue").unwrap();
+///     file::write_text_file(green, "upstream = green").unwrap();
+///
+///     file::swap(blue, green).unwrap();
+///     assert_eq!(file::read_text_file(blue).unwrap(), "upstream = green");
+///     assert_eq!(file::read_text_file(green).unwrap(), "upstream = blue");
+///
+///     assert!(file::swap(blue, "./target/__test/file_test/swap/missing.conf").is_err());
+/// }
+/// ```
+pub fn swap<A: AsPath + ?Sized, B: AsPath + ?Sized>(a: &A, b: &B) -> FsIOResult<()> {
+    let first_path = a.as_path();
+    let second_path = b.as_path();
+
+    for path in [first_path, second_path] {
+        if let Err(error) = fs::symlink_metadata(path) {
+            return Err(FsIOError::io(Operation::Metadata, &path, error));
+        }
+    }
+
+    match rename_native(first_path, second_path, NativeRename::Exchange) {
+        Ok(true) => return Ok(()),
+        Ok(false) => {}
+        Err(error) => {
+            return Err(FsIOError::io(
+                Operation::Rename(second_path.to_path_buf()),
+                &first_path,
+                error,
+            ))
+        }
+    }
+
+    let temp_path = unique_sibling(first_path, "swap");
+    if let Err(error) = fs::rename(first_path, &temp_path) {
+        return Err(FsIOError::io(
+            Operation::Rename(temp_path),
+            &first_path,
+            error,
+        ));
+    }
+    if let Err(error) = fs::rename(second_path, first_path) {
+        let _ = fs::rename(&temp_path, first_path);
+        return Err(FsIOError::io(
+            Operation::Rename(first_path.to_path_buf()),
+            &second_path,
+            error,
+        ));
+    }
+    if let Err(error) = fs::rename(&temp_path, second_path) {
+        if fs::rename(first_path, second_path).is_ok() {
+            let _ = fs::rename(&temp_path, first_path);
+        }
+        return Err(FsIOError::io(
+            Operation::Rename(second_path.to_path_buf()),
+            &temp_path,
+            error,
+        ));
+    }
+
+    Ok(())
+}
+
+/// `rename` 在目标路径已存在时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overwrite {
+    /// 不覆盖, 目标路径已存在时返回 `FsIOError::AlreadyExist`
+    Never,
+    /// 总是覆盖
+    Always,
+    /// 只在目标路径的修改时间比源路径早时覆盖
+    IfOlder,
+}
+
+/// 移动或重命名文件(包括父级目录), 并明确指定目标路径已存在时是否覆盖, 返回是否移动了文件
+///
+/// `Overwrite::Never` 在Linux和macOS上使用不覆盖的原子重命名, 其他平台(或文件系统不支持时)对文件创建硬链接后删除源路径,
+/// 同样不会覆盖目标路径. 目录和符号链接(以及不支持硬链接的文件系统)只能先检查目标路径再重命名,
+/// 检查之后由其他进程创建的目标路径会被覆盖.
+/// `Overwrite::IfOlder` 在目标路径较新时不移动文件并返回 `Ok(false)`.
+///
+/// # 参数
+///
+/// * `src` - 源路径
+/// * `dst` - 目标路径
+/// * `overwrite` - 目标路径已存在时的处理方式
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::file::Overwrite;
+///
+/// fn main() {
+///     let src_path = "./target/__test/file_test/rename/incoming/report.csv";
+///     let dst_path = "./target/__test/file_test/rename/archive/2024/report.csv";
+///     let _ = std::fs::remove_file(dst_path);
+///     file::write_text_file(src_path, "a,b").unwrap();
+///
+///     assert!(file::rename(src_path, dst_path, Overwrite::Never).unwrap());
+///     assert_eq!(file::read_text_file(dst_path).unwrap(), "a,b");
+///
+///     file::write_text_file(src_path, "c,d").unwrap();
+///     let error = file::rename(src_path, dst_path, Overwrite::Never).unwrap_err();
+///     assert!(error.is_already_exists());
+///
+///     assert!(file::rename(src_path, dst_path, Overwrite::Always).unwrap());
+///     assert_eq!(file::read_text_file(dst_path).unwrap(), "c,d");
+/// }
+/// ```
+pub fn rename<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    src: &S,
+    dst: &T,
+    overwrite: Overwrite,
+) -> FsIOResult<bool> {
+    let source_path = src.as_path();
+    let target_path = dst.as_path();
+
+    let source_metadata = match fs::symlink_metadata(source_path) {
+        Ok(value) => value,
+        Err(error) => return Err(FsIOError::io(Operation::Metadata, &source_path, error)),
+    };
+    directory::create_parent(&target_path)?;
+
+    let already_exists = || {
+        FsIOError::AlreadyExist(
+            format!(
+                "Unable to rename: {:?} to: {:?}, destination already exists.",
+                &source_path, &target_path
+            )
+            .to_string(),
+        )
+    };
+    let target_metadata = fs::symlink_metadata(target_path).ok();
+
+    match overwrite {
+        Overwrite::Never => {
+            if target_metadata.is_some() {
+                return Err(already_exists());
+            }
+            match rename_native(source_path, target_path, NativeRename::NoReplace) {
+                Ok(true) => return Ok(true),
+                // creating a hard link fails atomically when the target exists
+                Ok(false) if source_metadata.is_file() => {
+                    match fs::hard_link(source_path, target_path) {
+                        Ok(_) => {
+                            return match remove_file(source_path) {
+                                Ok(_) => Ok(true),
+                                Err(error) => {
+                                    let _ = remove_file(target_path);
+                                    Err(FsIOError::io(Operation::Delete, &source_path, error))
+                                }
+                            }
+                        }
+                        Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                            return Err(already_exists())
+                        }
+                        // file systems without hard links are renamed after the check above
+                        Err(_) => {}
+                    }
+                }
+                Ok(false) => {}
+                Err(error) if error.kind() == io::ErrorKind::AlreadyExists => {
+                    return Err(already_exists())
+                }
+                Err(error) => {
+                    return Err(FsIOError::io(
+                        Operation::Rename(target_path.to_path_buf()),
+                        &source_path,
+                        error,
+                    ))
+                }
+            }
+        }
+        Overwrite::IfOlder => {
+            if let Some(target_metadata) = target_metadata {
+                let older = match (target_metadata.modified(), source_metadata.modified()) {
+                    (Ok(target_modified), Ok(source_modified)) => target_modified < source_modified,
+                    _ => true,
+                };
+                if !older {
+                    return Ok(false);
+                }
+            }
+        }
+        Overwrite::Always => {}
+    }
+
+    match fs::rename(source_path, target_path) {
+        Ok(_) => Ok(true),
+        Err(error) => Err(FsIOError::io(
+            Operation::Rename(target_path.to_path_buf()),
+            &source_path,
+            error,
+        )),
+    }
+}
+
+/// 与 `rename` 相同, 移动文件后将源路径和目标路径的父级目录同步到磁盘(`directory::sync`), 崩溃后重命名仍然有效
+///
+/// # 参数
+///
+/// * `src` - 源路径
+/// * `dst` - 目标路径
+/// * `overwrite` - 目标路径已存在时的处理方式
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::file::Overwrite;
+///
+/// fn main() {
+///     let src_path = "./target/__test/file_test/rename_synced/state.json.new";
+///     let dst_path = "./target/__test/file_test/rename_synced/state.json";
+///     file::write_text_file(src_path, "{}").unwrap();
+///
+///     assert!(file::rename_synced(src_path, dst_path, Overwrite::Always).unwrap());
+///     assert_eq!(file::read_text_file(dst_path).unwrap(), "{}");
+/// }
+/// ```
+pub fn rename_synced<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    src: &S,
+    dst: &T,
+    overwrite: Overwrite,
+) -> FsIOResult<bool> {
+    let source_path = src.as_path();
+    let target_path = dst.as_path();
+
+    if !rename(source_path, target_path, overwrite)? {
+        return Ok(false);
+    }
+
+    directory::sync_parent(target_path)?;
+    if source_path.parent() != target_path.parent() {
+        directory::sync_parent(source_path)?;
+    }
+    Ok(true)
+}
+
+/// 使用平台提供的原子重命名
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NativeRename {
+    /// 交换两个路径
+    Exchange,
+    /// 目标路径已存在时失败(`AlreadyExists`)
+    NoReplace,
+}
+
+/// 使用平台的原子重命名, 平台或文件系统不支持时返回 `Ok(false)`
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(crate) fn rename_native(from: &Path, to: &Path, mode: NativeRename) -> io::Result<bool> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let to_c_string = |path: &Path| {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))
+    };
+    let from = to_c_string(from)?;
+    let to = to_c_string(to)?;
+    let flags = match mode {
+        NativeRename::Exchange => libc::RENAME_EXCHANGE,
+        NativeRename::NoReplace => libc::RENAME_NOREPLACE,
+    };
+
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_renameat2,
+            libc::AT_FDCWD,
+            from.as_ptr(),
+            libc::AT_FDCWD,
+            to.as_ptr(),
+            flags,
+        )
+    };
+    if result == 0 {
+        return Ok(true);
+    }
+
+    let error = io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(libc::ENOSYS) | Some(libc::EINVAL) | Some(libc::EOPNOTSUPP) => Ok(false),
+        _ => Err(error),
+    }
+}
+
+/// 使用平台的原子重命名, 平台或文件系统不支持时返回 `Ok(false)`
+#[cfg(target_os = "macos")]
+pub(crate) fn rename_native(from: &Path, to: &Path, mode: NativeRename) -> io::Result<bool> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let to_c_string = |path: &Path| {
+        CString::new(path.as_os_str().as_bytes())
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))
+    };
+    let from = to_c_string(from)?;
+    let to = to_c_string(to)?;
+    let flags = match mode {
+        NativeRename::Exchange => libc::RENAME_SWAP,
+        NativeRename::NoReplace => libc::RENAME_EXCL,
+    };
+
+    if unsafe { libc::renamex_np(from.as_ptr(), to.as_ptr(), flags) } == 0 {
+        return Ok(true);
+    }
+
+    let error = io::Error::last_os_error();
+    match error.raw_os_error() {
+        Some(libc::ENOTSUP) | Some(libc::EINVAL) => Ok(false),
+        _ => Err(error),
+    }
+}
+
+/// 使用平台的原子重命名, 平台或文件系统不支持时返回 `Ok(false)`
+#[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+pub(crate) fn rename_native(_from: &Path, _to: &Path, _mode: NativeRename) -> io::Result<bool> {
+    Ok(false)
+}
+
+/// 为文件预先分配磁盘空间(包括父级目录, 如果文件不存在则创建文件), 磁盘空间不足时立即返回错误
+///
+/// 文件长度小于 `size` 时扩展到 `size`(新增部分读取为0), 不会缩短已有的文件.
+/// Linux使用 `fallocate`, macOS使用 `F_PREALLOCATE`, Windows使用 `SetFileInformationByHandle`(`FileAllocationInfo`),
+/// 文件系统不支持或其他系统只设置文件长度(不一定预留磁盘空间).
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `size` - 字节数
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+///
+/// fn main() {
+///     let file_path = "./target/__test/file_test/allocate/download.part";
+///     file::delete(file_path).unwrap();
+///
+///     file::allocate(file_path, 1024 * 1024).unwrap();
+///     assert_eq!(std::fs::metadata(file_path).unwrap().len(), 1024 * 1024);
+///
+///     // existing files are never shortened
+///     file::allocate(file_path, 1024).unwrap();
+///     assert_eq!(std::fs::metadata(file_path).unwrap().len(), 1024 * 1024);
+/// }
+/// ```
+pub fn allocate<T: AsPath + ?Sized>(path: &T, size: u64) -> FsIOResult<()> {
+    let file_path = path.as_path();
+
+    directory::create_parent(file_path)?;
+
+    let file = match OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(file_path)
+    {
+        Ok(file) => file,
+        Err(error) => return Err(FsIOError::io(Operation::Create, &file_path, error)),
+    };
+
+    let result = file.metadata().and_then(|metadata| {
+        if metadata.len() >= size {
+            return Ok(());
+        }
+        if !allocate_native(&file, metadata.len(), size)? {
+            file.set_len(size)?;
+        }
+        Ok(())
+    });
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::Write, &file_path, error)),
+    }
+}
+
+/// 使用平台的空间分配将文件扩展到 `size`, 平台或文件系统不支持时返回 `Ok(false)`
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn allocate_native(file: &File, _length: u64, size: u64) -> io::Result<bool> {
+    use std::os::unix::io::AsRawFd;
+
+    let size = match libc::off_t::try_from(size) {
+        Ok(size) => size,
+        Err(_) => return Err(io::Error::from(io::ErrorKind::InvalidInput)),
+    };
+    loop {
+        if unsafe { libc::fallocate(file.as_raw_fd(), 0, 0, size) } == 0 {
+            return Ok(true);
+        }
+
+        let error = io::Error::last_os_error();
+        match error.raw_os_error() {
+            Some(libc::EINTR) => continue,
+            Some(libc::EOPNOTSUPP) | Some(libc::ENOSYS) => return Ok(false),
+            _ => return Err(error),
+        }
+    }
+}
+
+/// 使用平台的空间分配将文件扩展到 `size`, 平台或文件系统不支持时返回 `Ok(false)`
+#[cfg(target_os = "macos")]
+fn allocate_native(file: &File, length: u64, size: u64) -> io::Result<bool> {
+    use std::os::unix::io::AsRawFd;
+
+    let mut store = libc::fstore_t {
+        fst_flags: libc::F_ALLOCATECONTIG | libc::F_ALLOCATEALL,
+        fst_posmode: libc::F_PEOFPOSMODE,
+        fst_offset: 0,
+        fst_length: (size - length) as libc::off_t,
+        fst_bytesalloc: 0,
+    };
+    // contiguous space is preferred, but any space will do
+    if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_PREALLOCATE, &mut store) } == -1 {
+        store.fst_flags = libc::F_ALLOCATEALL;
+        if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_PREALLOCATE, &mut store) } == -1 {
+            let error = io::Error::last_os_error();
+            return match error.raw_os_error() {
+                Some(libc::ENOTSUP) | Some(libc::EINVAL) => Ok(false),
+                _ => Err(error),
+            };
+        }
+    }
+
+    // the preallocated space is beyond the end of the file until the length is set
+    file.set_len(size)?;
+    Ok(true)
+}
+
+/// 使用平台的空间分配将文件扩展到 `size`, 平台或文件系统不支持时返回 `Ok(false)`
+#[cfg(windows)]
+fn allocate_native(file: &File, _length: u64, size: u64) -> io::Result<bool> {
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Foundation::{ERROR_INVALID_FUNCTION, ERROR_NOT_SUPPORTED};
+    use windows_sys::Win32::Storage::FileSystem::{
+        FileAllocationInfo, SetFileInformationByHandle, FILE_ALLOCATION_INFO,
+    };
+
+    let info = match i64::try_from(size) {
+        Ok(size) => FILE_ALLOCATION_INFO {
+            AllocationSize: size,
+        },
+        Err(_) => return Err(io::Error::from(io::ErrorKind::InvalidInput)),
+    };
+    let result = unsafe {
+        SetFileInformationByHandle(
+            file.as_raw_handle(),
+            FileAllocationInfo,
+            &info as *const FILE_ALLOCATION_INFO as *const std::ffi::c_void,
+            std::mem::size_of::<FILE_ALLOCATION_INFO>() as u32,
+        )
+    };
+    if result == 0 {
+        let error = io::Error::last_os_error();
+        return match error.raw_os_error() {
+            Some(code)
+                if code as u32 == ERROR_INVALID_FUNCTION || code as u32 == ERROR_NOT_SUPPORTED =>
+            {
+                Ok(false)
+            }
+            _ => Err(error),
+        };
+    }
+
+    // the allocated space is beyond the end of the file until the length is set
+    file.set_len(size)?;
+    Ok(true)
+}
+
+/// 使用平台的空间分配将文件扩展到 `size`, 平台或文件系统不支持时返回 `Ok(false)`
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    windows
+)))]
+fn allocate_native(_file: &File, _length: u64, _size: u64) -> io::Result<bool> {
+    Ok(false)
+}
@@ -0,0 +1,160 @@
+//! # dry_run
+//!
+//! FileSystem decorator recording mutating operations instead of performing them.
+//!
+use std::fmt;
+use std::fmt::Display;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::filesystem::{FileSystem, OsFs};
+use crate::result::FsIOResult;
+
+/// 文件系统的修改操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsOperation {
+    /// 创建或覆盖文件(路径, 字节数)
+    WriteFile(PathBuf, usize),
+    /// 追加文件(路径, 字节数)
+    AppendFile(PathBuf, usize),
+    /// 删除文件
+    DeleteFile(PathBuf),
+    /// 创建目录
+    CreateDirectory(PathBuf),
+    /// 删除目录
+    DeleteDirectory(PathBuf),
+    /// 复制文件(源路径, 目标路径)
+    CopyFile(PathBuf, PathBuf),
+    /// 移动文件或目录(源路径, 目标路径)
+    Rename(PathBuf, PathBuf),
+}
+
+impl Display for FsOperation {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::WriteFile(path, size) => write!(formatter, "write {:?} ({} bytes)", path, size),
+            Self::AppendFile(path, size) => {
+                write!(formatter, "append {:?} ({} bytes)", path, size)
+            }
+            Self::DeleteFile(path) => write!(formatter, "delete file {:?}", path),
+            Self::CreateDirectory(path) => write!(formatter, "create directory {:?}", path),
+            Self::DeleteDirectory(path) => write!(formatter, "delete directory {:?}", path),
+            Self::CopyFile(src, dst) => write!(formatter, "copy {:?} to {:?}", src, dst),
+            Self::Rename(src, dst) => write!(formatter, "rename {:?} to {:?}", src, dst),
+        }
+    }
+}
+
+/// 空运行文件系统, 修改操作只记录到操作日志而不执行, 读取操作使用内部文件系统
+///
+/// # 示例
+///
+/// ```
+/// use x_io::filesystem::{DryRunFs, FileSystem, FsOperation, OsFs};
+/// use std::path::{Path, PathBuf};
+///
+/// fn main() {
+///     let fs = DryRunFs::new(OsFs);
+///     let file_path = Path::new("./target/__test/filesystem_test/dry_run/file.txt");
+///     let result = fs.write_text_file(file_path, "some content");
+///     assert!(result.is_ok());
+///
+///     assert!(!file_path.exists());
+///     assert_eq!(
+///         fs.operations(),
+///         vec![FsOperation::WriteFile(PathBuf::from(file_path), 12)]
+///     );
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct DryRunFs<F: FileSystem = OsFs> {
+    inner: F,
+    operations: Mutex<Vec<FsOperation>>,
+}
+
+impl<F: FileSystem> DryRunFs<F> {
+    /// 创建空运行文件系统
+    ///
+    /// # 参数
+    ///
+    /// * `inner` - 用于读取操作的文件系统
+    pub fn new(inner: F) -> DryRunFs<F> {
+        DryRunFs {
+            inner,
+            operations: Mutex::new(vec![]),
+        }
+    }
+
+    /// 返回已记录的操作
+    pub fn operations(&self) -> Vec<FsOperation> {
+        self.lock().clone()
+    }
+
+    /// 返回并清空已记录的操作
+    pub fn take_operations(&self) -> Vec<FsOperation> {
+        mem::take(&mut *self.lock())
+    }
+
+    fn record(&self, operation: FsOperation) -> FsIOResult<()> {
+        self.lock().push(operation);
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<FsOperation>> {
+        match self.operations.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl<F: FileSystem> FileSystem for DryRunFs<F> {
+    fn read_file(&self, path: &Path) -> FsIOResult<Vec<u8>> {
+        self.inner.read_file(path)
+    }
+
+    fn write_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        self.record(FsOperation::WriteFile(path.to_path_buf(), data.len()))
+    }
+
+    fn append_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        self.record(FsOperation::AppendFile(path.to_path_buf(), data.len()))
+    }
+
+    fn delete_file(&self, path: &Path) -> FsIOResult<()> {
+        self.record(FsOperation::DeleteFile(path.to_path_buf()))
+    }
+
+    fn create_directory(&self, path: &Path) -> FsIOResult<()> {
+        self.record(FsOperation::CreateDirectory(path.to_path_buf()))
+    }
+
+    fn delete_directory(&self, path: &Path) -> FsIOResult<()> {
+        self.record(FsOperation::DeleteDirectory(path.to_path_buf()))
+    }
+
+    fn copy_file(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        self.record(FsOperation::CopyFile(src.to_path_buf(), dst.to_path_buf()))
+    }
+
+    fn rename(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        self.record(FsOperation::Rename(src.to_path_buf(), dst.to_path_buf()))
+    }
+
+    fn list_directory(&self, path: &Path) -> FsIOResult<Vec<PathBuf>> {
+        self.inner.list_directory(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.inner.is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.inner.is_dir(path)
+    }
+}
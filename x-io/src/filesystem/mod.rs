@@ -0,0 +1,178 @@
+//! # filesystem
+//!
+//! The FileSystem trait, abstracting the file system operations so they can be decorated
+//! (dry run, read only, overlay, ...) or replaced.
+//!
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{FsIOError, Operation};
+use crate::result::FsIOResult;
+use crate::{directory, file};
+
+pub use dry_run::{DryRunFs, FsOperation};
+pub use overlay::OverlayFs;
+pub use read_only::ReadOnlyFs;
+
+mod dry_run;
+mod overlay;
+mod read_only;
+
+/// 文件系统操作接口
+pub trait FileSystem {
+    /// 读取文件内容
+    fn read_file(&self, path: &Path) -> FsIOResult<Vec<u8>>;
+
+    /// 创建文件, 如果存在则覆盖文件
+    fn write_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()>;
+
+    /// 追加文件, 如果不存在则创建文件
+    fn append_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()>;
+
+    /// 删除文件, 如果文件不存在返回Ok
+    fn delete_file(&self, path: &Path) -> FsIOResult<()>;
+
+    /// 创建目录(包括父级目录)
+    fn create_directory(&self, path: &Path) -> FsIOResult<()>;
+
+    /// 删除该目录和任何子文件目录, 如果目录不存在返回Ok
+    fn delete_directory(&self, path: &Path) -> FsIOResult<()>;
+
+    /// 复制文件
+    fn copy_file(&self, src: &Path, dst: &Path) -> FsIOResult<()>;
+
+    /// 移动(重命名)文件或目录
+    fn rename(&self, src: &Path, dst: &Path) -> FsIOResult<()>;
+
+    /// 返回目录下的条目路径(按字典序)
+    fn list_directory(&self, path: &Path) -> FsIOResult<Vec<PathBuf>>;
+
+    /// 返回路径是否存在
+    fn exists(&self, path: &Path) -> bool;
+
+    /// 返回路径是否为文件
+    fn is_file(&self, path: &Path) -> bool;
+
+    /// 返回路径是否为目录
+    fn is_dir(&self, path: &Path) -> bool;
+
+    /// 读取文本文件内容
+    fn read_text_file(&self, path: &Path) -> FsIOResult<String> {
+        let data = self.read_file(path)?;
+        match String::from_utf8(data) {
+            Ok(text) => Ok(text),
+            Err(error) => Err(FsIOError::Parse(
+                format!("File: {:?} is not valid UTF-8.", path).to_string(),
+                Some(Box::new(error)),
+            )),
+        }
+    }
+
+    /// 创建文本文件, 如果存在则覆盖文件
+    fn write_text_file(&self, path: &Path, text: &str) -> FsIOResult<()> {
+        self.write_file(path, text.as_bytes())
+    }
+}
+
+/// 操作系统的文件系统, 直接调用 `file` 和 `directory` 模块的函数
+///
+/// # 示例
+///
+/// ```
+/// use x_io::filesystem::{FileSystem, OsFs};
+/// use std::path::Path;
+///
+/// fn main() {
+///     let fs = OsFs;
+///     let file_path = Path::new("./target/__test/filesystem_test/os_fs/file.txt");
+///     let result = fs.write_text_file(file_path, "some content");
+///     assert!(result.is_ok());
+///
+///     assert!(fs.is_file(file_path));
+///     assert_eq!(fs.read_text_file(file_path).unwrap(), "some content");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OsFs;
+
+impl FileSystem for OsFs {
+    fn read_file(&self, path: &Path) -> FsIOResult<Vec<u8>> {
+        file::read_file(&path)
+    }
+
+    fn write_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        file::write_file(&path, data)
+    }
+
+    fn append_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        file::append_file(&path, data)
+    }
+
+    fn delete_file(&self, path: &Path) -> FsIOResult<()> {
+        file::delete(&path)
+    }
+
+    fn create_directory(&self, path: &Path) -> FsIOResult<()> {
+        directory::create(&path)
+    }
+
+    fn delete_directory(&self, path: &Path) -> FsIOResult<()> {
+        directory::delete(&path)
+    }
+
+    fn copy_file(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        directory::create_parent(&dst)?;
+
+        match fs::copy(src, dst) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(FsIOError::io(
+                Operation::Copy(dst.to_path_buf()),
+                &src,
+                error,
+            )),
+        }
+    }
+
+    fn rename(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        directory::create_parent(&dst)?;
+
+        match fs::rename(src, dst) {
+            Ok(_) => Ok(()),
+            Err(error) => Err(FsIOError::io(
+                Operation::Rename(dst.to_path_buf()),
+                &src,
+                error,
+            )),
+        }
+    }
+
+    fn list_directory(&self, path: &Path) -> FsIOResult<Vec<PathBuf>> {
+        let read_dir = match fs::read_dir(path) {
+            Ok(read_dir) => read_dir,
+            Err(error) => return Err(FsIOError::io(Operation::ReadDirectory, &path, error)),
+        };
+
+        let mut entries = vec![];
+        for entry in read_dir {
+            match entry {
+                Ok(entry) => entries.push(entry.path()),
+                Err(error) => return Err(FsIOError::io(Operation::ReadDirectory, &path, error)),
+            }
+        }
+        entries.sort();
+
+        Ok(entries)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        path.is_dir()
+    }
+}
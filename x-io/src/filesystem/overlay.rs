@@ -0,0 +1,246 @@
+//! # overlay
+//!
+//! FileSystem composing a writable upper layer over a read only lower layer.
+//!
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::error::FsIOError;
+use crate::filesystem::FileSystem;
+use crate::result::FsIOResult;
+
+/// 删除标记文件名称的前缀
+const WHITEOUT_PREFIX: &str = ".wh.";
+
+/// 叠加文件系统, 读取时优先使用上层, 上层不存在时使用下层, 所有修改只写入上层
+///
+/// 删除下层的文件或目录时在上层创建删除标记(同目录下名为 `.wh.<名称>` 的空文件), 被标记的路径及其子路径
+/// 不再读取下层, 删除标记不会出现在目录列表中. 追加或移动下层的文件时先将其复制到上层.
+///
+/// 两层使用相同的路径, 通常是指向不同根目录的 `RootedFs`.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+/// use x_io::filesystem::{FileSystem, OverlayFs};
+/// use x_io::safe::RootedFs;
+/// use std::path::{Path, PathBuf};
+///
+/// fn main() {
+///     directory::delete("./target/__test/filesystem_test/overlay").unwrap();
+///     file::write_text_file("./target/__test/filesystem_test/overlay/template/README.md", "template").unwrap();
+///     file::write_text_file("./target/__test/filesystem_test/overlay/template/LICENSE", "MIT").unwrap();
+///     directory::create("./target/__test/filesystem_test/overlay/patch").unwrap();
+///
+///     let fs = OverlayFs::new(
+///         RootedFs::new("./target/__test/filesystem_test/overlay/patch").unwrap(),
+///         RootedFs::new("./target/__test/filesystem_test/overlay/template").unwrap(),
+///     );
+///     fs.write_text_file(Path::new("README.md"), "my project").unwrap();
+///     fs.write_text_file(Path::new("src/main.rs"), "fn main() {}").unwrap();
+///     fs.delete_file(Path::new("LICENSE")).unwrap();
+///
+///     assert_eq!(fs.read_text_file(Path::new("README.md")).unwrap(), "my project");
+///     assert!(!fs.exists(Path::new("LICENSE")));
+///     assert_eq!(
+///         fs.list_directory(Path::new("")).unwrap(),
+///         vec![PathBuf::from("README.md"), PathBuf::from("src")]
+///     );
+///
+///     // the lower layer is not modified
+///     let text = file::read_text_file("./target/__test/filesystem_test/overlay/template/README.md").unwrap();
+///     assert_eq!(text, "template");
+///     assert!(Path::new("./target/__test/filesystem_test/overlay/template/LICENSE").exists());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OverlayFs<U: FileSystem, L: FileSystem> {
+    upper: U,
+    lower: L,
+}
+
+impl<U: FileSystem, L: FileSystem> OverlayFs<U, L> {
+    /// 创建叠加文件系统
+    ///
+    /// # 参数
+    ///
+    /// * `upper` - 上层文件系统(可写)
+    /// * `lower` - 下层文件系统(只读取)
+    pub fn new(upper: U, lower: L) -> OverlayFs<U, L> {
+        OverlayFs { upper, lower }
+    }
+
+    /// 返回上层文件系统
+    pub fn upper(&self) -> &U {
+        &self.upper
+    }
+
+    /// 返回下层文件系统
+    pub fn lower(&self) -> &L {
+        &self.lower
+    }
+
+    /// 返回路径的删除标记路径, 路径没有名称时返回None
+    fn whiteout(path: &Path) -> Option<PathBuf> {
+        let name = path.file_name()?;
+        let mut whiteout_name = OsString::from(WHITEOUT_PREFIX);
+        whiteout_name.push(name);
+        Some(path.with_file_name(whiteout_name))
+    }
+
+    /// 返回路径或其任一父级路径是否被删除标记
+    fn is_whited_out(&self, path: &Path) -> bool {
+        path.ancestors()
+            .filter_map(Self::whiteout)
+            .any(|whiteout| self.upper.is_file(&whiteout))
+    }
+
+    /// 返回下层的路径是否可见
+    fn in_lower(&self, path: &Path) -> bool {
+        !self.is_whited_out(path) && self.lower.exists(path)
+    }
+
+    /// 在上层创建删除标记, 隐藏下层的路径
+    fn hide_lower(&self, path: &Path) -> FsIOResult<()> {
+        match Self::whiteout(path) {
+            Some(whiteout) if self.in_lower(path) => self.upper.write_file(&whiteout, &[]),
+            _ => Ok(()),
+        }
+    }
+
+    /// 将文件或目录(包括子条目)复制到上层的目标路径
+    fn copy_up(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        if !self.is_dir(src) {
+            let data = self.read_file(src)?;
+            return self.upper.write_file(dst, &data);
+        }
+
+        self.upper.create_directory(dst)?;
+        for entry in self.list_directory(src)? {
+            if let Some(name) = entry.file_name() {
+                self.copy_up(&entry, &dst.join(name))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<U: FileSystem, L: FileSystem> FileSystem for OverlayFs<U, L> {
+    fn read_file(&self, path: &Path) -> FsIOResult<Vec<u8>> {
+        if self.upper.exists(path) {
+            self.upper.read_file(path)
+        } else if self.is_whited_out(path) {
+            Err(FsIOError::NotFound(path.to_path_buf(), None))
+        } else {
+            self.lower.read_file(path)
+        }
+    }
+
+    fn write_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        self.upper.write_file(path, data)
+    }
+
+    fn append_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        if !self.upper.exists(path) && self.in_lower(path) {
+            let mut content = self.lower.read_file(path)?;
+            content.extend_from_slice(data);
+            return self.upper.write_file(path, &content);
+        }
+
+        self.upper.append_file(path, data)
+    }
+
+    fn delete_file(&self, path: &Path) -> FsIOResult<()> {
+        if self.upper.is_file(path) {
+            self.upper.delete_file(path)?;
+        }
+        self.hide_lower(path)
+    }
+
+    fn create_directory(&self, path: &Path) -> FsIOResult<()> {
+        self.upper.create_directory(path)
+    }
+
+    fn delete_directory(&self, path: &Path) -> FsIOResult<()> {
+        if self.upper.is_dir(path) {
+            self.upper.delete_directory(path)?;
+        }
+        self.hide_lower(path)
+    }
+
+    fn copy_file(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        let data = self.read_file(src)?;
+        self.upper.write_file(dst, &data)
+    }
+
+    fn rename(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        if !self.exists(src) {
+            return Err(FsIOError::NotFound(src.to_path_buf(), None));
+        }
+
+        // the renamed directory replaces the lower one instead of being merged with it
+        self.hide_lower(dst)?;
+
+        if self.in_lower(src) {
+            self.copy_up(src, dst)?;
+            if self.is_dir(src) {
+                self.delete_directory(src)
+            } else {
+                self.delete_file(src)
+            }
+        } else {
+            self.upper.rename(src, dst)
+        }
+    }
+
+    fn list_directory(&self, path: &Path) -> FsIOResult<Vec<PathBuf>> {
+        let upper_dir = self.upper.is_dir(path);
+        let lower_dir = !self.is_whited_out(path) && self.lower.is_dir(path);
+        if !upper_dir && !lower_dir {
+            return self.upper.list_directory(path);
+        }
+
+        let mut entries = BTreeSet::new();
+        if upper_dir {
+            for entry in self.upper.list_directory(path)? {
+                let is_whiteout = entry
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with(WHITEOUT_PREFIX));
+                if !is_whiteout {
+                    entries.insert(entry);
+                }
+            }
+        }
+        if lower_dir {
+            for entry in self.lower.list_directory(path)? {
+                if !self.is_whited_out(&entry) {
+                    entries.insert(entry);
+                }
+            }
+        }
+
+        Ok(entries.into_iter().collect())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.upper.exists(path) || self.in_lower(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        if self.upper.exists(path) {
+            self.upper.is_file(path)
+        } else {
+            !self.is_whited_out(path) && self.lower.is_file(path)
+        }
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        if self.upper.exists(path) {
+            self.upper.is_dir(path)
+        } else {
+            !self.is_whited_out(path) && self.lower.is_dir(path)
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! # read_only
+//!
+//! FileSystem decorator rejecting every mutating operation.
+//!
+use std::path::{Path, PathBuf};
+
+use crate::error::FsIOError;
+use crate::filesystem::{FileSystem, OsFs};
+use crate::result::FsIOResult;
+
+/// 只读文件系统, 修改操作返回 `FsIOError::ReadOnly` 而不调用内部文件系统, 读取操作使用内部文件系统
+///
+/// # 示例
+///
+/// ```
+/// use x_io::error::FsIOError;
+/// use x_io::file;
+/// use x_io::filesystem::{FileSystem, OsFs, ReadOnlyFs};
+/// use std::path::Path;
+///
+/// fn main() {
+///     let file_path = Path::new("./target/__test/filesystem_test/read_only/file.txt");
+///     file::write_text_file(file_path, "some content").unwrap();
+///
+///     let fs = ReadOnlyFs::new(OsFs);
+///     assert_eq!(fs.read_text_file(file_path).unwrap(), "some content");
+///
+///     let result = fs.write_text_file(file_path, "other content");
+///     assert!(matches!(result, Err(FsIOError::ReadOnly(_))));
+///     assert!(fs.delete_file(file_path).unwrap_err().is_permission_denied());
+///     assert_eq!(file::read_text_file(file_path).unwrap(), "some content");
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ReadOnlyFs<F: FileSystem = OsFs> {
+    inner: F,
+}
+
+impl<F: FileSystem> ReadOnlyFs<F> {
+    /// 创建只读文件系统
+    ///
+    /// # 参数
+    ///
+    /// * `inner` - 用于读取操作的文件系统
+    pub fn new(inner: F) -> ReadOnlyFs<F> {
+        ReadOnlyFs { inner }
+    }
+
+    /// 返回内部文件系统
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    fn reject(&self, path: &Path) -> FsIOResult<()> {
+        Err(FsIOError::ReadOnly(path.to_path_buf()))
+    }
+}
+
+impl<F: FileSystem> FileSystem for ReadOnlyFs<F> {
+    fn read_file(&self, path: &Path) -> FsIOResult<Vec<u8>> {
+        self.inner.read_file(path)
+    }
+
+    fn write_file(&self, path: &Path, _data: &[u8]) -> FsIOResult<()> {
+        self.reject(path)
+    }
+
+    fn append_file(&self, path: &Path, _data: &[u8]) -> FsIOResult<()> {
+        self.reject(path)
+    }
+
+    fn delete_file(&self, path: &Path) -> FsIOResult<()> {
+        self.reject(path)
+    }
+
+    fn create_directory(&self, path: &Path) -> FsIOResult<()> {
+        self.reject(path)
+    }
+
+    fn delete_directory(&self, path: &Path) -> FsIOResult<()> {
+        self.reject(path)
+    }
+
+    fn copy_file(&self, _src: &Path, dst: &Path) -> FsIOResult<()> {
+        self.reject(dst)
+    }
+
+    fn rename(&self, src: &Path, _dst: &Path) -> FsIOResult<()> {
+        self.reject(src)
+    }
+
+    fn list_directory(&self, path: &Path) -> FsIOResult<Vec<PathBuf>> {
+        self.inner.list_directory(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.inner.is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.inner.is_dir(path)
+    }
+}
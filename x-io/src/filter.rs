@@ -0,0 +1,267 @@
+//! # filter
+//!
+//! Include/exclude path filters shared by walk, copy, archive and delete operations.
+//!
+use std::fs;
+use std::fs::Metadata;
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+use std::time::SystemTime;
+
+use crate::ignore::IgnoreSet;
+use crate::path::as_path::AsPath;
+use crate::path::GlobPattern;
+use crate::result::FsIOResult;
+
+/// 路径过滤器, 路径都相对于操作的根目录
+///
+/// 目录只检查忽略规则和排除模式, 被排除的目录及其中的所有条目都被跳过.
+/// 文件(以及其他非目录条目)必须满足所有条件:
+///
+/// * 不被忽略规则忽略, 不匹配任何排除模式
+/// * 包含模式为空或匹配任一包含模式
+/// * 扩展名列表为空或扩展名在列表中(不区分大小写)
+/// * 大小和修改时间在范围内(读取元数据失败时被排除)
+///
+/// 模式的语法参见 `path::GlobPattern`, 匹配任意层级的文件需要使用 `**/`, 例如 `**/*.log`.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::filter::PathFilter;
+/// use x_io::{directory, file};
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let root = "./target/__test/filter_test/path_filter";
+///     directory::delete(root).unwrap();
+///     file::write_text_file("./target/__test/filter_test/path_filter/src/lib.rs", "pub mod a;").unwrap();
+///     file::write_text_file("./target/__test/filter_test/path_filter/src/a.RS", "").unwrap();
+///     file::write_text_file("./target/__test/filter_test/path_filter/README.md", "# readme").unwrap();
+///     file::write_text_file("./target/__test/filter_test/path_filter/target/out.rs", "").unwrap();
+///
+///     let filter = PathFilter::new()
+///         .exclude("target")
+///         .unwrap()
+///         .extensions(["rs"]);
+///     assert!(filter.is_match(root, "src/lib.rs"));
+///     assert!(filter.is_match(root, "src/a.RS"));
+///     assert!(!filter.is_match(root, "README.md"));
+///     // the excluded directory and everything in it are skipped by walks
+///     assert!(!filter.is_match(root, "target"));
+///
+///     // the empty file is excluded by the size range
+///     let filter = filter.size(1..);
+///     assert!(filter.is_match(root, "src/lib.rs"));
+///     assert!(!filter.is_match(root, "src/a.RS"));
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathFilter {
+    include: Vec<GlobPattern>,
+    exclude: Vec<GlobPattern>,
+    extensions: Vec<String>,
+    size: (Bound<u64>, Bound<u64>),
+    modified: (Bound<SystemTime>, Bound<SystemTime>),
+    ignore: Vec<IgnoreSet>,
+}
+
+impl Default for PathFilter {
+    fn default() -> Self {
+        PathFilter {
+            include: vec![],
+            exclude: vec![],
+            extensions: vec![],
+            size: (Bound::Unbounded, Bound::Unbounded),
+            modified: (Bound::Unbounded, Bound::Unbounded),
+            ignore: vec![],
+        }
+    }
+}
+
+impl PathFilter {
+    /// 创建不排除任何路径的过滤器
+    pub fn new() -> PathFilter {
+        PathFilter::default()
+    }
+
+    /// 添加包含模式, 设置包含模式后只包含匹配任一包含模式的文件
+    ///
+    /// # 参数
+    ///
+    /// * `pattern` - glob模式
+    pub fn include(mut self, pattern: &str) -> FsIOResult<PathFilter> {
+        self.include.push(GlobPattern::new(pattern)?);
+        Ok(self)
+    }
+
+    /// 添加排除模式, 匹配的文件和目录被排除
+    ///
+    /// # 参数
+    ///
+    /// * `pattern` - glob模式
+    pub fn exclude(mut self, pattern: &str) -> FsIOResult<PathFilter> {
+        self.exclude.push(GlobPattern::new(pattern)?);
+        Ok(self)
+    }
+
+    /// 只包含这些扩展名的文件(不包含 `.`, 不区分大小写)
+    ///
+    /// # 参数
+    ///
+    /// * `extensions` - 扩展名
+    pub fn extensions<I, S>(mut self, extensions: I) -> PathFilter
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for extension in extensions {
+            let extension = extension.as_ref().trim_start_matches('.');
+            self.extensions.push(extension.to_lowercase());
+        }
+        self
+    }
+
+    /// 只包含大小在范围内的文件, 例如 `1024..` 或 `..=4096`
+    ///
+    /// # 参数
+    ///
+    /// * `range` - 文件大小(字节)的范围
+    pub fn size<R: RangeBounds<u64>>(mut self, range: R) -> PathFilter {
+        self.size = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// 只包含修改时间在范围内的文件, 例如 `since..`
+    ///
+    /// # 参数
+    ///
+    /// * `range` - 修改时间的范围
+    pub fn modified<R: RangeBounds<SystemTime>>(mut self, range: R) -> PathFilter {
+        self.modified = (range.start_bound().cloned(), range.end_bound().cloned());
+        self
+    }
+
+    /// 添加忽略规则
+    ///
+    /// # 参数
+    ///
+    /// * `ignore` - 忽略规则
+    pub fn ignore(mut self, ignore: IgnoreSet) -> PathFilter {
+        self.ignore.push(ignore);
+        self
+    }
+
+    /// 从 `.gitignore` 风格的文件添加忽略规则
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 忽略文件路径
+    pub fn ignore_file<T: AsPath + ?Sized>(self, path: &T) -> FsIOResult<PathFilter> {
+        let ignore = IgnoreSet::from_file(path)?;
+        Ok(self.ignore(ignore))
+    }
+
+    /// 返回条目是否通过过滤器
+    ///
+    /// # 参数
+    ///
+    /// * `relative` - 相对于根目录的路径
+    /// * `metadata` - 条目的元数据
+    pub fn matches<T: AsPath + ?Sized>(&self, relative: &T, metadata: &Metadata) -> bool {
+        self.check(relative.as_path(), metadata.is_dir(), || {
+            Some((metadata.len(), metadata.modified().ok()))
+        })
+    }
+
+    /// 返回根目录下的路径是否通过过滤器(跟随符号链接读取元数据), 路径不存在时返回false
+    ///
+    /// # 参数
+    ///
+    /// * `root` - 根目录
+    /// * `relative` - 相对于根目录的路径
+    pub fn is_match<R: AsPath + ?Sized, T: AsPath + ?Sized>(&self, root: &R, relative: &T) -> bool {
+        let relative_path = relative.as_path();
+        match fs::metadata(root.as_path().join(relative_path)) {
+            Ok(metadata) => self.matches(relative_path, &metadata),
+            Err(_) => false,
+        }
+    }
+
+    /// 返回条目是否通过过滤器, 只有需要检查大小或修改时间时才读取 `path` 的元数据
+    pub(crate) fn matches_entry(&self, relative: &Path, is_dir: bool, path: &Path) -> bool {
+        self.check(relative, is_dir, || {
+            fs::metadata(path)
+                .ok()
+                .map(|metadata| (metadata.len(), metadata.modified().ok()))
+        })
+    }
+
+    /// 返回归档中的条目是否通过过滤器, 没有修改时间的条目不通过修改时间过滤
+    #[cfg(feature = "archive")]
+    pub(crate) fn matches_archive_entry(
+        &self,
+        relative: &Path,
+        is_dir: bool,
+        size: u64,
+        modified: Option<SystemTime>,
+    ) -> bool {
+        self.check(relative, is_dir, || Some((size, modified)))
+    }
+
+    /// 检查条目, `attributes` 只有需要检查大小或修改时间时才调用, 返回大小和修改时间
+    fn check<F: FnOnce() -> Option<(u64, Option<SystemTime>)>>(
+        &self,
+        relative: &Path,
+        is_dir: bool,
+        attributes: F,
+    ) -> bool {
+        if self
+            .ignore
+            .iter()
+            .any(|ignore| ignore.is_ignored_with(relative, is_dir))
+        {
+            return false;
+        }
+        if self.exclude.iter().any(|pattern| pattern.matches(relative)) {
+            return false;
+        }
+        if is_dir {
+            return true;
+        }
+
+        if !self.include.is_empty() && !self.include.iter().any(|pattern| pattern.matches(relative))
+        {
+            return false;
+        }
+
+        if !self.extensions.is_empty() {
+            let extension = match relative.extension() {
+                Some(extension) => extension.to_string_lossy().to_lowercase(),
+                None => return false,
+            };
+            if !self.extensions.contains(&extension) {
+                return false;
+            }
+        }
+
+        let size_bounded = self.size != (Bound::Unbounded, Bound::Unbounded);
+        let modified_bounded = self.modified != (Bound::Unbounded, Bound::Unbounded);
+        if !size_bounded && !modified_bounded {
+            return true;
+        }
+
+        let (size, modified) = match attributes() {
+            Some(attributes) => attributes,
+            None => return false,
+        };
+        if size_bounded && !self.size.contains(&size) {
+            return false;
+        }
+        if modified_bounded {
+            return modified.is_some_and(|modified| self.modified.contains(&modified));
+        }
+
+        true
+    }
+}
@@ -0,0 +1,234 @@
+//! # ignore
+//!
+//! `.gitignore` style ignore rules.
+//!
+use std::path::{Component, Path, PathBuf};
+
+use crate::file;
+use crate::path::as_path::AsPath;
+use crate::path::GlobPattern;
+use crate::result::FsIOResult;
+
+/// 一条忽略规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct IgnoreRule {
+    /// 匹配相对路径的glob模式
+    glob: GlobPattern,
+    /// `!` 开头, 重新包含之前忽略的路径
+    negated: bool,
+    /// `/` 结尾, 只匹配目录
+    directory_only: bool,
+}
+
+/// `.gitignore` 风格的忽略规则集合
+///
+/// 与git相同的语义:
+///
+/// * 空行和 `#` 开头的行被忽略, `\#` 和 `\!` 表示字面的 `#` 和 `!`
+/// * `!` 开头的规则重新包含之前忽略的路径, 最后一条匹配的规则生效
+/// * `/` 结尾的规则只匹配目录
+/// * 开头或中间包含 `/` 的规则相对于根目录匹配, 否则匹配任意层级的名称
+/// * 目录被忽略时其中的所有条目都被忽略(不能被 `!` 重新包含)
+/// * 支持 `*`, `?`, `**` 和 `[...]`(参见 `path::GlobPattern`)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::ignore::IgnoreSet;
+///
+/// fn main() {
+///     let ignore = IgnoreSet::from_patterns(["*.log", "!keep.log", "/build/", "docs/*.tmp"]).unwrap();
+///
+///     assert!(ignore.is_ignored("debug.log"));
+///     assert!(ignore.is_ignored("logs/debug.log"));
+///     assert!(!ignore.is_ignored("logs/keep.log"));
+///     assert!(ignore.is_ignored_with("build", true));
+///     assert!(ignore.is_ignored("build/out/app"));
+///     assert!(!ignore.is_ignored("src/build/main.rs"));
+///     assert!(ignore.is_ignored("docs/draft.tmp"));
+///     assert!(!ignore.is_ignored("docs/api/draft.tmp"));
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IgnoreSet {
+    root: Option<PathBuf>,
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreSet {
+    /// 创建空的规则集合(不忽略任何路径)
+    pub fn new() -> IgnoreSet {
+        IgnoreSet::default()
+    }
+
+    /// 根据规则行创建规则集合, 路径相对于当前目录, 规则无效时返回错误
+    ///
+    /// # 参数
+    ///
+    /// * `patterns` - 规则行(与 `.gitignore` 文件的行相同)
+    pub fn from_patterns<I, S>(patterns: I) -> FsIOResult<IgnoreSet>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut ignore = IgnoreSet::new();
+        for pattern in patterns {
+            ignore.add_pattern(pattern.as_ref())?;
+        }
+        Ok(ignore)
+    }
+
+    /// 读取忽略文件(例如 `.gitignore`)创建规则集合, 规则相对于文件所在的目录
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 忽略文件路径
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::file;
+    /// use x_io::ignore::IgnoreSet;
+    ///
+    /// fn main() {
+    ///     file::write_text_file("./target/__test/ignore_test/from_file/.gitignore", "# build output\ntarget/\n*.bak\n").unwrap();
+    ///     file::ensure_exists("./target/__test/ignore_test/from_file/target/app").unwrap();
+    ///
+    ///     let ignore = IgnoreSet::from_file("./target/__test/ignore_test/from_file/.gitignore").unwrap();
+    ///     assert!(ignore.is_ignored("target"));
+    ///     assert!(ignore.is_ignored("./target/__test/ignore_test/from_file/notes.bak"));
+    ///     assert!(!ignore.is_ignored("notes.txt"));
+    /// }
+    /// ```
+    pub fn from_file<T: AsPath + ?Sized>(path: &T) -> FsIOResult<IgnoreSet> {
+        let file_path = path.as_path();
+        let text = file::read_text_file(&file_path)?;
+
+        let mut ignore = IgnoreSet::from_patterns(text.lines())?;
+        ignore.root = match file_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => Some(parent.to_path_buf()),
+            _ => Some(PathBuf::from(".")),
+        };
+        Ok(ignore)
+    }
+
+    /// 添加一条规则行, 规则无效时返回错误
+    ///
+    /// # 参数
+    ///
+    /// * `pattern` - 规则行
+    pub fn add_pattern(&mut self, pattern: &str) -> FsIOResult<()> {
+        let mut pattern = pattern.trim_end();
+        if pattern.is_empty() || pattern.starts_with('#') {
+            return Ok(());
+        }
+
+        let negated = pattern.starts_with('!');
+        let escaped = pattern.starts_with("\\!") || pattern.starts_with("\\#");
+        if negated || escaped {
+            pattern = &pattern[1..];
+        }
+
+        let directory_only = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        if pattern.is_empty() {
+            return Ok(());
+        }
+
+        let glob = if pattern.contains('/') {
+            GlobPattern::new(pattern.trim_start_matches('/'))?
+        } else {
+            GlobPattern::new(&format!("**/{}", pattern))?
+        };
+
+        self.rules.push(IgnoreRule {
+            glob,
+            negated,
+            directory_only,
+        });
+        Ok(())
+    }
+
+    /// 返回规则数量
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// 返回是否没有规则
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// 返回路径是否被忽略
+    ///
+    /// 相对路径相对于规则的根目录(`from_file` 时为忽略文件所在的目录), 位于根目录之内的路径也可以直接使用.
+    /// 路径是否为目录根据文件系统判断, 需要指定时使用 `is_ignored_with`.
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 路径
+    pub fn is_ignored<T: AsPath + ?Sized>(&self, path: &T) -> bool {
+        let path_obj = path.as_path();
+        let is_dir = match self.root {
+            Some(ref root) if !path_obj.starts_with(root) => root.join(path_obj).is_dir(),
+            _ => path_obj.is_dir(),
+        };
+        self.is_ignored_with(path, is_dir)
+    }
+
+    /// 返回路径是否被忽略, 由调用者指定路径是否为目录
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 路径
+    /// * `is_dir` - 路径是否为目录
+    pub fn is_ignored_with<T: AsPath + ?Sized>(&self, path: &T, is_dir: bool) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+
+        let names = self.relative_names(path.as_path());
+        if names.is_empty() {
+            return false;
+        }
+
+        // an ignored parent directory excludes everything inside it
+        for index in 1..names.len() {
+            if self.matches(&names[..index].join("/"), true) {
+                return true;
+            }
+        }
+
+        self.matches(&names.join("/"), is_dir)
+    }
+
+    /// 返回最后一条匹配的规则是否为忽略规则
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.directory_only && !is_dir {
+                continue;
+            }
+            if rule.glob.matches(relative) {
+                ignored = !rule.negated;
+            }
+        }
+        ignored
+    }
+
+    /// 返回相对于根目录的路径名称列表
+    fn relative_names(&self, path: &Path) -> Vec<String> {
+        let relative = match self.root {
+            Some(ref root) => path.strip_prefix(root).unwrap_or(path),
+            None => path,
+        };
+
+        relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect()
+    }
+}
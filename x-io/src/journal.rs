@@ -0,0 +1,452 @@
+//! # journal
+//!
+//! Append-only journal file with length-prefixed, checksummed records.
+//!
+use std::fs::{remove_file, rename, File, OpenOptions};
+use std::io;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::directory;
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::path::unique_sibling;
+use crate::result::FsIOResult;
+
+/// 记录头部的大小(长度和校验和各4字节)
+const HEADER_SIZE: usize = 8;
+
+/// 单条记录的最大长度
+const MAX_RECORD_SIZE: usize = u32::MAX as usize;
+
+/// 追加记录后调用fsync的策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// 每次追加后同步
+    Always,
+    /// 每追加n条记录后同步
+    Every(u32),
+    /// 不自动同步(由操作系统决定, 可以调用 `Journal::sync`)
+    Never,
+}
+
+/// 只追加的日志文件
+///
+/// 每条记录保存为 `[长度: u32 LE][CRC32: u32 LE][内容]`. 打开时会截断末尾不完整或校验失败的记录
+/// (例如写入时进程崩溃), 因此读取到的总是完整写入的记录. 之后还有其他记录的记录校验失败时
+/// 不会截断(否则会丢失之后的记录), 而是返回 `FsIOError::ChecksumMismatch`.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::journal::{Journal, SyncPolicy};
+///
+/// fn main() {
+///     let file_path = "./target/__test/journal_test/journal/events.log";
+///     file::delete(file_path).unwrap();
+///
+///     let mut journal = Journal::open(file_path, SyncPolicy::Always).unwrap();
+///     journal.append(b"first").unwrap();
+///     journal.append(b"second").unwrap();
+///
+///     let records: Vec<Vec<u8>> = journal.iter().unwrap().map(|record| record.unwrap()).collect();
+///     assert_eq!(records, vec![b"first".to_vec(), b"second".to_vec()]);
+///
+///     // a torn final record is dropped when the journal is opened again
+///     drop(journal);
+///     file::append_file(file_path, &[6, 0, 0, 0, 1, 2]).unwrap();
+///     let journal = Journal::open(file_path, SyncPolicy::Always).unwrap();
+///     assert_eq!(journal.iter().unwrap().count(), 2);
+///
+///     // a corrupted record followed by other records is reported instead of truncated
+///     drop(journal);
+///     let mut data = file::read_file(file_path).unwrap();
+///     data[8] ^= 0xff;
+///     file::write_file(file_path, &data).unwrap();
+///     let result = Journal::open(file_path, SyncPolicy::Always);
+///     assert!(matches!(result, Err(x_io::error::FsIOError::ChecksumMismatch { .. })));
+///     assert_eq!(file::read_file(file_path).unwrap(), data);
+///
+///     // so is a corrupted length that reaches past the end of the file
+///     data[8] ^= 0xff;
+///     data[3] = 0x7f;
+///     file::write_file(file_path, &data).unwrap();
+///     let result = Journal::open(file_path, SyncPolicy::Always);
+///     assert!(matches!(result, Err(x_io::error::FsIOError::ChecksumMismatch { .. })));
+///     assert_eq!(file::read_file(file_path).unwrap(), data);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Journal {
+    path: PathBuf,
+    file: File,
+    sync_policy: SyncPolicy,
+    unsynced: u32,
+}
+
+impl Journal {
+    /// 打开日志文件(不存在时创建, 包括父级目录), 并截断末尾不完整的记录,
+    /// 中间的记录校验失败时返回 `FsIOError::ChecksumMismatch`
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 日志文件路径
+    /// * `sync_policy` - fsync策略
+    pub fn open<T: AsPath + ?Sized>(path: &T, sync_policy: SyncPolicy) -> FsIOResult<Journal> {
+        let file_path = path.as_path();
+        directory::create_parent(&file_path)?;
+
+        let file = open_append(file_path)?;
+
+        let valid_size = valid_size(file_path)?;
+        let file_size = match file.metadata() {
+            Ok(metadata) => metadata.len(),
+            Err(error) => return Err(FsIOError::io(Operation::Metadata, &file_path, error)),
+        };
+        if file_size > valid_size {
+            check_torn(file_path, valid_size)?;
+            if let Err(error) = file.set_len(valid_size) {
+                return Err(FsIOError::io(Operation::Write, &file_path, error));
+            }
+        }
+
+        Ok(Journal {
+            path: file_path.to_path_buf(),
+            file,
+            sync_policy,
+            unsynced: 0,
+        })
+    }
+
+    /// 返回日志文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 追加一条记录, 按fsync策略同步
+    ///
+    /// # 参数
+    ///
+    /// * `record` - 记录内容
+    pub fn append(&mut self, record: &[u8]) -> FsIOResult<()> {
+        if record.len() > MAX_RECORD_SIZE {
+            return Err(FsIOError::io(
+                Operation::Append,
+                &self.path,
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("journal record of {} bytes is too large", record.len()),
+                ),
+            ));
+        }
+
+        // header and record are written at once so a crash leaves at most one torn record
+        let mut frame = Vec::with_capacity(HEADER_SIZE + record.len());
+        frame.extend_from_slice(&(record.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&crc32(record).to_le_bytes());
+        frame.extend_from_slice(record);
+
+        if let Err(error) = self.file.write_all(&frame) {
+            return Err(FsIOError::io(Operation::Append, &self.path, error));
+        }
+
+        self.unsynced += 1;
+        let should_sync = match self.sync_policy {
+            SyncPolicy::Always => true,
+            SyncPolicy::Every(count) => self.unsynced >= count,
+            SyncPolicy::Never => false,
+        };
+        if should_sync {
+            self.sync()?;
+        }
+
+        Ok(())
+    }
+
+    /// 将已追加的记录同步到磁盘
+    pub fn sync(&mut self) -> FsIOResult<()> {
+        match self.file.sync_data() {
+            Ok(_) => {
+                self.unsynced = 0;
+                Ok(())
+            }
+            Err(error) => Err(FsIOError::io(Operation::Sync, &self.path, error)),
+        }
+    }
+
+    /// 返回按写入顺序遍历所有记录的迭代器
+    pub fn iter(&self) -> FsIOResult<JournalIter> {
+        match File::open(&self.path) {
+            Ok(file) => Ok(JournalIter {
+                path: self.path.clone(),
+                reader: BufReader::new(file),
+                done: false,
+            }),
+            Err(error) => Err(FsIOError::io(Operation::Read, &self.path, error)),
+        }
+    }
+
+    /// 删除所有记录
+    pub fn truncate(&mut self) -> FsIOResult<()> {
+        if let Err(error) = self.file.set_len(0) {
+            return Err(FsIOError::io(Operation::Write, &self.path, error));
+        }
+        self.sync()
+    }
+
+    /// 压缩日志, 只保留 `keep` 返回true的记录
+    ///
+    /// 保留的记录先写入临时文件再替换日志文件, 失败时日志文件保持不变.
+    ///
+    /// # 参数
+    ///
+    /// * `keep` - 返回是否保留该记录
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::file;
+    /// use x_io::journal::{Journal, SyncPolicy};
+    ///
+    /// fn main() {
+    ///     let file_path = "./target/__test/journal_test/compact/events.log";
+    ///     file::delete(file_path).unwrap();
+    ///
+    ///     let mut journal = Journal::open(file_path, SyncPolicy::Never).unwrap();
+    ///     for record in ["a", "b", "c"] {
+    ///         journal.append(record.as_bytes()).unwrap();
+    ///     }
+    ///
+    ///     journal.compact(|record| record != b"b").unwrap();
+    ///     journal.append(b"d").unwrap();
+    ///
+    ///     let records: Vec<Vec<u8>> = journal.iter().unwrap().map(|record| record.unwrap()).collect();
+    ///     assert_eq!(records, vec![b"a".to_vec(), b"c".to_vec(), b"d".to_vec()]);
+    /// }
+    /// ```
+    pub fn compact<F: FnMut(&[u8]) -> bool>(&mut self, mut keep: F) -> FsIOResult<()> {
+        self.sync()?;
+
+        let temp_path = unique_sibling(&self.path, "compact");
+        let result = self.write_compacted(&temp_path, &mut keep);
+        if let Err(error) = result {
+            let _ = remove_file(&temp_path);
+            return Err(error);
+        }
+
+        if let Err(error) = rename(&temp_path, &self.path) {
+            let _ = remove_file(&temp_path);
+            return Err(FsIOError::io(
+                Operation::Rename(self.path.clone()),
+                &temp_path,
+                error,
+            ));
+        }
+
+        self.file = open_append(&self.path)?;
+        self.unsynced = 0;
+
+        Ok(())
+    }
+
+    fn write_compacted(
+        &self,
+        temp_path: &Path,
+        keep: &mut dyn FnMut(&[u8]) -> bool,
+    ) -> FsIOResult<()> {
+        let temp_file = match File::create(temp_path) {
+            Ok(file) => file,
+            Err(error) => return Err(FsIOError::io(Operation::Create, &temp_path, error)),
+        };
+        let mut writer = BufWriter::new(temp_file);
+
+        for record in self.iter()? {
+            let record = record?;
+            if !keep(&record) {
+                continue;
+            }
+
+            let result = writer
+                .write_all(&(record.len() as u32).to_le_bytes())
+                .and_then(|_| writer.write_all(&crc32(&record).to_le_bytes()))
+                .and_then(|_| writer.write_all(&record));
+            if let Err(error) = result {
+                return Err(FsIOError::io(Operation::Write, &temp_path, error));
+            }
+        }
+
+        match writer.into_inner() {
+            Ok(file) => match file.sync_all() {
+                Ok(_) => Ok(()),
+                Err(error) => Err(FsIOError::io(Operation::Sync, &temp_path, error)),
+            },
+            Err(error) => Err(FsIOError::io(
+                Operation::Write,
+                &temp_path,
+                error.into_error(),
+            )),
+        }
+    }
+}
+
+/// 日志记录迭代器, 遇到末尾不完整或校验失败的记录时结束
+#[derive(Debug)]
+pub struct JournalIter {
+    path: PathBuf,
+    reader: BufReader<File>,
+    done: bool,
+}
+
+impl Iterator for JournalIter {
+    type Item = FsIOResult<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match read_record(&mut self.reader) {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(FsIOError::io(Operation::Read, &self.path, error)))
+            }
+        }
+    }
+}
+
+fn open_append(path: &Path) -> FsIOResult<File> {
+    match OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(path)
+    {
+        Ok(file) => Ok(file),
+        Err(error) => Err(FsIOError::io(Operation::Append, &path, error)),
+    }
+}
+
+/// 读取一条记录, 文件结束或记录不完整(校验失败)时返回None
+fn read_record<R: Read>(reader: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut header = [0u8; HEADER_SIZE];
+    if !read_full(reader, &mut header)? {
+        return Ok(None);
+    }
+
+    let size = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let checksum = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+    let mut record = vec![];
+    let read_size = reader.take(size as u64).read_to_end(&mut record)?;
+    if read_size != size || crc32(&record) != checksum {
+        return Ok(None);
+    }
+
+    Ok(Some(record))
+}
+
+/// 填满缓冲区, 数据不足时返回false
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<bool> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => return Ok(false),
+            Ok(size) => filled += size,
+            Err(ref error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(true)
+}
+
+/// 返回日志文件开头完整记录的总大小
+fn valid_size(path: &Path) -> FsIOResult<u64> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) => return Err(FsIOError::io(Operation::Read, &path, error)),
+    };
+    let mut reader = BufReader::new(file);
+
+    let mut size = 0;
+    loop {
+        match read_record(&mut reader) {
+            Ok(Some(record)) => size += (HEADER_SIZE + record.len()) as u64,
+            Ok(None) => return Ok(size),
+            Err(error) => return Err(FsIOError::io(Operation::Read, &path, error)),
+        }
+    }
+}
+
+/// 检查有效记录之后的数据是否是末尾不完整的记录, 之后还有完整的记录时返回 `FsIOError::ChecksumMismatch`
+///
+/// 损坏的长度字段也可能延伸到文件末尾, 因此只有头部不完整或之后的数据中没有完整的记录时才是不完整的末尾记录.
+fn check_torn(path: &Path, valid_size: u64) -> FsIOResult<()> {
+    let result = (|| -> io::Result<Option<(u32, u32)>> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(valid_size))?;
+        let mut data = vec![];
+        file.read_to_end(&mut data)?;
+
+        let (size, checksum) = match record_header(&data, 0) {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+        let has_record = (HEADER_SIZE..data.len()).any(|offset| is_record_at(&data, offset));
+        if !has_record {
+            return Ok(None);
+        }
+
+        let end = HEADER_SIZE.saturating_add(size).min(data.len());
+        Ok(Some((checksum, crc32(&data[HEADER_SIZE..end]))))
+    })();
+
+    match result {
+        Ok(None) => Ok(()),
+        Ok(Some((expected, actual))) => Err(FsIOError::ChecksumMismatch {
+            path: path.to_path_buf(),
+            expected: format!("{:08x}", expected),
+            actual: format!("{:08x}", actual),
+        }),
+        Err(error) => Err(FsIOError::io(Operation::Read, &path, error)),
+    }
+}
+
+/// 返回 `offset` 处记录头部中的长度和校验和, 头部不完整时返回None
+fn record_header(data: &[u8], offset: usize) -> Option<(usize, u32)> {
+    let header = data.get(offset..offset.checked_add(HEADER_SIZE)?)?;
+    let size = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let checksum = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    Some((size, checksum))
+}
+
+/// 返回 `offset` 处是否是一条完整的非空记录(空记录与填充的0无法区分)
+fn is_record_at(data: &[u8], offset: usize) -> bool {
+    let (size, checksum) = match record_header(data, offset) {
+        Some(header) => header,
+        None => return false,
+    };
+    let start = offset + HEADER_SIZE;
+    match start.checked_add(size).and_then(|end| data.get(start..end)) {
+        Some(record) => size > 0 && crc32(record) == checksum,
+        None => false,
+    }
+}
+
+/// CRC-32(IEEE)校验和
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
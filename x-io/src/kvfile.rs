@@ -0,0 +1,258 @@
+//! # kvfile
+//!
+//! Key-value store persisted in a single text file.
+//!
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::FsIOError;
+use crate::file;
+use crate::path::as_path::AsPath;
+use crate::result::{FsIOResult, ResultExt};
+
+/// 键值存储选项
+#[derive(Debug, Clone, Copy)]
+pub struct KvOptions {
+    /// 是否在内存中缓存所有键值, 不缓存时每次读取都会读取文件(可以看到其他进程的修改)
+    pub cache: bool,
+}
+
+impl Default for KvOptions {
+    fn default() -> Self {
+        KvOptions { cache: true }
+    }
+}
+
+/// 保存在单个文件中的字符串键值存储
+///
+/// 每行保存一个键值 `key<TAB>value`(`\`, 制表符和换行符会被转义), 每次修改都会原子地重写文件
+/// (参见 `file::write_file_atomic`), 因此文件总是完整的.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::kvfile::KvStore;
+///
+/// fn main() {
+///     let file_path = "./target/__test/kvfile_test/kv_store/settings.kv";
+///     file::delete(file_path).unwrap();
+///
+///     let mut store = KvStore::open(file_path).unwrap();
+///     store.set("theme", "dark").unwrap();
+///     store.set("greeting", "hello\tworld\n").unwrap();
+///     assert_eq!(store.get("theme").unwrap().unwrap(), "dark");
+///
+///     let store = KvStore::open(file_path).unwrap();
+///     assert_eq!(store.get("greeting").unwrap().unwrap(), "hello\tworld\n");
+///     assert_eq!(store.keys().unwrap(), vec!["greeting", "theme"]);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct KvStore {
+    path: PathBuf,
+    cache: Option<BTreeMap<String, String>>,
+}
+
+impl KvStore {
+    /// 打开键值存储(缓存所有键值), 文件不存在时为空
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件路径
+    pub fn open<T: AsPath + ?Sized>(path: &T) -> FsIOResult<KvStore> {
+        KvStore::open_with(path, KvOptions::default())
+    }
+
+    /// 按选项打开键值存储, 文件不存在时为空
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件路径
+    /// * `options` - 选项
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::file;
+    /// use x_io::kvfile::{KvOptions, KvStore};
+    ///
+    /// fn main() {
+    ///     let file_path = "./target/__test/kvfile_test/open_with/settings.kv";
+    ///     file::delete(file_path).unwrap();
+    ///
+    ///     let reader = KvStore::open_with(file_path, KvOptions { cache: false }).unwrap();
+    ///     let mut writer = KvStore::open(file_path).unwrap();
+    ///     writer.set("name", "x-io").unwrap();
+    ///
+    ///     assert_eq!(reader.get("name").unwrap().unwrap(), "x-io");
+    /// }
+    /// ```
+    pub fn open_with<T: AsPath + ?Sized>(path: &T, options: KvOptions) -> FsIOResult<KvStore> {
+        let file_path = path.as_path().to_path_buf();
+        let cache = if options.cache {
+            Some(load(&file_path)?)
+        } else {
+            None
+        };
+
+        Ok(KvStore {
+            path: file_path,
+            cache,
+        })
+    }
+
+    /// 返回文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 返回键对应的值, 不存在时返回None
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 键
+    pub fn get(&self, key: &str) -> FsIOResult<Option<String>> {
+        match self.cache {
+            Some(ref entries) => Ok(entries.get(key).cloned()),
+            None => Ok(load(&self.path)?.remove(key)),
+        }
+    }
+
+    /// 返回是否存在键
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 键
+    pub fn contains(&self, key: &str) -> FsIOResult<bool> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    /// 返回所有键(按字典序)
+    pub fn keys(&self) -> FsIOResult<Vec<String>> {
+        match self.cache {
+            Some(ref entries) => Ok(entries.keys().cloned().collect()),
+            None => Ok(load(&self.path)?.into_keys().collect()),
+        }
+    }
+
+    /// 设置键值并写入文件
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 键
+    /// * `value` - 值
+    pub fn set(&mut self, key: &str, value: &str) -> FsIOResult<()> {
+        self.modify(|entries| {
+            entries.insert(key.to_string(), value.to_string());
+        })
+    }
+
+    /// 删除键并写入文件, 返回原来的值
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 键
+    pub fn remove(&mut self, key: &str) -> FsIOResult<Option<String>> {
+        let mut removed = None;
+        self.modify(|entries| removed = entries.remove(key))?;
+        Ok(removed)
+    }
+
+    /// 重新读取文件(丢弃缓存)
+    pub fn reload(&mut self) -> FsIOResult<()> {
+        if self.cache.is_some() {
+            self.cache = Some(load(&self.path)?);
+        }
+        Ok(())
+    }
+
+    fn modify<F: FnOnce(&mut BTreeMap<String, String>)>(&mut self, operation: F) -> FsIOResult<()> {
+        let mut entries = match self.cache {
+            Some(ref entries) => entries.clone(),
+            None => load(&self.path)?,
+        };
+        operation(&mut entries);
+
+        file::write_file_atomic(&self.path, serialize(&entries).as_bytes())?;
+        if self.cache.is_some() {
+            self.cache = Some(entries);
+        }
+
+        Ok(())
+    }
+}
+
+fn load(path: &Path) -> FsIOResult<BTreeMap<String, String>> {
+    let text = match file::read_text_file(&path).ignore_not_found()? {
+        Some(text) => text,
+        None => return Ok(BTreeMap::new()),
+    };
+
+    let mut entries = BTreeMap::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.is_empty() {
+            continue;
+        }
+
+        match line.split_once('\t') {
+            Some((key, value)) => {
+                entries.insert(unescape(key), unescape(value));
+            }
+            None => {
+                return Err(FsIOError::Parse(
+                    format!("Invalid key-value line: {} in file: {:?}", index + 1, path)
+                        .to_string(),
+                    None,
+                ))
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+fn serialize(entries: &BTreeMap<String, String>) -> String {
+    let mut text = String::new();
+    for (key, value) in entries {
+        text.push_str(&escape(key));
+        text.push('\t');
+        text.push_str(&escape(value));
+        text.push('\n');
+    }
+    text
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for character in value.chars() {
+        match character {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(character) = chars.next() {
+        if character != '\\' {
+            unescaped.push(character);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
@@ -9,6 +9,22 @@
 //! * x-io::path - Holds path related functions and traits. They do not directly modify the file system.
 //! * x-io::file - File utility functions such as read_file, write_file, ...
 //! * x-io::directory - Directory specific utility functions.
+//! * x-io::walk - Lazy directory traversal (depth/breadth first, max depth, pruning).
+//! * x-io::filesystem - FileSystem trait with the OS implementation and decorators (dry run, read only, overlay, ...).
+//! * x-io::transaction - All-or-nothing file system changes with rollback.
+//! * x-io::progress - Progress events and the observing FileSystem decorator.
+//! * x-io::cas - Content-addressed store on top of x-hash.
+//! * x-io::journal - Append-only journal files.
+//! * x-io::kvfile - Key-value store persisted in a single file.
+//! * x-io::ignore - `.gitignore` style ignore rules, usable as a filter for copy and archive.
+//! * x-io::filter - Include/exclude path filters shared by walk, copy, archive and delete_filtered.
+//! * x-io::safe - File system access confined to a root directory.
+//! * x-io::template - Template tree expansion (`{{var}}` placeholders in contents and names).
+//! * x-io::retry - Retrying of operations that failed with transient errors.
+//! * x-io::timeout - Deadlines for blocking operations.
+//! * x-io::throttle - Bandwidth limiting for reads and copies.
+//! * x-io::watch - File system watching (requires the `watch` feature).
+//! * x-io::archive - Zip/tar archive creation and extraction (requires the `archive` feature).
 //!
 //! ### Examples
 //!
@@ -85,10 +101,31 @@
 //! }
 //! ```
 
+#[allow(dead_code, unused_imports)]
 mod dunce;
+#[cfg(all(feature = "uring", target_os = "linux"))]
+mod uring;
 
 pub mod file;
 pub mod directory;
+pub mod walk;
 pub mod path;
+pub mod filesystem;
+pub mod transaction;
+pub mod retry;
+pub mod timeout;
+pub mod throttle;
+pub mod progress;
+pub mod cas;
+pub mod journal;
+pub mod kvfile;
+pub mod ignore;
+pub mod filter;
+pub mod safe;
+pub mod template;
 pub mod result;
 pub mod error;
+#[cfg(feature = "watch")]
+pub mod watch;
+#[cfg(feature = "archive")]
+pub mod archive;
@@ -0,0 +1,176 @@
+//! # app_dirs
+//!
+//! Standard per-user application directories (XDG, macOS Library, Windows Known Folders).
+//!
+use std::io;
+use std::path::PathBuf;
+
+use crate::directory;
+use crate::error::FsIOError;
+use crate::path::home_dir;
+use crate::result::FsIOResult;
+
+/// 应用程序的标准目录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppDirs {
+    /// 配置文件目录
+    pub config: PathBuf,
+    /// 缓存目录(可以随时删除)
+    pub cache: PathBuf,
+    /// 数据目录
+    pub data: PathBuf,
+    /// 状态目录(日志, 历史记录等)
+    pub state: PathBuf,
+    /// 运行时目录(socket, pid文件等), 系统没有提供时为None
+    pub runtime: Option<PathBuf>,
+}
+
+impl AppDirs {
+    /// 创建所有目录(包括父级目录)
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::path::AppDirs;
+    /// use std::path::PathBuf;
+    ///
+    /// fn main() {
+    ///     let root = PathBuf::from("./target/__test/path_test/create_all");
+    ///     let app_dirs = AppDirs {
+    ///         config: root.join("config"),
+    ///         cache: root.join("cache"),
+    ///         data: root.join("data"),
+    ///         state: root.join("state"),
+    ///         runtime: None,
+    ///     };
+    ///     let result = app_dirs.create_all();
+    ///     assert!(result.is_ok());
+    ///     assert!(app_dirs.config.is_dir());
+    /// }
+    /// ```
+    pub fn create_all(&self) -> FsIOResult<()> {
+        directory::create(&self.config)?;
+        directory::create(&self.cache)?;
+        directory::create(&self.data)?;
+        directory::create(&self.state)?;
+        if let Some(runtime) = &self.runtime {
+            directory::create(runtime)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 返回应用程序的标准目录(不会创建目录, 使用 `AppDirs::create_all` 创建)
+///
+/// * Linux等unix系统 - XDG目录(`$XDG_CONFIG_HOME`, `~/.cache`, `~/.local/share`, ...)
+/// * macOS - `~/Library/Application Support` 和 `~/Library/Caches`
+/// * Windows - RoamingAppData和LocalAppData已知文件夹(`SHGetKnownFolderPath`), 无法获取时使用 `%APPDATA%` 和 `%LOCALAPPDATA%`
+///
+/// # 参数
+///
+/// * `app_name` - 应用程序名称
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let app_dirs = path::app_dirs("my-app").unwrap();
+///     assert!(app_dirs.config.to_string_lossy().contains("my-app"));
+///     assert!(app_dirs.cache.is_absolute());
+/// }
+/// ```
+pub fn app_dirs(app_name: &str) -> FsIOResult<AppDirs> {
+    match platform_dirs(app_name) {
+        Some(app_dirs) => Ok(app_dirs),
+        None => Err(FsIOError::Context(
+            format!("Unable to find directories for application: {}", app_name).to_string(),
+            Box::new(io::Error::from(io::ErrorKind::NotFound).into()),
+        )),
+    }
+}
+
+/// 读取绝对路径的环境变量(空值和相对路径视为未设置)
+#[cfg(not(target_os = "macos"))]
+fn absolute_env(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name)
+        .map(PathBuf::from)
+        .filter(|path_buf| path_buf.is_absolute())
+}
+
+/// 返回已知文件夹的路径, 无法获取时返回None
+#[cfg(windows)]
+fn known_folder(id: &windows_sys::core::GUID) -> Option<PathBuf> {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use windows_sys::Win32::System::Com::CoTaskMemFree;
+    use windows_sys::Win32::UI::Shell::SHGetKnownFolderPath;
+
+    let mut pointer: windows_sys::core::PWSTR = std::ptr::null_mut();
+    let result = unsafe { SHGetKnownFolderPath(id, 0, std::ptr::null_mut(), &mut pointer) };
+    let path = if result >= 0 && !pointer.is_null() {
+        let length = (0..)
+            .take_while(|&index| unsafe { *pointer.add(index) } != 0)
+            .count();
+        let wide = unsafe { std::slice::from_raw_parts(pointer, length) };
+        Some(PathBuf::from(OsString::from_wide(wide))).filter(|path_buf| path_buf.is_absolute())
+    } else {
+        None
+    };
+    // the buffer must be freed even when the call fails
+    unsafe { CoTaskMemFree(pointer as *const std::ffi::c_void) };
+
+    path
+}
+
+#[cfg(windows)]
+fn platform_dirs(app_name: &str) -> Option<AppDirs> {
+    use windows_sys::Win32::UI::Shell::{FOLDERID_LocalAppData, FOLDERID_RoamingAppData};
+
+    let roaming = known_folder(&FOLDERID_RoamingAppData)
+        .or_else(|| absolute_env("APPDATA"))
+        .or_else(|| home_dir().map(|home| home.join("AppData").join("Roaming")))?;
+    let local = known_folder(&FOLDERID_LocalAppData)
+        .or_else(|| absolute_env("LOCALAPPDATA"))
+        .or_else(|| home_dir().map(|home| home.join("AppData").join("Local")))?;
+
+    Some(AppDirs {
+        config: roaming.join(app_name).join("config"),
+        cache: local.join(app_name).join("cache"),
+        data: roaming.join(app_name).join("data"),
+        state: local.join(app_name).join("state"),
+        runtime: None,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn platform_dirs(app_name: &str) -> Option<AppDirs> {
+    let library = home_dir()?.join("Library");
+    let support = library.join("Application Support").join(app_name);
+
+    Some(AppDirs {
+        config: support.clone(),
+        cache: library.join("Caches").join(app_name),
+        data: support.clone(),
+        state: support,
+        runtime: None,
+    })
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn platform_dirs(app_name: &str) -> Option<AppDirs> {
+    let home = home_dir();
+    let xdg_dir = |name: &str, default: &str| {
+        absolute_env(name).or_else(|| home.as_ref().map(|home| home.join(default)))
+    };
+
+    Some(AppDirs {
+        config: xdg_dir("XDG_CONFIG_HOME", ".config")?.join(app_name),
+        cache: xdg_dir("XDG_CACHE_HOME", ".cache")?.join(app_name),
+        data: xdg_dir("XDG_DATA_HOME", ".local/share")?.join(app_name),
+        state: xdg_dir("XDG_STATE_HOME", ".local/state")?.join(app_name),
+        runtime: absolute_env("XDG_RUNTIME_DIR").map(|runtime| runtime.join(app_name)),
+    })
+}
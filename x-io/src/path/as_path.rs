@@ -2,6 +2,8 @@
 //!
 //! AsPath trait and implementations.
 //!
+use std::borrow::Cow;
+use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 
 /// Defines as path trait.
@@ -34,6 +36,12 @@ impl AsPath for &String {
     }
 }
 
+impl AsPath for Path {
+    fn as_path(&self) -> &Path {
+        self
+    }
+}
+
 impl AsPath for &Path {
     fn as_path(&self) -> &Path {
         self
@@ -42,6 +50,48 @@ impl AsPath for &Path {
 
 impl AsPath for PathBuf {
     fn as_path(&self) -> &Path {
-        PathBuf::as_path(&self)
+        PathBuf::as_path(self)
+    }
+}
+
+impl AsPath for &PathBuf {
+    fn as_path(&self) -> &Path {
+        PathBuf::as_path(self)
+    }
+}
+
+impl AsPath for Cow<'_, str> {
+    fn as_path(&self) -> &Path {
+        Path::new(self.as_ref())
+    }
+}
+
+impl AsPath for Cow<'_, Path> {
+    fn as_path(&self) -> &Path {
+        self.as_ref()
+    }
+}
+
+impl AsPath for OsStr {
+    fn as_path(&self) -> &Path {
+        Path::new(self)
+    }
+}
+
+impl AsPath for &OsStr {
+    fn as_path(&self) -> &Path {
+        Path::new(self)
     }
-}
\ No newline at end of file
+}
+
+impl AsPath for OsString {
+    fn as_path(&self) -> &Path {
+        Path::new(self)
+    }
+}
+
+impl AsPath for &OsString {
+    fn as_path(&self) -> &Path {
+        Path::new(self)
+    }
+}
@@ -0,0 +1,336 @@
+//! # attributes
+//!
+//! Platform file attributes (Windows attribute flags, Linux inode flags).
+//!
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::result::FsIOResult;
+
+/// 文件属性
+///
+/// 平台不支持的属性读取时总是false, 设置为true时返回错误:
+///
+/// * `readonly` - 所有平台(unix上为所有者的写权限)
+/// * `hidden`, `system`, `archive` - Windows文件属性
+/// * `immutable`, `append_only` - Linux inode标志(`chattr +i` / `chattr +a`), 修改需要 `CAP_LINUX_IMMUTABLE` 权限,
+///   文件系统不支持时读取为false
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileAttributes {
+    /// 只读
+    pub readonly: bool,
+    /// 隐藏(Windows)
+    pub hidden: bool,
+    /// 系统文件(Windows)
+    pub system: bool,
+    /// 需要存档(Windows)
+    pub archive: bool,
+    /// 不可修改, 删除或重命名(Linux)
+    pub immutable: bool,
+    /// 只能追加(Linux)
+    pub append_only: bool,
+}
+
+/// 读取文件或目录的属性(跟随符号链接)
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     let file_path = "./target/__test/path_test/attributes/file.txt";
+///     file::write_text_file(file_path, "some content").unwrap();
+///
+///     let attributes = path::attributes(file_path).unwrap();
+///     assert!(!attributes.readonly);
+///     assert!(!attributes.immutable);
+/// }
+/// ```
+pub fn attributes<T: AsPath + ?Sized>(path: &T) -> FsIOResult<FileAttributes> {
+    attributes_of(path.as_path())
+}
+
+/// 设置文件或目录的属性(跟随符号链接), 只修改与当前值不同的属性
+///
+/// 将当前平台不支持的属性设置为true时返回错误.
+///
+/// # 参数
+///
+/// * `path` - 路径
+/// * `attributes` - 属性
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     let file_path = "./target/__test/path_test/set_attributes/file.txt";
+///     let _ = path::set_attributes(file_path, &path::FileAttributes::default());
+///     file::write_text_file(file_path, "some content").unwrap();
+///
+///     let mut attributes = path::attributes(file_path).unwrap();
+///     attributes.readonly = true;
+///     path::set_attributes(file_path, &attributes).unwrap();
+///     assert!(path::attributes(file_path).unwrap().readonly);
+///
+///     attributes.readonly = false;
+///     path::set_attributes(file_path, &attributes).unwrap();
+///     assert!(!path::attributes(file_path).unwrap().readonly);
+/// }
+/// ```
+pub fn set_attributes<T: AsPath + ?Sized>(path: &T, attributes: &FileAttributes) -> FsIOResult<()> {
+    let file_path = path.as_path();
+
+    let current = attributes_of(file_path)?;
+    if current == *attributes {
+        return Ok(());
+    }
+
+    match apply_attributes(file_path, &current, attributes) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::Write, &file_path, error)),
+    }
+}
+
+fn attributes_of(path: &Path) -> FsIOResult<FileAttributes> {
+    match query_attributes(path) {
+        Ok(attributes) => Ok(attributes),
+        Err(error) => Err(FsIOError::io(Operation::Metadata, &path, error)),
+    }
+}
+
+fn unsupported(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "File attribute: {} is not supported on this platform.",
+            name
+        ),
+    )
+}
+
+#[cfg(unix)]
+fn query_attributes(path: &Path) -> io::Result<FileAttributes> {
+    let metadata = fs::metadata(path)?;
+    let (immutable, append_only) = query_inode_flags(path)?;
+
+    Ok(FileAttributes {
+        readonly: metadata.permissions().readonly(),
+        immutable,
+        append_only,
+        ..FileAttributes::default()
+    })
+}
+
+#[cfg(unix)]
+fn apply_attributes(
+    path: &Path,
+    current: &FileAttributes,
+    attributes: &FileAttributes,
+) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if attributes.hidden {
+        return Err(unsupported("hidden"));
+    }
+    if attributes.system {
+        return Err(unsupported("system"));
+    }
+    if attributes.archive {
+        return Err(unsupported("archive"));
+    }
+
+    // an immutable file rejects permission changes, so the flags are cleared first and set last
+    let clears_flags = (current.immutable && !attributes.immutable)
+        || (current.append_only && !attributes.append_only);
+    if clears_flags {
+        set_inode_flags(path, attributes.immutable, attributes.append_only)?;
+    }
+
+    if current.readonly != attributes.readonly {
+        let mut permissions = fs::metadata(path)?.permissions();
+        let mode = permissions.mode();
+        permissions.set_mode(if attributes.readonly {
+            mode & !0o222
+        } else {
+            mode | 0o200
+        });
+        fs::set_permissions(path, permissions)?;
+    }
+
+    if !clears_flags
+        && (current.immutable != attributes.immutable
+            || current.append_only != attributes.append_only)
+    {
+        set_inode_flags(path, attributes.immutable, attributes.append_only)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+const FS_IMMUTABLE_FL: libc::c_int = 0x0000_0010;
+
+#[cfg(target_os = "linux")]
+const FS_APPEND_FL: libc::c_int = 0x0000_0020;
+
+#[cfg(target_os = "linux")]
+fn open_for_flags(path: &Path) -> io::Result<fs::File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    fs::OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+}
+
+/// 读取inode标志(不可修改, 只能追加), 文件系统不支持时返回false
+#[cfg(target_os = "linux")]
+fn query_inode_flags(path: &Path) -> io::Result<(bool, bool)> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = open_for_flags(path)?;
+    let mut flags: libc::c_int = 0;
+    if unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) } == -1 {
+        let error = io::Error::last_os_error();
+        return match error.raw_os_error() {
+            Some(libc::ENOTTY) | Some(libc::ENOTSUP) | Some(libc::EINVAL) => Ok((false, false)),
+            _ => Err(error),
+        };
+    }
+
+    Ok((flags & FS_IMMUTABLE_FL != 0, flags & FS_APPEND_FL != 0))
+}
+
+#[cfg(target_os = "linux")]
+fn set_inode_flags(path: &Path, immutable: bool, append_only: bool) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let file = open_for_flags(path)?;
+    let mut flags: libc::c_int = 0;
+    if unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_GETFLAGS, &mut flags) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    flags &= !(FS_IMMUTABLE_FL | FS_APPEND_FL);
+    if immutable {
+        flags |= FS_IMMUTABLE_FL;
+    }
+    if append_only {
+        flags |= FS_APPEND_FL;
+    }
+
+    if unsafe { libc::ioctl(file.as_raw_fd(), libc::FS_IOC_SETFLAGS, &flags) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn query_inode_flags(path: &Path) -> io::Result<(bool, bool)> {
+    let _ = path;
+    Ok((false, false))
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn set_inode_flags(path: &Path, immutable: bool, append_only: bool) -> io::Result<()> {
+    let _ = path;
+    if immutable {
+        return Err(unsupported("immutable"));
+    }
+    if append_only {
+        return Err(unsupported("append_only"));
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn query_attributes(path: &Path) -> io::Result<FileAttributes> {
+    use std::os::windows::fs::MetadataExt;
+    use windows_sys::Win32::Storage::FileSystem::{
+        FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_HIDDEN, FILE_ATTRIBUTE_READONLY,
+        FILE_ATTRIBUTE_SYSTEM,
+    };
+
+    let flags = fs::metadata(path)?.file_attributes();
+    Ok(FileAttributes {
+        readonly: flags & FILE_ATTRIBUTE_READONLY != 0,
+        hidden: flags & FILE_ATTRIBUTE_HIDDEN != 0,
+        system: flags & FILE_ATTRIBUTE_SYSTEM != 0,
+        archive: flags & FILE_ATTRIBUTE_ARCHIVE != 0,
+        ..FileAttributes::default()
+    })
+}
+
+#[cfg(windows)]
+fn apply_attributes(
+    path: &Path,
+    current: &FileAttributes,
+    attributes: &FileAttributes,
+) -> io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use std::os::windows::fs::MetadataExt;
+    use windows_sys::Win32::Storage::FileSystem::{
+        SetFileAttributesW, FILE_ATTRIBUTE_ARCHIVE, FILE_ATTRIBUTE_HIDDEN, FILE_ATTRIBUTE_NORMAL,
+        FILE_ATTRIBUTE_READONLY, FILE_ATTRIBUTE_SYSTEM,
+    };
+
+    let _ = current;
+    if attributes.immutable {
+        return Err(unsupported("immutable"));
+    }
+    if attributes.append_only {
+        return Err(unsupported("append_only"));
+    }
+
+    let mut flags = fs::metadata(path)?.file_attributes();
+    for (flag, enabled) in [
+        (FILE_ATTRIBUTE_READONLY, attributes.readonly),
+        (FILE_ATTRIBUTE_HIDDEN, attributes.hidden),
+        (FILE_ATTRIBUTE_SYSTEM, attributes.system),
+        (FILE_ATTRIBUTE_ARCHIVE, attributes.archive),
+    ] {
+        if enabled {
+            flags |= flag;
+        } else {
+            flags &= !flag;
+        }
+    }
+    if flags == 0 {
+        flags = FILE_ATTRIBUTE_NORMAL;
+    }
+
+    let wide_path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    if unsafe { SetFileAttributesW(wide_path.as_ptr(), flags) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(unix, windows)))]
+fn query_attributes(path: &Path) -> io::Result<FileAttributes> {
+    let metadata = fs::metadata(path)?;
+    Ok(FileAttributes {
+        readonly: metadata.permissions().readonly(),
+        ..FileAttributes::default()
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn apply_attributes(
+    path: &Path,
+    current: &FileAttributes,
+    attributes: &FileAttributes,
+) -> io::Result<()> {
+    let _ = (path, current, attributes);
+    Err(unsupported("readonly"))
+}
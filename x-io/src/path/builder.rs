@@ -0,0 +1,133 @@
+//! # builder
+//!
+//! Fluent path construction.
+//!
+use std::path::PathBuf;
+
+use crate::path::as_path::AsPath;
+use crate::path::from_path::FromPath;
+use crate::result::FsIOResult;
+use crate::{directory, file};
+
+/// 路径构建器, 以链式调用拼接路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path::PathBuilder;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let debug = false;
+///     let path = PathBuilder::new("./target")
+///         .join("out")
+///         .join_if(debug, "debug")
+///         .join("report")
+///         .with_extension("json")
+///         .build();
+///     assert_eq!(path, PathBuf::from("./target/out/report.json"));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathBuilder {
+    path: PathBuf,
+}
+
+impl PathBuilder {
+    /// 创建路径构建器
+    ///
+    /// # 参数
+    ///
+    /// * `root` - 根路径
+    pub fn new<T: AsPath + ?Sized>(root: &T) -> PathBuilder {
+        PathBuilder {
+            path: root.as_path().to_path_buf(),
+        }
+    }
+
+    /// 拼接路径
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 要拼接的路径
+    pub fn join<T: AsPath + ?Sized>(mut self, path: &T) -> PathBuilder {
+        self.path.push(path.as_path());
+        self
+    }
+
+    /// 条件为true时拼接路径
+    ///
+    /// # 参数
+    ///
+    /// * `condition` - 条件
+    /// * `path` - 要拼接的路径
+    pub fn join_if<T: AsPath + ?Sized>(self, condition: bool, path: &T) -> PathBuilder {
+        if condition {
+            self.join(path)
+        } else {
+            self
+        }
+    }
+
+    /// 替换(或添加)扩展名
+    ///
+    /// # 参数
+    ///
+    /// * `extension` - 扩展名(可以包括开头的 `.`)
+    pub fn with_extension(mut self, extension: &str) -> PathBuilder {
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        self.path.set_extension(extension);
+        self
+    }
+
+    /// 返回构建的路径
+    pub fn build(self) -> PathBuf {
+        self.path
+    }
+
+    /// 返回构建的字符串路径
+    pub fn build_string(self) -> String {
+        FromPath::from_path(&self.path)
+    }
+
+    /// 创建父级目录并返回构建的路径
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::path::PathBuilder;
+    ///
+    /// fn main() {
+    ///     let path = PathBuilder::new("./target/__test/path_test/ensure_parent")
+    ///         .join("dir1/file.txt")
+    ///         .ensure_parent()
+    ///         .unwrap();
+    ///     assert!(path.parent().unwrap().is_dir());
+    /// }
+    /// ```
+    pub fn ensure_parent(self) -> FsIOResult<PathBuf> {
+        directory::create_parent(&self.path)?;
+        Ok(self.path)
+    }
+
+    /// 文件不存在时创建空文件(包括父级目录)并返回构建的路径
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::path::PathBuilder;
+    ///
+    /// fn main() {
+    ///     let path = PathBuilder::new("./target/__test/path_test/ensure_exists")
+    ///         .join("file")
+    ///         .with_extension("txt")
+    ///         .ensure_exists()
+    ///         .unwrap();
+    ///     assert!(path.is_file());
+    /// }
+    /// ```
+    pub fn ensure_exists(self) -> FsIOResult<PathBuf> {
+        file::ensure_exists(&self.path)?;
+        Ok(self.path)
+    }
+}
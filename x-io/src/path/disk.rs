@@ -0,0 +1,141 @@
+//! # disk
+//!
+//! Disk space queries for the file system containing a path.
+//!
+use std::io;
+use std::path::Path;
+
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::result::FsIOResult;
+
+/// 磁盘空间信息(单位为字节)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiskSpace {
+    /// 总空间
+    pub total: u64,
+    /// 剩余空间(包括保留给超级用户的空间)
+    pub free: u64,
+    /// 当前用户可用的空间
+    pub available: u64,
+}
+
+/// 返回路径所在文件系统的磁盘空间信息
+///
+/// # 参数
+///
+/// * `path` - 路径(文件或目录)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let space = path::disk_space("./src").unwrap();
+///     assert!(space.total > 0);
+///     assert!(space.available <= space.total);
+/// }
+/// ```
+pub fn disk_space<T: AsPath + ?Sized>(path: &T) -> FsIOResult<DiskSpace> {
+    let path_obj = path.as_path();
+
+    match query_disk_space(path_obj) {
+        Ok(space) => Ok(space),
+        Err(error) => Err(FsIOError::io(Operation::Metadata, &path_obj, error)),
+    }
+}
+
+/// 返回路径所在文件系统中当前用户可用的空间(单位为字节)
+///
+/// # 参数
+///
+/// * `path` - 路径(文件或目录)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let free = path::disk_free("./src").unwrap();
+///     assert!(free <= path::disk_total("./src").unwrap());
+/// }
+/// ```
+pub fn disk_free<T: AsPath + ?Sized>(path: &T) -> FsIOResult<u64> {
+    disk_space(path).map(|space| space.available)
+}
+
+/// 返回路径所在文件系统的总空间(单位为字节)
+///
+/// # 参数
+///
+/// * `path` - 路径(文件或目录)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let total = path::disk_total("./src").unwrap();
+///     assert!(total > 0);
+/// }
+/// ```
+pub fn disk_total<T: AsPath + ?Sized>(path: &T) -> FsIOResult<u64> {
+    disk_space(path).map(|space| space.total)
+}
+
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn query_disk_space(path: &Path) -> io::Result<DiskSpace> {
+    use std::ffi::CString;
+    use std::mem::MaybeUninit;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path_string = match CString::new(path.as_os_str().as_bytes()) {
+        Ok(value) => value,
+        Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidInput, error)),
+    };
+
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    if unsafe { libc::statvfs(path_string.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let stat = unsafe { stat.assume_init() };
+
+    let block_size = stat.f_frsize as u64;
+    Ok(DiskSpace {
+        total: stat.f_blocks as u64 * block_size,
+        free: stat.f_bfree as u64 * block_size,
+        available: stat.f_bavail as u64 * block_size,
+    })
+}
+
+#[cfg(windows)]
+fn query_disk_space(path: &Path) -> io::Result<DiskSpace> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide_path: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut available = 0u64;
+    let mut total = 0u64;
+    let mut free = 0u64;
+
+    let result =
+        unsafe { GetDiskFreeSpaceExW(wide_path.as_ptr(), &mut available, &mut total, &mut free) };
+    if result == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    Ok(DiskSpace {
+        total,
+        free,
+        available,
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn query_disk_space(_path: &Path) -> io::Result<DiskSpace> {
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
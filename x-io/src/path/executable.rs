@@ -0,0 +1,102 @@
+//! # executable
+//!
+//! Executable lookup in the PATH (like `which`).
+//!
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// 在 `PATH` 环境变量的目录中查找可执行文件, 返回第一个找到的路径
+///
+/// windows上依次尝试 `PATHEXT` 中的扩展名, unix上要求文件具有执行权限.
+/// 名称包含路径分隔符时直接检查该路径.
+///
+/// # 参数
+///
+/// * `name` - 可执行文件名称
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let cargo = path::find_executable("cargo");
+///     assert!(cargo.is_some());
+///
+///     let missing = path::find_executable("x-io-missing-executable");
+///     assert!(missing.is_none());
+/// }
+/// ```
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    if name.is_empty() {
+        return None;
+    }
+
+    let name_path = Path::new(name);
+    if name_path.components().count() > 1 {
+        return executable_candidates(name_path)
+            .into_iter()
+            .find(|candidate| is_executable(candidate));
+    }
+
+    let paths = env::var_os("PATH")?;
+    for directory in env::split_paths(&paths) {
+        if directory.as_os_str().is_empty() {
+            continue;
+        }
+
+        let found = executable_candidates(&directory.join(name))
+            .into_iter()
+            .find(|candidate| is_executable(candidate));
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    None
+}
+
+/// 返回需要检查的路径(windows上包括添加 `PATHEXT` 扩展名的路径)
+fn executable_candidates(path: &Path) -> Vec<PathBuf> {
+    let mut candidates = vec![];
+
+    if cfg!(windows) {
+        let extensions = env::var("PATHEXT").unwrap_or(".COM;.EXE;.BAT;.CMD".to_string());
+        let has_extension = path.extension().is_some_and(|extension| {
+            extensions.split(';').any(|known| {
+                known
+                    .trim_start_matches('.')
+                    .eq_ignore_ascii_case(&extension.to_string_lossy())
+            })
+        });
+        if has_extension {
+            candidates.push(path.to_path_buf());
+        }
+
+        for extension in extensions.split(';').filter(|value| !value.is_empty()) {
+            let mut candidate = OsString::from(path.as_os_str());
+            candidate.push(extension);
+            candidates.push(PathBuf::from(candidate));
+        }
+    } else {
+        candidates.push(path.to_path_buf());
+    }
+
+    candidates
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    match path.metadata() {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -2,6 +2,7 @@
 //!
 //! FromPath trait and implementations.
 //!
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 
 /// Defines as path trait.
@@ -20,4 +21,10 @@ impl FromPath for PathBuf {
     fn from_path(path: &Path) -> PathBuf {
         path.to_path_buf()
     }
-}
\ No newline at end of file
+}
+
+impl FromPath for OsString {
+    fn from_path(path: &Path) -> OsString {
+        path.as_os_str().to_os_string()
+    }
+}
@@ -0,0 +1,269 @@
+//! # glob
+//!
+//! Glob pattern matching against single paths.
+//!
+use std::path::Component;
+
+use crate::error::FsIOError;
+use crate::path::as_path::AsPath;
+use crate::result::FsIOResult;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    /// 普通字符
+    Literal(char),
+    /// `?`, 匹配除 `/` 外的任意一个字符
+    AnyChar,
+    /// `*`, 匹配除 `/` 外的任意多个字符
+    Star,
+    /// `**/`, 匹配零个或多个目录
+    RecursivePrefix,
+    /// `**`, 匹配任意多个字符(包括 `/`)
+    RecursiveAny,
+    /// `[...]` 或 `[!...]`, 匹配字符范围
+    Class(bool, Vec<(char, char)>),
+}
+
+/// 预编译的glob模式, 用于重复匹配路径
+///
+/// 支持的语法:
+///
+/// * `?` - 除 `/` 外的任意一个字符
+/// * `*` - 除 `/` 外的任意多个字符
+/// * `**` - 任意多层目录, 例如 `src/**/*.rs`
+/// * `[abc]`, `[a-z]`, `[!a-z]` - 字符范围
+///
+/// 路径和模式都使用 `/` 作为分隔符比较, 开头的 `./` 会被忽略.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path::GlobPattern;
+///
+/// fn main() {
+///     let pattern = GlobPattern::new("src/**/*.rs").unwrap();
+///     assert!(pattern.matches("src/lib.rs"));
+///     assert!(pattern.matches("./src/path/mod.rs"));
+///     assert!(!pattern.matches("src/path/mod.txt"));
+///     assert!(!pattern.matches("tests/lib.rs"));
+///
+///     // many stars do not backtrack exponentially
+///     let pattern = GlobPattern::new(&"*a".repeat(20)).unwrap();
+///     assert!(!pattern.matches(&("a".repeat(60) + "b")));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobPattern {
+    pattern: String,
+    tokens: Vec<Token>,
+}
+
+impl GlobPattern {
+    /// 编译glob模式, 模式无效(例如 `[` 没有结束)时返回错误
+    ///
+    /// # 参数
+    ///
+    /// * `pattern` - glob模式
+    pub fn new(pattern: &str) -> FsIOResult<GlobPattern> {
+        let normalized = pattern.replace('\\', "/");
+        let normalized = normalized.trim_start_matches("./");
+        let chars: Vec<char> = normalized.chars().collect();
+        let mut tokens = vec![];
+        let mut index = 0;
+
+        while index < chars.len() {
+            match chars[index] {
+                '?' => tokens.push(Token::AnyChar),
+                '*' => {
+                    if chars.get(index + 1) == Some(&'*') {
+                        index += 1;
+                        if chars.get(index + 1) == Some(&'/') {
+                            index += 1;
+                            tokens.push(Token::RecursivePrefix);
+                        } else {
+                            tokens.push(Token::RecursiveAny);
+                        }
+                    } else {
+                        tokens.push(Token::Star);
+                    }
+                }
+                '[' => {
+                    let (token, next_index) = parse_class(&chars, index, pattern)?;
+                    tokens.push(token);
+                    index = next_index;
+                }
+                value => tokens.push(Token::Literal(value)),
+            }
+            index += 1;
+        }
+
+        Ok(GlobPattern {
+            pattern: pattern.to_string(),
+            tokens,
+        })
+    }
+
+    /// 返回路径是否匹配模式
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 路径
+    pub fn matches<T: AsPath + ?Sized>(&self, path: &T) -> bool {
+        let mut path_string = String::new();
+        for component in path.as_path().components() {
+            match component {
+                Component::CurDir => {}
+                Component::RootDir => path_string.push('/'),
+                Component::Prefix(prefix) => {
+                    path_string.push_str(&prefix.as_os_str().to_string_lossy())
+                }
+                Component::ParentDir | Component::Normal(_) => {
+                    if !path_string.is_empty() && !path_string.ends_with('/') {
+                        path_string.push('/');
+                    }
+                    path_string.push_str(&component.as_os_str().to_string_lossy());
+                }
+            }
+        }
+
+        let chars: Vec<char> = path_string.chars().collect();
+        match_tokens(&self.tokens, &chars)
+    }
+
+    /// 返回原始的glob模式
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+}
+
+/// 返回路径是否匹配glob模式(语法参见 `GlobPattern`), 模式无效时返回错误
+///
+/// # 参数
+///
+/// * `path` - 路径
+/// * `pattern` - glob模式
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert!(path::matches_glob("assets/img/logo.png", "assets/**/*.[pj][np]g").unwrap());
+///     assert!(!path::matches_glob("assets/img/logo.gif", "assets/*.png").unwrap());
+///     assert!(path::matches_glob("file.txt", "[a-").is_err());
+/// }
+/// ```
+pub fn matches_glob<T: AsPath + ?Sized>(path: &T, pattern: &str) -> FsIOResult<bool> {
+    GlobPattern::new(pattern).map(|glob| glob.matches(path))
+}
+
+fn parse_class(chars: &[char], start: usize, pattern: &str) -> FsIOResult<(Token, usize)> {
+    let mut index = start + 1;
+    let negated = matches!(chars.get(index), Some('!') | Some('^'));
+    if negated {
+        index += 1;
+    }
+
+    let mut ranges = vec![];
+    let mut first = true;
+    loop {
+        let value = match chars.get(index) {
+            Some(value) => *value,
+            None => {
+                return Err(FsIOError::Parse(
+                    format!("Invalid glob pattern: {}, unclosed '['.", pattern).to_string(),
+                    None,
+                ))
+            }
+        };
+
+        // `]` directly after `[` or `[!` is a literal
+        if value == ']' && !first {
+            return Ok((Token::Class(negated, ranges), index));
+        }
+        first = false;
+
+        if chars.get(index + 1) == Some(&'-') && chars.get(index + 2).is_some_and(|end| *end != ']')
+        {
+            ranges.push((value, chars[index + 2]));
+            index += 3;
+        } else {
+            ranges.push((value, value));
+            index += 1;
+        }
+    }
+}
+
+fn match_tokens(tokens: &[Token], chars: &[char]) -> bool {
+    // positions (token index, char index) already known not to match, so
+    // patterns with several stars are matched in polynomial time
+    let mut failed = vec![false; (tokens.len() + 1) * (chars.len() + 1)];
+    match_from(tokens, chars, 0, 0, &mut failed)
+}
+
+fn match_from(
+    tokens: &[Token],
+    chars: &[char],
+    token_index: usize,
+    char_index: usize,
+    failed: &mut [bool],
+) -> bool {
+    let key = token_index * (chars.len() + 1) + char_index;
+    if failed[key] {
+        return false;
+    }
+
+    let matched = match tokens.get(token_index) {
+        None => char_index == chars.len(),
+        Some(token) => {
+            let next = token_index + 1;
+            let current = chars.get(char_index);
+            match token {
+                Token::Literal(value) => {
+                    current == Some(value) && match_from(tokens, chars, next, char_index + 1, failed)
+                }
+                Token::AnyChar => {
+                    current.is_some_and(|value| *value != '/')
+                        && match_from(tokens, chars, next, char_index + 1, failed)
+                }
+                Token::Class(negated, ranges) => match current {
+                    Some(value) if *value != '/' => {
+                        let in_ranges = ranges
+                            .iter()
+                            .any(|(start, end)| start <= value && value <= end);
+                        in_ranges != *negated
+                            && match_from(tokens, chars, next, char_index + 1, failed)
+                    }
+                    _ => false,
+                },
+                Token::Star => {
+                    let mut matched = false;
+                    for index in char_index..=chars.len() {
+                        if match_from(tokens, chars, next, index, failed) {
+                            matched = true;
+                            break;
+                        }
+                        if index < chars.len() && chars[index] == '/' {
+                            break;
+                        }
+                    }
+                    matched
+                }
+                Token::RecursiveAny => (char_index..=chars.len())
+                    .any(|index| match_from(tokens, chars, next, index, failed)),
+                Token::RecursivePrefix => {
+                    match_from(tokens, chars, next, char_index, failed)
+                        || (char_index..chars.len())
+                            .filter(|index| chars[*index] == '/')
+                            .any(|index| match_from(tokens, chars, next, index + 1, failed))
+                }
+            }
+        }
+    };
+
+    if !matched {
+        failed[key] = true;
+    }
+    matched
+}
@@ -0,0 +1,130 @@
+//! # identity
+//!
+//! File identity checks (same file, same file system).
+//!
+use std::io;
+use std::path::Path;
+
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::result::FsIOResult;
+
+/// 文件标识(设备号, 文件号)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct FileId {
+    device: u64,
+    index: u64,
+}
+
+/// 返回两个路径是否指向同一个文件(比较设备号和inode/文件索引, 跟随符号链接)
+///
+/// # 参数
+///
+/// * `path1` - 第一个路径
+/// * `path2` - 第二个路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     let file_path = "./target/__test/path_test/is_same_file/file.txt";
+///     file::write_text_file(file_path, "some content").unwrap();
+///     file::write_text_file("./target/__test/path_test/is_same_file/other.txt", "some content").unwrap();
+///
+///     assert!(path::is_same_file(file_path, "./target/__test/path_test/is_same_file/../is_same_file/file.txt").unwrap());
+///     assert!(!path::is_same_file(file_path, "./target/__test/path_test/is_same_file/other.txt").unwrap());
+/// }
+/// ```
+pub fn is_same_file<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    path1: &S,
+    path2: &T,
+) -> FsIOResult<bool> {
+    let first = file_id(path1.as_path())?;
+    let second = file_id(path2.as_path())?;
+
+    Ok(first == second)
+}
+
+/// 返回两个路径是否位于同一个文件系统(同一设备/卷), 可用于决定使用移动还是复制
+///
+/// # 参数
+///
+/// * `path1` - 第一个路径
+/// * `path2` - 第二个路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/path_test/is_same_filesystem/file.txt", "some content").unwrap();
+///
+///     let result = path::is_same_filesystem("./target/__test/path_test/is_same_filesystem/file.txt", "./target/__test/path_test");
+///     assert!(result.unwrap());
+/// }
+/// ```
+pub fn is_same_filesystem<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    path1: &S,
+    path2: &T,
+) -> FsIOResult<bool> {
+    let first = file_id(path1.as_path())?;
+    let second = file_id(path2.as_path())?;
+
+    Ok(first.device == second.device)
+}
+
+/// 返回路径(跟随符号链接)的文件标识
+pub(crate) fn file_id(path: &Path) -> FsIOResult<FileId> {
+    match query_file_id(path) {
+        Ok(id) => Ok(id),
+        Err(error) => Err(FsIOError::io(Operation::Metadata, &path, error)),
+    }
+}
+
+#[cfg(unix)]
+fn query_file_id(path: &Path) -> io::Result<FileId> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = path.metadata()?;
+    Ok(FileId {
+        device: metadata.dev(),
+        index: metadata.ino(),
+    })
+}
+
+#[cfg(windows)]
+fn query_file_id(path: &Path) -> io::Result<FileId> {
+    use std::fs::OpenOptions;
+    use std::mem::MaybeUninit;
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        GetFileInformationByHandle, BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS,
+    };
+
+    // FILE_FLAG_BACKUP_SEMANTICS is required to open directories
+    let file = OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)?;
+
+    let mut info = MaybeUninit::<BY_HANDLE_FILE_INFORMATION>::uninit();
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), info.as_mut_ptr()) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let info = unsafe { info.assume_init() };
+
+    Ok(FileId {
+        device: info.dwVolumeSerialNumber as u64,
+        index: ((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64,
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn query_file_id(path: &Path) -> io::Result<FileId> {
+    let _ = path.metadata()?;
+    Err(io::Error::from(io::ErrorKind::Unsupported))
+}
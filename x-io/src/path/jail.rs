@@ -0,0 +1,152 @@
+//! # jail
+//!
+//! Joining untrusted relative paths onto a root without leaving it.
+//!
+use std::path::{Component, Path, PathBuf};
+
+use crate::dunce;
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::path::resolve_symlinks;
+use crate::result::FsIOResult;
+
+/// 路径限制的检查方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JailMode {
+    /// 解析根目录和结果路径中的所有符号链接后检查(根目录必须存在)
+    #[default]
+    Strict,
+    /// 只按字面检查, 不访问文件系统(不能防止通过符号链接离开根目录)
+    Lexical,
+}
+
+/// 将不可信的相对路径拼接到根目录, 结果在根目录之外时返回 `FsIOError::PermissionDenied`
+///
+/// 解析根目录和结果路径中的所有符号链接(`JailMode::Strict`), 返回根目录内的绝对路径.
+/// 绝对路径被拒绝, `..` 按字面移除上一级组件.
+///
+/// # 参数
+///
+/// * `root` - 根目录
+/// * `relative` - 不可信的相对路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     let root = "./target/__test/path_test/join_jailed/root";
+///     file::write_text_file("./target/__test/path_test/join_jailed/root/file.txt", "some content").unwrap();
+///
+///     let file_path = path::join_jailed(root, "dir/../file.txt").unwrap();
+///     assert_eq!(file_path, std::fs::canonicalize(root).unwrap().join("file.txt"));
+///
+///     assert!(path::join_jailed(root, "../secret.txt").unwrap_err().is_permission_denied());
+///     assert!(path::join_jailed(root, "/etc/passwd").unwrap_err().is_permission_denied());
+///
+///     #[cfg(unix)]
+///     {
+///         let link_path = "./target/__test/path_test/join_jailed/root/escape";
+///         let _ = std::fs::remove_file(link_path);
+///         std::os::unix::fs::symlink("..", link_path).unwrap();
+///
+///         assert!(path::join_jailed(root, "escape/secret.txt").unwrap_err().is_permission_denied());
+///     }
+/// }
+/// ```
+pub fn join_jailed<R: AsPath + ?Sized, T: AsPath + ?Sized>(
+    root: &R,
+    relative: &T,
+) -> FsIOResult<PathBuf> {
+    join_jailed_with(root, relative, JailMode::Strict)
+}
+
+/// 将不可信的相对路径拼接到根目录, 结果在根目录之外时返回 `FsIOError::PermissionDenied`
+///
+/// # 参数
+///
+/// * `root` - 根目录
+/// * `relative` - 不可信的相对路径
+/// * `mode` - 检查方式
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use x_io::path::JailMode;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let file_path = path::join_jailed_with("/srv/files", "a/./b/../c.txt", JailMode::Lexical).unwrap();
+///     assert_eq!(file_path, PathBuf::from("/srv/files/a/c.txt"));
+///
+///     let result = path::join_jailed_with("/srv/files", "a/../../c.txt", JailMode::Lexical);
+///     assert!(result.unwrap_err().is_permission_denied());
+/// }
+/// ```
+pub fn join_jailed_with<R: AsPath + ?Sized, T: AsPath + ?Sized>(
+    root: &R,
+    relative: &T,
+    mode: JailMode,
+) -> FsIOResult<PathBuf> {
+    let root_path = root.as_path();
+    let relative_path = relative.as_path();
+
+    match mode {
+        JailMode::Lexical => Ok(root_path.join(normalize_relative(relative_path)?)),
+        JailMode::Strict => {
+            let canonical_root = match dunce::canonicalize(root_path) {
+                Ok(canonical_root) => canonical_root,
+                Err(error) => {
+                    return Err(FsIOError::io(Operation::Canonicalize, &root_path, error))
+                }
+            };
+            resolve_jailed(&canonical_root, relative_path, true)
+        }
+    }
+}
+
+/// 按字面规范相对路径, 绝对路径或超出起点的 `..` 返回 `FsIOError::PermissionDenied`
+fn normalize_relative(relative: &Path) -> FsIOResult<PathBuf> {
+    let mut normalized = PathBuf::new();
+
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => normalized.push(name),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    return Err(FsIOError::PermissionDenied(relative.to_path_buf(), None));
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(FsIOError::PermissionDenied(relative.to_path_buf(), None))
+            }
+        }
+    }
+
+    Ok(normalized)
+}
+
+/// 将相对路径拼接到规范化的根目录并解析符号链接, `follow_last` 为false时不跟随最后一级的符号链接
+pub(crate) fn resolve_jailed(
+    canonical_root: &Path,
+    relative: &Path,
+    follow_last: bool,
+) -> FsIOResult<PathBuf> {
+    let normalized = normalize_relative(relative)?;
+
+    let resolved = match (follow_last, normalized.file_name(), normalized.parent()) {
+        (false, Some(name), Some(parent)) => {
+            resolve_symlinks(&canonical_root.join(parent))?.join(name)
+        }
+        _ => resolve_symlinks(&canonical_root.join(&normalized))?,
+    };
+
+    if resolved.starts_with(canonical_root) {
+        Ok(resolved)
+    } else {
+        Err(FsIOError::PermissionDenied(relative.to_path_buf(), None))
+    }
+}
@@ -0,0 +1,214 @@
+//! # kind
+//!
+//! File type detection, including special files (sockets, FIFOs, devices), and error-aware existence checks.
+//!
+use std::fs::{metadata, symlink_metadata, FileType, Metadata};
+use std::io;
+use std::path::Path;
+
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::result::FsIOResult;
+
+/// 文件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileKind {
+    /// 普通文件
+    File,
+    /// 目录
+    Directory,
+    /// 符号链接
+    Symlink,
+    /// 套接字(unix)
+    Socket,
+    /// 命名管道(unix)
+    Fifo,
+    /// 块设备(unix)
+    BlockDevice,
+    /// 字符设备(unix)
+    CharDevice,
+    /// 其他类型
+    Other,
+}
+
+impl FileKind {
+    /// 根据 `FileType` 返回文件类型
+    ///
+    /// # 参数
+    ///
+    /// * `file_type` - 文件类型
+    pub fn from_file_type(file_type: FileType) -> FileKind {
+        if file_type.is_file() {
+            return FileKind::File;
+        }
+        if file_type.is_dir() {
+            return FileKind::Directory;
+        }
+        if file_type.is_symlink() {
+            return FileKind::Symlink;
+        }
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+
+            if file_type.is_socket() {
+                return FileKind::Socket;
+            }
+            if file_type.is_fifo() {
+                return FileKind::Fifo;
+            }
+            if file_type.is_block_device() {
+                return FileKind::BlockDevice;
+            }
+            if file_type.is_char_device() {
+                return FileKind::CharDevice;
+            }
+        }
+
+        FileKind::Other
+    }
+
+    /// 返回是否为特殊文件(套接字, 命名管道, 设备或其他类型), 读取特殊文件可能阻塞或失败
+    pub fn is_special(&self) -> bool {
+        !matches!(
+            self,
+            FileKind::File | FileKind::Directory | FileKind::Symlink
+        )
+    }
+}
+
+/// 返回路径的文件类型(不跟随符号链接)
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+/// use x_io::path::FileKind;
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/path_test/kind/file.txt", "some content").unwrap();
+///
+///     assert_eq!(path::kind("./target/__test/path_test/kind/file.txt").unwrap(), FileKind::File);
+///     assert_eq!(path::kind("./target/__test/path_test/kind").unwrap(), FileKind::Directory);
+///     assert!(path::kind("./target/__test/path_test/kind/missing").is_err());
+///
+///     #[cfg(unix)]
+///     {
+///         let fifo_path = "./target/__test/path_test/kind/events.fifo";
+///         let _ = std::fs::remove_file(fifo_path);
+///         file::create_fifo(fifo_path, 0o600).unwrap();
+///
+///         let kind = path::kind(fifo_path).unwrap();
+///         assert_eq!(kind, FileKind::Fifo);
+///         assert!(kind.is_special());
+///     }
+/// }
+/// ```
+pub fn kind<T: AsPath + ?Sized>(path: &T) -> FsIOResult<FileKind> {
+    let file_path = path.as_path();
+
+    match symlink_metadata(file_path) {
+        Ok(metadata) => Ok(FileKind::from_file_type(metadata.file_type())),
+        Err(error) => Err(FsIOError::io(Operation::Metadata, &file_path, error)),
+    }
+}
+
+/// 返回路径是否存在(跟随符号链接), 与 `Path::exists` 不同, 无法确定时(例如权限不足)返回错误
+///
+/// 路径不存在或某一级父路径不是目录时返回 `false`.
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/path_test/exists/file.txt", "some content").unwrap();
+///
+///     assert!(path::exists("./target/__test/path_test/exists/file.txt").unwrap());
+///     assert!(!path::exists("./target/__test/path_test/exists/missing.txt").unwrap());
+///     assert!(!path::exists("./target/__test/path_test/exists/file.txt/child").unwrap());
+/// }
+/// ```
+pub fn exists<T: AsPath + ?Sized>(path: &T) -> FsIOResult<bool> {
+    Ok(target_metadata(path.as_path())?.is_some())
+}
+
+/// 返回路径是否是文件(跟随符号链接), 无法确定时(例如权限不足)返回错误
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/path_test/is_file/file.txt", "some content").unwrap();
+///
+///     assert!(path::is_file("./target/__test/path_test/is_file/file.txt").unwrap());
+///     assert!(!path::is_file("./target/__test/path_test/is_file").unwrap());
+///     assert!(!path::is_file("./target/__test/path_test/is_file/missing.txt").unwrap());
+/// }
+/// ```
+pub fn is_file<T: AsPath + ?Sized>(path: &T) -> FsIOResult<bool> {
+    Ok(target_metadata(path.as_path())?.is_some_and(|metadata| metadata.is_file()))
+}
+
+/// 返回路径是否是目录(跟随符号链接), 无法确定时(例如权限不足)返回错误
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/path_test/is_dir/file.txt", "some content").unwrap();
+///
+///     assert!(path::is_dir("./target/__test/path_test/is_dir").unwrap());
+///     assert!(!path::is_dir("./target/__test/path_test/is_dir/file.txt").unwrap());
+///     assert!(!path::is_dir("./target/__test/path_test/is_dir/missing").unwrap());
+/// }
+/// ```
+pub fn is_dir<T: AsPath + ?Sized>(path: &T) -> FsIOResult<bool> {
+    Ok(target_metadata(path.as_path())?.is_some_and(|metadata| metadata.is_dir()))
+}
+
+/// 读取路径(跟随符号链接)的元数据, 路径确定不存在时返回 `None`
+fn target_metadata(path: &Path) -> FsIOResult<Option<Metadata>> {
+    match metadata(path) {
+        Ok(value) => Ok(Some(value)),
+        Err(error) if is_absent(&error) => Ok(None),
+        Err(error) => Err(FsIOError::io(Operation::Metadata, &path, error)),
+    }
+}
+
+fn is_absent(error: &io::Error) -> bool {
+    if error.kind() == io::ErrorKind::NotFound {
+        return true;
+    }
+
+    #[cfg(unix)]
+    {
+        error.raw_os_error() == Some(libc::ENOTDIR)
+    }
+    #[cfg(not(unix))]
+    {
+        false
+    }
+}
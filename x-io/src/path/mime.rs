@@ -0,0 +1,147 @@
+//! # mime
+//!
+//! MIME type guessing from the file extension, with a magic byte fallback.
+//!
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::path::as_path::AsPath;
+
+/// 扩展名(小写)和MIME类型
+const EXTENSION_TYPES: [(&str, &str); 56] = [
+    ("7z", "application/x-7z-compressed"),
+    ("avif", "image/avif"),
+    ("bmp", "image/bmp"),
+    ("bz2", "application/x-bzip2"),
+    ("css", "text/css"),
+    ("csv", "text/csv"),
+    ("doc", "application/msword"),
+    (
+        "docx",
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    ),
+    ("eot", "application/vnd.ms-fontobject"),
+    ("epub", "application/epub+zip"),
+    ("flac", "audio/flac"),
+    ("gif", "image/gif"),
+    ("gz", "application/gzip"),
+    ("htm", "text/html"),
+    ("html", "text/html"),
+    ("ico", "image/vnd.microsoft.icon"),
+    ("ics", "text/calendar"),
+    ("jpeg", "image/jpeg"),
+    ("jpg", "image/jpeg"),
+    ("js", "text/javascript"),
+    ("json", "application/json"),
+    ("jsonld", "application/ld+json"),
+    ("m4a", "audio/mp4"),
+    ("md", "text/markdown"),
+    ("mjs", "text/javascript"),
+    ("mp3", "audio/mpeg"),
+    ("mp4", "video/mp4"),
+    ("mpeg", "video/mpeg"),
+    ("oga", "audio/ogg"),
+    ("ogg", "audio/ogg"),
+    ("ogv", "video/ogg"),
+    ("otf", "font/otf"),
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("ppt", "application/vnd.ms-powerpoint"),
+    (
+        "pptx",
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    ),
+    ("rar", "application/vnd.rar"),
+    ("rtf", "application/rtf"),
+    ("svg", "image/svg+xml"),
+    ("tar", "application/x-tar"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("toml", "application/toml"),
+    ("ttf", "font/ttf"),
+    ("txt", "text/plain"),
+    ("wasm", "application/wasm"),
+    ("wav", "audio/wav"),
+    ("weba", "audio/webm"),
+    ("webm", "video/webm"),
+    ("webp", "image/webp"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("xls", "application/vnd.ms-excel"),
+    (
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    ),
+    ("xml", "application/xml"),
+    ("zip", "application/zip"),
+];
+
+/// 文件开头的魔术字节和MIME类型
+const MAGIC_TYPES: [(&[u8], &str); 10] = [
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"BZh", "application/x-bzip2"),
+    (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (b"\0asm", "application/wasm"),
+];
+
+/// 根据扩展名返回MIME类型, 扩展名未知时根据已存在文件开头的魔术字节判断, 无法判断时返回None
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     assert_eq!(path::mime_type("./static/site.CSS").unwrap(), "text/css");
+///     assert_eq!(path::mime_type("./static/index.html").unwrap(), "text/html");
+///
+///     let file_path = "./target/__test/path_test/mime_type/document";
+///     file::write_text_file(file_path, "%PDF-1.7").unwrap();
+///     assert_eq!(path::mime_type(file_path).unwrap(), "application/pdf");
+/// }
+/// ```
+pub fn mime_type<T: AsPath + ?Sized>(path: &T) -> Option<&'static str> {
+    let path_obj = path.as_path();
+
+    let by_extension = path_obj.extension().and_then(|extension| {
+        let extension = extension.to_string_lossy().to_ascii_lowercase();
+        EXTENSION_TYPES
+            .iter()
+            .find(|(known, _)| *known == extension)
+            .map(|(_, mime)| *mime)
+    });
+
+    by_extension.or_else(|| sniff_mime_type(path_obj))
+}
+
+/// 根据文件开头的魔术字节判断MIME类型
+fn sniff_mime_type(path: &Path) -> Option<&'static str> {
+    if !path.is_file() {
+        return None;
+    }
+
+    let mut header = [0u8; 16];
+    let mut file = File::open(path).ok()?;
+    let size = file.read(&mut header).ok()?;
+    let header = &header[..size];
+
+    if header.len() >= 12 && header.starts_with(b"RIFF") && &header[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+
+    MAGIC_TYPES
+        .iter()
+        .find(|(magic, _)| header.starts_with(magic))
+        .map(|(_, mime)| *mime)
+}
@@ -2,18 +2,54 @@
 //!
 //! Path utility functions and traits.
 //!
+use std::env;
+use std::ffi::{OsStr, OsString};
 use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR, MAIN_SEPARATOR_STR};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::SystemTime;
 
 use as_path::AsPath;
 use from_path::FromPath;
 
 use crate::dunce;
-use crate::error::FsIOError;
+use crate::error::{FsIOError, Operation};
 use crate::result::FsIOResult;
 
+pub use app_dirs::{app_dirs, AppDirs};
+#[cfg(feature = "attributes")]
+pub use attributes::{attributes, set_attributes, FileAttributes};
+pub use builder::PathBuilder;
+pub use disk::{disk_free, disk_space, disk_total, DiskSpace};
+pub use executable::find_executable;
+pub use glob::{matches_glob, GlobPattern};
+pub(crate) use identity::{file_id, FileId};
+pub use identity::{is_same_file, is_same_filesystem};
+pub(crate) use jail::resolve_jailed;
+pub use jail::{join_jailed, join_jailed_with, JailMode};
+pub use kind::{exists, is_dir, is_file, kind, FileKind};
+pub use mime::mime_type;
+pub(crate) use symlink::create_symlink;
+pub use symlink::{resolve_symlinks, symlink_depth};
+pub use windows::{is_unc, simplify_windows, to_extended_length};
+
+mod app_dirs;
 pub mod as_path;
+#[cfg(feature = "attributes")]
+mod attributes;
+mod builder;
+mod disk;
+mod executable;
 pub mod from_path;
+mod glob;
+mod identity;
+mod jail;
+mod kind;
+mod mime;
+mod symlink;
+mod windows;
 
 /// 规范提供的路径并且返回字符串路径
 ///
@@ -55,13 +91,60 @@ pub fn normalize_as_string<T: AsPath + ?Sized>(path: &T) -> FsIOResult<String> {
                 Ok(win_path_string)
             }
         }
-        Err(error) => Err(FsIOError::IOError(
-            "Unable to canonicalize path.".to_string(),
-            Some(error),
-        )),
+        Err(error) => Err(FsIOError::io(Operation::Canonicalize, &path_obj, error)),
+    }
+}
+
+/// 规范提供的路径并且返回指定类型(`String`, `PathBuf` 或 `OsString`)的路径
+///
+/// # 参数
+///
+/// * `path` - 路径值
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let path_buf = path::normalize_as::<PathBuf, _>("./src/path/mod.rs").unwrap();
+///     assert!(path_buf.is_absolute());
+///     assert_eq!(path_buf.to_string_lossy(), path::normalize_as_string("./src/path/mod.rs").unwrap());
+/// }
+/// ```
+pub fn normalize_as<R: FromPath, T: AsPath + ?Sized>(path: &T) -> FsIOResult<R> {
+    let path_obj = path.as_path();
+
+    match path_obj.canonicalize() {
+        #[cfg(not(windows))]
+        Ok(path_buf) => Ok(FromPath::from_path(&path_buf)),
+        #[cfg(windows)]
+        Ok(path_buf) => Ok(FromPath::from_path(dunce::simplified(&path_buf))),
+        Err(error) => Err(FsIOError::io(Operation::Canonicalize, &path_obj, error)),
     }
 }
 
+/// 规范提供的路径并且返回 `PathBuf`, 不会丢失非UTF-8内容
+///
+/// # 参数
+///
+/// * `path` - 路径值
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let path_buf = path::normalize("./src/path/mod.rs").unwrap();
+///     assert!(path_buf.ends_with("src/path/mod.rs"));
+/// }
+/// ```
+pub fn normalize<T: AsPath + ?Sized>(path: &T) -> FsIOResult<PathBuf> {
+    normalize_as(path)
+}
+
 /// 规范提供的路径并且返回字符串路径
 ///
 /// # 参数
@@ -89,7 +172,9 @@ pub fn canonicalize_or<T: AsPath + ?Sized>(path: &T, or_value: &str) -> String {
     }
 }
 
-/// 返回最后一个路径组件(文件名或最后一个目录名)
+/// 仅按文本规范路径(处理 `.`, `..` 和多余的分隔符), 不访问文件系统, 路径可以不存在
+///
+/// 相对路径开头无法消除的 `..` 会被保留, 根目录之上的 `..` 会被忽略.
 ///
 /// # 参数
 ///
@@ -99,90 +184,1164 @@ pub fn canonicalize_or<T: AsPath + ?Sized>(path: &T, or_value: &str) -> String {
 ///
 /// ```
 /// use x_io::path;
-/// use x_io::path::as_path::AsPath;
-/// use std::path::Path;
+/// use std::path::PathBuf;
 ///
 /// fn main() {
-///     let basename = path::base_name("./src/path/mod.rs");
-///     assert_eq!(basename.unwrap(), "mod.rs");
+///     let path1 = path::normalize_lexically("./target//__test/./out/../build/file.txt");
+///     assert_eq!(path1, PathBuf::from("target/__test/build/file.txt"));
+///
+///     let path2 = path::normalize_lexically("../src/./path/..");
+///     assert_eq!(path2, PathBuf::from("../src"));
+///
+///     let path3 = path::normalize_lexically("a/..");
+///     assert_eq!(path3, PathBuf::from("."));
 /// }
 /// ```
-pub fn base_name<T: AsPath + ?Sized>(path: &T) -> Option<String> {
+pub fn normalize_lexically<T: AsPath + ?Sized>(path: &T) -> PathBuf {
     let path_obj = path.as_path();
-    match path_obj.file_name() {
-        Some(name) => Some(name.to_string_lossy().into_owned()),
-        None => None,
+    let mut components: Vec<Component> = vec![];
+
+    for component in path_obj.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match components.last() {
+                Some(Component::Normal(_)) => {
+                    components.pop();
+                }
+                Some(Component::RootDir) => {}
+                _ => components.push(component),
+            },
+            _ => components.push(component),
+        }
+    }
+
+    if components.is_empty() {
+        PathBuf::from(".")
+    } else {
+        components.iter().collect()
     }
 }
 
-/// 返回父级路径
+/// 返回多个路径最深的共同父级目录, 没有共同部分或没有路径时返回None
+///
+/// 路径会先按文本规范(参见 `normalize_lexically`), 如果结果是一个已存在的文件则返回其父级目录.
 ///
 /// # 参数
 ///
-/// * `path` - 路径
+/// * `paths` - 路径列表
 ///
 /// # 示例
 ///
 /// ```
 /// use x_io::path;
-/// use x_io::path::as_path::AsPath;
-/// use std::path::Path;
+/// use std::path::PathBuf;
 ///
 /// fn main() {
-///     let dirname = path::parent_directory("./src/path/mod.rs");
-///     assert_eq!(dirname.unwrap(), "./src/path");
+///     let ancestor = path::common_ancestor(vec!["./src/path/mod.rs", "./src/file.rs", "src/directory.rs"]);
+///     assert_eq!(ancestor.unwrap(), PathBuf::from("src"));
+///
+///     let ancestor = path::common_ancestor(vec!["./src/path/mod.rs"]);
+///     assert_eq!(ancestor.unwrap(), PathBuf::from("src/path"));
+///
+///     let ancestor = path::common_ancestor(vec!["src", "benches"]);
+///     assert!(ancestor.is_none());
 /// }
 /// ```
-pub fn parent_directory<T: AsPath + ?Sized>(path: &T) -> Option<String> {
-    let path_obj = path.as_path();
-    let directory = path_obj.parent();
-    match directory {
-        Some(directory_path) => {
-            let directory_path_string: String = FromPath::from_path(directory_path);
-            if directory_path_string.is_empty() {
-                None
-            } else {
-                Some(directory_path_string)
+pub fn common_ancestor<I, P>(paths: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = P>,
+    P: AsPath,
+{
+    let mut ancestor: Option<PathBuf> = None;
+
+    for path in paths {
+        let normalized = normalize_lexically(&path.as_path());
+        ancestor = match ancestor {
+            None => Some(normalized),
+            Some(current) => {
+                let shared: PathBuf = current
+                    .components()
+                    .zip(normalized.components())
+                    .take_while(|(left, right)| left == right)
+                    .map(|(left, _)| left)
+                    .collect();
+                Some(shared)
             }
+        };
+    }
+
+    match ancestor {
+        Some(path_buf) if path_buf.is_file() => path_buf.parent().map(Path::to_path_buf),
+        other => other,
+    }
+    .filter(|path_buf| !path_buf.as_os_str().is_empty() && path_buf.as_os_str() != ".")
+}
+
+/// 返回child路径是否位于ancestor目录之内(按文本规范后比较, 不访问文件系统), 路径相同时返回false
+///
+/// 相对路径以当前目录为基准, 需要解析符号链接时使用 `is_descendant_of_strict`.
+///
+/// # 参数
+///
+/// * `child` - 子路径
+/// * `ancestor` - 父级目录
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert!(path::is_descendant_of("./upload/a/../b/file.txt", "upload"));
+///     assert!(!path::is_descendant_of("./upload/../etc/passwd", "upload"));
+///     assert!(!path::is_descendant_of("./upload", "upload"));
+/// }
+/// ```
+pub fn is_descendant_of<S: AsPath + ?Sized, T: AsPath + ?Sized>(child: &S, ancestor: &T) -> bool {
+    let child_path = match absolute_lexically(child.as_path()) {
+        Some(path_buf) => path_buf,
+        None => return false,
+    };
+    let ancestor_path = match absolute_lexically(ancestor.as_path()) {
+        Some(path_buf) => path_buf,
+        None => return false,
+    };
+
+    child_path != ancestor_path && child_path.starts_with(&ancestor_path)
+}
+
+/// 返回child路径是否位于ancestor目录之内(解析所有符号链接后比较), 路径相同时返回false
+///
+/// 不存在的路径组件按原样比较(参见 `resolve_symlinks`).
+///
+/// # 参数
+///
+/// * `child` - 子路径
+/// * `ancestor` - 父级目录
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/path_test/is_descendant_of_strict/root/file.txt", "some content").unwrap();
+///
+///     let result = path::is_descendant_of_strict(
+///         "./target/__test/path_test/is_descendant_of_strict/root/new/file.txt",
+///         "./target/__test/path_test/is_descendant_of_strict/root",
+///     );
+///     assert!(result.unwrap());
+///
+///     #[cfg(unix)]
+///     {
+///         let link_path = "./target/__test/path_test/is_descendant_of_strict/root/escape";
+///         let _ = std::fs::remove_file(link_path);
+///         std::os::unix::fs::symlink("../", link_path).unwrap();
+///
+///         let result = path::is_descendant_of_strict(
+///             "./target/__test/path_test/is_descendant_of_strict/root/escape/file.txt",
+///             "./target/__test/path_test/is_descendant_of_strict/root",
+///         );
+///         assert!(!result.unwrap());
+///     }
+/// }
+/// ```
+pub fn is_descendant_of_strict<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    child: &S,
+    ancestor: &T,
+) -> FsIOResult<bool> {
+    let child_path = resolve_symlinks(child)?;
+    let ancestor_path = resolve_symlinks(ancestor)?;
+
+    Ok(child_path != ancestor_path && child_path.starts_with(&ancestor_path))
+}
+
+fn absolute_lexically(path: &Path) -> Option<PathBuf> {
+    if path.is_absolute() {
+        Some(normalize_lexically(&path))
+    } else {
+        match env::current_dir() {
+            Ok(current_dir) => Some(normalize_lexically(&current_dir.join(path))),
+            Err(_) => None,
         }
-        None => None,
     }
 }
 
-/// 返回最后修改时间(单位为毫秒)
+/// 返回当前用户的主目录(unix使用 `HOME`, windows使用 `USERPROFILE` 或 `HOMEDRIVE` + `HOMEPATH`)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let home = path::home_dir();
+///     assert!(home.is_some());
+/// }
+/// ```
+pub fn home_dir() -> Option<PathBuf> {
+    let home = if cfg!(windows) {
+        env::var_os("USERPROFILE").or_else(|| {
+            match (env::var_os("HOMEDRIVE"), env::var_os("HOMEPATH")) {
+                (Some(drive), Some(path)) => {
+                    let mut home = drive;
+                    home.push(path);
+                    Some(home)
+                }
+                _ => None,
+            }
+        })
+    } else {
+        env::var_os("HOME")
+    };
+
+    home.filter(|value| !value.is_empty()).map(PathBuf::from)
+}
+
+/// 将路径开头的 `~` 展开为当前用户的主目录, 其他路径按原样返回
 ///
 /// # 参数
 ///
-/// * `path` - 路径
+/// * `path` - 路径, 例如 `~/.config/app`
 ///
 /// # 示例
 ///
 /// ```
 /// use x_io::path;
+/// use std::path::PathBuf;
 ///
 /// fn main() {
-///     let time = path::get_last_modified_time("./src/path/mod.rs").unwrap();
-///     assert!(time > 0);
+///     let home = path::home_dir().unwrap();
+///     assert_eq!(path::expand_home("~/.config/app").unwrap(), home.join(".config/app"));
+///     assert_eq!(path::expand_home("~").unwrap(), home);
+///     assert_eq!(path::expand_home("./~/app").unwrap(), PathBuf::from("./~/app"));
 /// }
 /// ```
-pub fn get_last_modified_time(path: &str) -> FsIOResult<u128> {
-    match fs::metadata(path) {
-        Ok(metadata) => match metadata.modified() {
-            Ok(time) => match time.duration_since(SystemTime::UNIX_EPOCH) {
-                Ok(duration) => Ok(duration.as_millis()),
-                Err(error) => Err(FsIOError::SystemTimeError(
-                    "Unable to get last modified duration for path.".to_string(),
-                    Some(error),
-                )),
-            },
-            Err(error) => Err(FsIOError::IOError(
-                "Unable to extract modified time for path.".to_string(),
-                Some(error),
+pub fn expand_home<T: AsPath + ?Sized>(path: &T) -> FsIOResult<PathBuf> {
+    let path_obj = path.as_path();
+
+    let mut components = path_obj.components();
+    match components.next() {
+        Some(Component::Normal(name)) if name == "~" => match home_dir() {
+            Some(home) => Ok(home.join(components.as_path())),
+            None => Err(FsIOError::io(
+                Operation::ExpandHome,
+                &path_obj,
+                io::Error::new(io::ErrorKind::NotFound, "home directory not found"),
             )),
         },
-        Err(error) => Err(FsIOError::IOError(
-            "Unable to extract metadata for path.".to_string(),
-            Some(error)),
-        ),
+        _ => Ok(path_obj.to_path_buf()),
+    }
+}
+
+/// 路径中引用的环境变量不存在时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissingVariable {
+    /// 保留未展开的原文
+    #[default]
+    Keep,
+    /// 返回错误
+    Error,
+}
+
+/// 展开路径中的环境变量, 支持unix的 `$VAR`/`${VAR}` 和windows的 `%VAR%` 语法, 不存在的变量保留原文
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     std::env::set_var("X_IO_EXPAND_ENV", "value");
+///     let expanded = path::expand_env("${X_IO_EXPAND_ENV}/data/%X_IO_EXPAND_ENV%/$X_IO_EXPAND_ENV.txt");
+///     assert_eq!(expanded.unwrap(), "value/data/value/value.txt");
+///
+///     let expanded = path::expand_env("$X_IO_MISSING_VARIABLE/data");
+///     assert_eq!(expanded.unwrap(), "$X_IO_MISSING_VARIABLE/data");
+/// }
+/// ```
+pub fn expand_env(path: &str) -> FsIOResult<String> {
+    expand_env_with(path, MissingVariable::Keep)
+}
+
+/// 展开路径中的环境变量, 支持unix的 `$VAR`/`${VAR}` 和windows的 `%VAR%` 语法
+///
+/// # 参数
+///
+/// * `path` - 路径
+/// * `missing` - 变量不存在时的处理方式
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use x_io::path::MissingVariable;
+///
+/// fn main() {
+///     let expanded = path::expand_env_with("%X_IO_MISSING_VARIABLE%/data", MissingVariable::Error);
+///     assert!(expanded.is_err());
+/// }
+/// ```
+pub fn expand_env_with(path: &str, missing: MissingVariable) -> FsIOResult<String> {
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path;
+
+    while let Some(index) = rest.find(['$', '%']) {
+        expanded.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let (name, length) = match parse_variable(rest) {
+            Some(variable) => variable,
+            None => {
+                expanded.push_str(&rest[..1]);
+                rest = &rest[1..];
+                continue;
+            }
+        };
+
+        match env::var_os(name) {
+            Some(value) => expanded.push_str(&value.to_string_lossy()),
+            None => match missing {
+                MissingVariable::Keep => expanded.push_str(&rest[..length]),
+                MissingVariable::Error => {
+                    return Err(FsIOError::Parse(
+                        format!(
+                            "Environment variable: {} used in path: {} is not set.",
+                            name, path
+                        )
+                        .to_string(),
+                        Some(Box::new(env::VarError::NotPresent)),
+                    ))
+                }
+            },
+        }
+        rest = &rest[length..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+/// 解析 `$VAR`, `${VAR}` 或 `%VAR%`, 返回变量名称和引用的长度
+fn parse_variable(text: &str) -> Option<(&str, usize)> {
+    let is_name_char = |value: char| value.is_ascii_alphanumeric() || value == '_';
+
+    if let Some(braced) = text.strip_prefix("${") {
+        let end = braced.find('}')?;
+        let name = &braced[..end];
+        if name.is_empty() || !name.chars().all(is_name_char) {
+            return None;
+        }
+        Some((name, end + 3))
+    } else if let Some(unix) = text.strip_prefix('$') {
+        let end = unix
+            .find(|value: char| !is_name_char(value))
+            .unwrap_or(unix.len());
+        let name = &unix[..end];
+        if name.is_empty() || name.starts_with(|value: char| value.is_ascii_digit()) {
+            return None;
+        }
+        Some((name, end + 1))
+    } else {
+        let windows = text.strip_prefix('%')?;
+        let end = windows.find('%')?;
+        let name = &windows[..end];
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|value| is_name_char(value) || value == '(' || value == ')')
+        {
+            return None;
+        }
+        Some((name, end + 2))
+    }
+}
+
+/// `expand` 应用的展开步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpandStep {
+    /// 展开环境变量(参见 `expand_env`)
+    EnvVariables,
+    /// 展开开头的 `~`(参见 `expand_home`)
+    Home,
+    /// 按文本规范路径(参见 `normalize_lexically`)
+    Normalize,
+}
+
+/// `expand` 的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expanded {
+    /// 展开后的路径
+    pub path: PathBuf,
+    /// 改变了路径的展开步骤(按应用顺序)
+    pub steps: Vec<ExpandStep>,
+}
+
+/// 依次展开环境变量, 开头的 `~` 并按文本规范路径, 返回结果路径和改变了路径的步骤, 不存在的环境变量保留原文
+///
+/// 环境变量先于 `~` 展开, 因此值以 `~` 开头的变量也会被展开为主目录.
+///
+/// # 参数
+///
+/// * `input` - 路径, 例如 `~/$APP/../config`
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use x_io::path::ExpandStep;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     std::env::set_var("X_IO_EXPAND_APP", "app");
+///
+///     let expanded = path::expand("~/.config/$X_IO_EXPAND_APP/../shared").unwrap();
+///     assert_eq!(expanded.path, path::home_dir().unwrap().join(".config/shared"));
+///     assert_eq!(expanded.steps, vec![ExpandStep::EnvVariables, ExpandStep::Home, ExpandStep::Normalize]);
+///
+///     let expanded = path::expand("config/app.toml").unwrap();
+///     assert_eq!(expanded.path, PathBuf::from("config/app.toml"));
+///     assert!(expanded.steps.is_empty());
+/// }
+/// ```
+pub fn expand(input: &str) -> FsIOResult<Expanded> {
+    expand_with(input, MissingVariable::Keep)
+}
+
+/// 依次展开环境变量, 开头的 `~` 并按文本规范路径, 返回结果路径和改变了路径的步骤
+///
+/// # 参数
+///
+/// * `input` - 路径
+/// * `missing` - 环境变量不存在时的处理方式
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use x_io::path::MissingVariable;
+///
+/// fn main() {
+///     let expanded = path::expand_with("$X_IO_MISSING_VARIABLE/config", MissingVariable::Error);
+///     assert!(expanded.is_err());
+/// }
+/// ```
+pub fn expand_with(input: &str, missing: MissingVariable) -> FsIOResult<Expanded> {
+    let mut steps = vec![];
+
+    let with_variables = expand_env_with(input, missing)?;
+    if with_variables != input {
+        steps.push(ExpandStep::EnvVariables);
     }
-}
\ No newline at end of file
+
+    let with_home = expand_home(&with_variables)?;
+    if with_home != Path::new(&with_variables) {
+        steps.push(ExpandStep::Home);
+    }
+
+    let path = normalize_lexically(&with_home);
+    if path != with_home {
+        steps.push(ExpandStep::Normalize);
+    }
+
+    Ok(Expanded { path, steps })
+}
+
+/// 规范提供的路径并且返回字符串路径, 失败时返回fallback函数的结果
+///
+/// # 参数
+///
+/// * `path` - 路径
+/// * `fallback` - 规范失败时调用, 返回默认值
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let value = path::canonicalize_or_else("./missing/file.txt", || "fallback".to_string());
+///     assert_eq!(value, "fallback");
+/// }
+/// ```
+pub fn canonicalize_or_else<T, F>(path: &T, fallback: F) -> String
+where
+    T: AsPath + ?Sized,
+    F: FnOnce() -> String,
+{
+    match normalize_as_string(path) {
+        Ok(value) => value,
+        Err(_) => fallback(),
+    }
+}
+
+/// 规范提供的路径并且返回字符串路径, 失败时返回原始输入
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let value = path::canonicalize_or_input("./missing/../file.txt");
+///     assert_eq!(value, "./missing/../file.txt");
+/// }
+/// ```
+pub fn canonicalize_or_input<T: AsPath + ?Sized>(path: &T) -> String {
+    canonicalize_or_else(path, || FromPath::from_path(path.as_path()))
+}
+
+/// 返回最后一个路径组件(文件名或最后一个目录名)
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use x_io::path::as_path::AsPath;
+/// use std::path::Path;
+///
+/// fn main() {
+///     let basename = path::base_name("./src/path/mod.rs");
+///     assert_eq!(basename.unwrap(), "mod.rs");
+/// }
+/// ```
+pub fn base_name<T: AsPath + ?Sized>(path: &T) -> Option<String> {
+    let path_obj = path.as_path();
+    path_obj
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// 返回最后一个路径组件(文件名或最后一个目录名), 转换为指定类型(`String`, `PathBuf` 或 `OsString`)
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use std::ffi::OsString;
+///
+/// fn main() {
+///     let basename = path::base_name_as::<OsString, _>("./src/path/mod.rs");
+///     assert_eq!(basename.unwrap(), "mod.rs");
+/// }
+/// ```
+pub fn base_name_as<R: FromPath, T: AsPath + ?Sized>(path: &T) -> Option<R> {
+    let path_obj = path.as_path();
+    path_obj
+        .file_name()
+        .map(|name| FromPath::from_path(Path::new(name)))
+}
+
+/// 返回最后一个路径组件(文件名或最后一个目录名), 不会丢失非UTF-8内容
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let basename = path::base_name_os("./src/path/mod.rs");
+///     assert_eq!(basename.unwrap(), "mod.rs");
+/// }
+/// ```
+pub fn base_name_os<T: AsPath + ?Sized>(path: &T) -> Option<OsString> {
+    base_name_as(path)
+}
+
+/// 返回路径的所有组件(根目录, 盘符, `..` 和名称), 非UTF-8内容会被替换
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::components("./src//path/mod.rs"), vec![".", "src", "path", "mod.rs"]);
+///     assert_eq!(path::components("/usr/../lib"), vec!["/", "usr", "..", "lib"]);
+/// }
+/// ```
+pub fn components<T: AsPath + ?Sized>(path: &T) -> Vec<String> {
+    let path_obj = path.as_path();
+    path_obj
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().into_owned())
+        .collect()
+}
+
+/// 返回路径的深度(名称组件的数量, 不包括根目录, `.` 和 `..`)
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::depth("./src/path/mod.rs"), 3);
+///     assert_eq!(path::depth("/"), 0);
+/// }
+/// ```
+pub fn depth<T: AsPath + ?Sized>(path: &T) -> usize {
+    let path_obj = path.as_path();
+    path_obj
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .count()
+}
+
+/// 返回父级路径
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use x_io::path::as_path::AsPath;
+/// use std::path::Path;
+///
+/// fn main() {
+///     let dirname = path::parent_directory("./src/path/mod.rs");
+///     assert_eq!(dirname.unwrap(), "./src/path");
+/// }
+/// ```
+pub fn parent_directory<T: AsPath + ?Sized>(path: &T) -> Option<String> {
+    let path_obj = path.as_path();
+    let directory = path_obj.parent();
+    match directory {
+        Some(directory_path) => {
+            let directory_path_string: String = FromPath::from_path(directory_path);
+            if directory_path_string.is_empty() {
+                None
+            } else {
+                Some(directory_path_string)
+            }
+        }
+        None => None,
+    }
+}
+
+/// 返回父级路径, 转换为指定类型(`String`, `PathBuf` 或 `OsString`)
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let dirname = path::parent_directory_as::<PathBuf, _>("./src/path/mod.rs");
+///     assert_eq!(dirname.unwrap(), PathBuf::from("./src/path"));
+/// }
+/// ```
+pub fn parent_directory_as<R: FromPath, T: AsPath + ?Sized>(path: &T) -> Option<R> {
+    let path_obj = path.as_path();
+    match path_obj.parent() {
+        Some(directory_path) if !directory_path.as_os_str().is_empty() => {
+            Some(FromPath::from_path(directory_path))
+        }
+        _ => None,
+    }
+}
+
+/// 返回父级路径, 不会丢失非UTF-8内容
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let dirname = path::parent_directory_os("./src/path/mod.rs");
+///     assert_eq!(dirname.unwrap(), PathBuf::from("./src/path"));
+/// }
+/// ```
+pub fn parent_directory_os<T: AsPath + ?Sized>(path: &T) -> Option<PathBuf> {
+    parent_directory_as(path)
+}
+
+/// 返回文件扩展名(不包括 `.`), 没有扩展名时返回None
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::extension("./src/path/mod.rs").unwrap(), "rs");
+///     assert!(path::extension("./src/path").is_none());
+/// }
+/// ```
+pub fn extension<T: AsPath + ?Sized>(path: &T) -> Option<String> {
+    let path_obj = path.as_path();
+    path_obj
+        .extension()
+        .map(|extension| extension.to_string_lossy().into_owned())
+}
+
+/// 返回替换(或添加)扩展名后的路径, 扩展名为空时删除扩展名
+///
+/// # 参数
+///
+/// * `path` - 路径
+/// * `extension` - 新的扩展名(可以包括开头的 `.`)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::with_extension("./src/config.toml", "json"), "./src/config.json");
+///     assert_eq!(path::with_extension("./src/config", ".json"), "./src/config.json");
+/// }
+/// ```
+pub fn with_extension<T: AsPath + ?Sized>(path: &T, extension: &str) -> String {
+    let path_obj = path.as_path();
+    let extension = extension.strip_prefix('.').unwrap_or(extension);
+    FromPath::from_path(&path_obj.with_extension(extension))
+}
+
+/// 返回删除扩展名后的路径
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::strip_extension("./src/path/mod.rs"), "./src/path/mod");
+///     assert_eq!(path::strip_extension("./.gitignore"), "./.gitignore");
+/// }
+/// ```
+pub fn strip_extension<T: AsPath + ?Sized>(path: &T) -> String {
+    let path_obj = path.as_path();
+    FromPath::from_path(&path_obj.with_extension(""))
+}
+
+/// 在文件名的扩展名之前插入后缀, 例如 `app.js` 加上 `-min` 得到 `app-min.js`
+///
+/// # 参数
+///
+/// * `path` - 路径
+/// * `suffix` - 后缀
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::add_suffix("./dist/app.js", "-min"), "./dist/app-min.js");
+///     assert_eq!(path::add_suffix("./dist/app", "-min"), "./dist/app-min");
+/// }
+/// ```
+pub fn add_suffix<T: AsPath + ?Sized>(path: &T, suffix: &str) -> String {
+    let path_obj = path.as_path();
+
+    let stem = match path_obj.file_stem() {
+        Some(stem) => stem,
+        None => return FromPath::from_path(path_obj),
+    };
+
+    let mut name = stem.to_os_string();
+    name.push(suffix);
+    if let Some(extension) = path_obj.extension() {
+        name.push(".");
+        name.push(extension);
+    }
+
+    FromPath::from_path(&path_obj.with_file_name(name))
+}
+
+/// 默认识别的复合扩展名
+pub const COMPOUND_EXTENSIONS: [&str; 7] = [
+    "tar.gz", "tar.bz2", "tar.xz", "tar.zst", "tar.lz", "tar.lzma", "tar.z",
+];
+
+/// 返回文件名去掉扩展名后的部分, 复合扩展名(例如 `archive.tar.gz` 的 `tar.gz`)作为一个整体去掉
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::file_stem("./dist/archive.tar.gz").unwrap(), "archive");
+///     assert_eq!(path::file_stem("./dist/app.min.js").unwrap(), "app.min");
+/// }
+/// ```
+pub fn file_stem<T: AsPath + ?Sized>(path: &T) -> Option<String> {
+    file_stem_with(path, &COMPOUND_EXTENSIONS)
+}
+
+/// 返回文件名去掉扩展名后的部分, 使用指定的复合扩展名列表
+///
+/// # 参数
+///
+/// * `path` - 路径
+/// * `compound_extensions` - 复合扩展名列表(不包括开头的 `.`, 不区分大小写)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::file_stem_with("./dist/app.min.js", &["min.js"]).unwrap(), "app");
+/// }
+/// ```
+pub fn file_stem_with<T: AsPath + ?Sized>(
+    path: &T,
+    compound_extensions: &[&str],
+) -> Option<String> {
+    split_file_name(path.as_path(), compound_extensions).map(|(stem, _)| stem)
+}
+
+/// 返回完整的扩展名(不包括开头的 `.`), 复合扩展名(例如 `tar.gz`)作为一个整体返回, 没有扩展名时返回None
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::full_extension("./dist/archive.tar.gz").unwrap(), "tar.gz");
+///     assert_eq!(path::full_extension("./dist/app.min.js").unwrap(), "js");
+///     assert!(path::full_extension("./dist/LICENSE").is_none());
+/// }
+/// ```
+pub fn full_extension<T: AsPath + ?Sized>(path: &T) -> Option<String> {
+    full_extension_with(path, &COMPOUND_EXTENSIONS)
+}
+
+/// 返回完整的扩展名(不包括开头的 `.`), 使用指定的复合扩展名列表
+///
+/// # 参数
+///
+/// * `path` - 路径
+/// * `compound_extensions` - 复合扩展名列表(不包括开头的 `.`, 不区分大小写)
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::full_extension_with("./dist/app.min.js", &["min.js"]).unwrap(), "min.js");
+/// }
+/// ```
+pub fn full_extension_with<T: AsPath + ?Sized>(
+    path: &T,
+    compound_extensions: &[&str],
+) -> Option<String> {
+    split_file_name(path.as_path(), compound_extensions).and_then(|(_, extension)| extension)
+}
+
+/// 将文件名拆分为(主干, 扩展名)
+fn split_file_name(path: &Path, compound_extensions: &[&str]) -> Option<(String, Option<String>)> {
+    let name = path.file_name()?.to_string_lossy().into_owned();
+    let lower_name = name.to_ascii_lowercase();
+
+    for compound in compound_extensions {
+        let compound = compound.strip_prefix('.').unwrap_or(compound);
+        let suffix = format!(".{}", compound.to_ascii_lowercase());
+        if lower_name.len() > suffix.len() && lower_name.ends_with(&suffix) {
+            let index = name.len() - suffix.len();
+            if name.is_char_boundary(index) {
+                return Some((
+                    name[..index].to_string(),
+                    Some(name[index + 1..].to_string()),
+                ));
+            }
+        }
+    }
+
+    let stem = Path::new(&name).file_stem()?.to_string_lossy().into_owned();
+    let extension = Path::new(&name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().into_owned());
+    Some((stem, extension))
+}
+
+/// 返回使用 `/` 作为分隔符的字符串路径(windows上替换 `\\`), 用于清单文件, URL和归档条目
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let path_buf = PathBuf::from("src").join("path").join("mod.rs");
+///     assert_eq!(path::to_slash(&path_buf), "src/path/mod.rs");
+/// }
+/// ```
+pub fn to_slash<T: AsPath + ?Sized>(path: &T) -> String {
+    let path_string: String = FromPath::from_path(path.as_path());
+    if MAIN_SEPARATOR == '/' {
+        path_string
+    } else {
+        path_string.replace(MAIN_SEPARATOR, "/")
+    }
+}
+
+/// 将使用 `/` 作为分隔符的字符串路径转换为当前平台的路径
+///
+/// # 参数
+///
+/// * `path` - 使用 `/` 分隔的路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let path_buf = path::from_slash("src/path/mod.rs");
+///     assert_eq!(path_buf, PathBuf::from("src").join("path").join("mod.rs"));
+/// }
+/// ```
+pub fn from_slash(path: &str) -> PathBuf {
+    if MAIN_SEPARATOR == '/' {
+        PathBuf::from(path)
+    } else {
+        PathBuf::from(path.replace('/', MAIN_SEPARATOR_STR))
+    }
+}
+
+/// 返回两个路径是否相同(逐个组件比较, 忽略大小写), 不访问文件系统
+///
+/// # 参数
+///
+/// * `path1` - 第一个路径
+/// * `path2` - 第二个路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert!(path::eq_ignore_case("./Config/App.TOML", "./config//app.toml"));
+///     assert!(!path::eq_ignore_case("./config/app.toml", "./config/app.json"));
+/// }
+/// ```
+pub fn eq_ignore_case<S: AsPath + ?Sized, T: AsPath + ?Sized>(path1: &S, path2: &T) -> bool {
+    let mut components1 = path1.as_path().components();
+    let mut components2 = path2.as_path().components();
+
+    loop {
+        match (components1.next(), components2.next()) {
+            (None, None) => return true,
+            (Some(component1), Some(component2)) => {
+                if !name_eq_ignore_case(component1.as_os_str(), component2.as_os_str()) {
+                    return false;
+                }
+            }
+            _ => return false,
+        }
+    }
+}
+
+/// 比较两个名称是否相同(忽略大小写)
+pub(crate) fn name_eq_ignore_case(name1: &OsStr, name2: &OsStr) -> bool {
+    if name1 == name2 {
+        return true;
+    }
+
+    match (name1.to_str(), name2.to_str()) {
+        (Some(value1), Some(value2)) => value1.to_lowercase() == value2.to_lowercase(),
+        _ => false,
+    }
+}
+
+/// 返回最后修改时间(单位为毫秒)
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let time = path::get_last_modified_time("./src/path/mod.rs").unwrap();
+///     assert!(time > 0);
+/// }
+/// ```
+pub fn get_last_modified_time(path: &str) -> FsIOResult<u128> {
+    match fs::metadata(path) {
+        Ok(metadata) => match metadata.modified() {
+            Ok(time) => match time.duration_since(SystemTime::UNIX_EPOCH) {
+                Ok(duration) => Ok(duration.as_millis()),
+                Err(error) => Err(FsIOError::SystemTimeError(
+                    "Unable to get last modified duration for path.".to_string(),
+                    Some(error),
+                )),
+            },
+            Err(error) => Err(FsIOError::io(Operation::Metadata, path, error)),
+        },
+        Err(error) => Err(FsIOError::io(Operation::Metadata, path, error)),
+    }
+}
+
+/// 清理不可信的路径(例如上传的文件名), 返回可以安全拼接到根目录下的相对路径(使用 `/` 分隔)
+///
+/// 删除 `.` 和 `..` 组件, 盘符, 开头的分隔符, 非法字符(`<>:"|?*` 和控制字符)以及结尾的点和空格,
+/// Windows保留名称(CON, NUL, ...)会加上 `_` 前缀, 全部被删除时返回空字符串.
+///
+/// # 参数
+///
+/// * `untrusted` - 不可信的路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert_eq!(path::sanitize("../../etc/passwd"), "etc/passwd");
+///     assert_eq!(path::sanitize("C:\\Windows\\..\\system32\\a?b.txt"), "Windows/system32/ab.txt");
+///     assert_eq!(path::sanitize("/uploads/con.txt"), "uploads/_con.txt");
+///     assert_eq!(path::sanitize(".."), "");
+/// }
+/// ```
+pub fn sanitize(untrusted: &str) -> String {
+    let mut names = vec![];
+
+    for (index, component) in untrusted.split(['/', '\\']).enumerate() {
+        let mut name = component;
+        if index == 0 {
+            name = strip_drive_letter(name);
+        }
+
+        let mut cleaned: String = name
+            .chars()
+            .filter(|value| !value.is_control() && !"<>:\"|?*".contains(*value))
+            .collect();
+        cleaned = cleaned.trim_end_matches(['.', ' ']).to_string();
+
+        if cleaned.is_empty() {
+            continue;
+        }
+
+        let stem = cleaned.split('.').next().unwrap_or_default().trim_end();
+        if dunce::RESERVED_NAMES
+            .iter()
+            .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+        {
+            cleaned.insert(0, '_');
+        }
+
+        names.push(cleaned);
+    }
+
+    names.join("/")
+}
+
+fn strip_drive_letter(name: &str) -> &str {
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), Some(':')) if letter.is_ascii_alphabetic() => &name[2..],
+        _ => name,
+    }
+}
+
+/// 将相对路径拼接到根目录, 拒绝绝对路径和 `..` 组件
+pub(crate) fn join_relative(root: &Path, relative: &Path) -> FsIOResult<PathBuf> {
+    let mut path = root.to_path_buf();
+
+    for component in relative.components() {
+        match component {
+            Component::Normal(value) => path.push(value),
+            Component::CurDir => {}
+            _ => {
+                return Err(FsIOError::io(
+                    Operation::Join,
+                    relative,
+                    io::Error::new(io::ErrorKind::InvalidData, "unsafe relative path"),
+                ))
+            }
+        }
+    }
+
+    Ok(path)
+}
+
+static SIBLING_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// 返回同目录下唯一的隐藏临时路径, 例如 `.name.backup-<pid>-<n>`
+pub(crate) fn unique_sibling(path: &Path, tag: &str) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+    name.push(format!(
+        ".{}-{}-{}",
+        tag,
+        process::id(),
+        SIBLING_COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    path.with_file_name(name)
+}
@@ -0,0 +1,205 @@
+//! # symlink
+//!
+//! Symlink aware path resolution.
+//!
+use std::collections::VecDeque;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::result::FsIOResult;
+
+/// 解析路径时最多跟随的符号链接数量(与Linux的MAXSYMLINKS一致)
+const MAX_SYMLINKS: usize = 40;
+
+/// 逐个组件解析路径中的所有符号链接并返回最终的绝对路径, 不存在的组件按原样拼接
+///
+/// 符号链接形成循环(或超过40层)时返回 `FsIOError::SymlinkLoop`.
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+///
+/// fn main() {
+///     let file_path = "./target/__test/path_test/resolve_symlinks/file.txt";
+///     file::write_text_file(file_path, "some content").unwrap();
+///
+///     #[cfg(unix)]
+///     {
+///         let link_path = "./target/__test/path_test/resolve_symlinks/link.txt";
+///         let _ = std::fs::remove_file(link_path);
+///         std::os::unix::fs::symlink("file.txt", link_path).unwrap();
+///
+///         let target = path::resolve_symlinks(link_path).unwrap();
+///         assert_eq!(target, std::fs::canonicalize(file_path).unwrap());
+///     }
+/// }
+/// ```
+pub fn resolve_symlinks<T: AsPath + ?Sized>(path: &T) -> FsIOResult<PathBuf> {
+    resolve(path.as_path()).map(|(target, _)| target)
+}
+
+/// 返回解析路径时跟随的符号链接数量, 没有符号链接时返回0
+///
+/// 符号链接形成循环(或超过40层)时返回 `FsIOError::SymlinkLoop`.
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, path};
+/// use x_io::error::FsIOError;
+///
+/// fn main() {
+///     let file_path = "./target/__test/path_test/symlink_depth/file.txt";
+///     file::write_text_file(file_path, "some content").unwrap();
+///     assert_eq!(path::symlink_depth(file_path).unwrap(), 0);
+///
+///     #[cfg(unix)]
+///     {
+///         let link1_path = "./target/__test/path_test/symlink_depth/link1.txt";
+///         let link2_path = "./target/__test/path_test/symlink_depth/link2.txt";
+///         let _ = std::fs::remove_file(link1_path);
+///         let _ = std::fs::remove_file(link2_path);
+///         std::os::unix::fs::symlink("file.txt", link1_path).unwrap();
+///         std::os::unix::fs::symlink("link1.txt", link2_path).unwrap();
+///         assert_eq!(path::symlink_depth(link2_path).unwrap(), 2);
+///
+///         let loop1_path = "./target/__test/path_test/symlink_depth/loop1";
+///         let loop2_path = "./target/__test/path_test/symlink_depth/loop2";
+///         let _ = std::fs::remove_file(loop1_path);
+///         let _ = std::fs::remove_file(loop2_path);
+///         std::os::unix::fs::symlink("loop2", loop1_path).unwrap();
+///         std::os::unix::fs::symlink("loop1", loop2_path).unwrap();
+///         let result = path::symlink_depth(loop1_path);
+///         assert!(matches!(result, Err(FsIOError::SymlinkLoop(_))));
+///     }
+/// }
+/// ```
+pub fn symlink_depth<T: AsPath + ?Sized>(path: &T) -> FsIOResult<usize> {
+    resolve(path.as_path()).map(|(_, depth)| depth)
+}
+
+fn resolve(path: &Path) -> FsIOResult<(PathBuf, usize)> {
+    let absolute_path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        match env::current_dir() {
+            Ok(current_dir) => current_dir.join(path),
+            Err(error) => {
+                return Err(FsIOError::io(Operation::Canonicalize, path, error))
+            }
+        }
+    };
+
+    let mut pending: VecDeque<OsString> = components_of(&absolute_path);
+    let mut resolved = PathBuf::new();
+    let mut depth = 0;
+
+    while let Some(name) = pending.pop_front() {
+        let component_path = PathBuf::from(&name);
+        match component_path.components().next() {
+            Some(Component::Prefix(_)) | Some(Component::RootDir) => {
+                resolved.push(&component_path);
+                continue;
+            }
+            Some(Component::CurDir) | None => continue,
+            Some(Component::ParentDir) => {
+                resolved.pop();
+                continue;
+            }
+            Some(Component::Normal(_)) => {}
+        }
+
+        let candidate = resolved.join(&name);
+        let is_symlink = match fs::symlink_metadata(&candidate) {
+            Ok(metadata) => metadata.file_type().is_symlink(),
+            Err(_) => false,
+        };
+        if !is_symlink {
+            resolved = candidate;
+            continue;
+        }
+
+        depth += 1;
+        if depth > MAX_SYMLINKS {
+            return Err(FsIOError::SymlinkLoop(
+                format!("Too many levels of symbolic links: {:?}", path).to_string(),
+            ));
+        }
+
+        let target = match fs::read_link(&candidate) {
+            Ok(target) => target,
+            Err(error) => return Err(FsIOError::io(Operation::ReadLink, &candidate, error)),
+        };
+
+        if target.is_absolute() {
+            resolved = PathBuf::new();
+        }
+        for component in components_of(&target).into_iter().rev() {
+            pending.push_front(component);
+        }
+    }
+
+    Ok((resolved, depth))
+}
+
+fn components_of(path: &Path) -> VecDeque<OsString> {
+    path.components()
+        .map(|component| component.as_os_str().to_os_string())
+        .collect()
+}
+
+/// 创建指向 `target` 的符号链接 `link`, `target` 按原样保存(相对路径相对于链接所在的目录)
+///
+/// Windows需要区分目录链接和文件链接, 根据 `target` 指向的路径类型决定.
+#[cfg(unix)]
+pub(crate) fn create_symlink(target: &Path, link: &Path) -> FsIOResult<()> {
+    match std::os::unix::fs::symlink(target, link) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::Create, &link, error)),
+    }
+}
+
+/// 创建指向 `target` 的符号链接 `link`, `target` 按原样保存(相对路径相对于链接所在的目录)
+///
+/// Windows需要区分目录链接和文件链接, 根据 `target` 指向的路径类型决定.
+#[cfg(windows)]
+pub(crate) fn create_symlink(target: &Path, link: &Path) -> FsIOResult<()> {
+    let resolved_target = match link.parent() {
+        Some(parent) => parent.join(target),
+        None => target.to_path_buf(),
+    };
+    let result = if resolved_target.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    };
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(error) => Err(FsIOError::io(Operation::Create, &link, error)),
+    }
+}
+
+/// 创建指向 `target` 的符号链接 `link`, `target` 按原样保存(相对路径相对于链接所在的目录)
+#[cfg(not(any(unix, windows)))]
+pub(crate) fn create_symlink(target: &Path, link: &Path) -> FsIOResult<()> {
+    let _ = target;
+    Err(FsIOError::io(
+        Operation::Create,
+        &link,
+        std::io::Error::from(std::io::ErrorKind::Unsupported),
+    ))
+}
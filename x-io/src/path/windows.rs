@@ -0,0 +1,139 @@
+//! # windows
+//!
+//! Windows long-path (`\\?\`) and UNC path helpers, no-ops on other platforms.
+//!
+use std::env;
+use std::ffi::OsString;
+use std::path::{Component, PathBuf, Prefix};
+
+use crate::dunce;
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::path::normalize_lexically;
+use crate::result::FsIOResult;
+
+/// 尽可能将windows的扩展长度路径(`\\?\C:\foo`)转换为普通路径(`C:\foo`), 其他平台上原样返回
+///
+/// 路径包含保留名称或超过MAX_PATH等无法安全转换的情况会原样返回, 不访问文件系统.
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+/// use std::path::PathBuf;
+///
+/// fn main() {
+///     let simplified = path::simplify_windows("./src/path/mod.rs");
+///     assert_eq!(simplified, PathBuf::from("./src/path/mod.rs"));
+/// }
+/// ```
+pub fn simplify_windows<T: AsPath + ?Sized>(path: &T) -> PathBuf {
+    dunce::simplified(path.as_path()).to_path_buf()
+}
+
+/// 返回windows的扩展长度路径(`\\?\C:\foo` 或 `\\?\UNC\server\share\foo`), 用于超过MAX_PATH(260)的路径,
+/// 其他平台上原样返回
+///
+/// 相对路径以当前目录为基准, 扩展长度路径不会被系统规范, 所以会先按文本规范路径(参见 `normalize_lexically`).
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     let extended = path::to_extended_length("./src/path/mod.rs").unwrap();
+///     if cfg!(windows) {
+///         assert!(extended.to_string_lossy().starts_with(r"\\?\"));
+///     } else {
+///         assert_eq!(extended, std::path::PathBuf::from("./src/path/mod.rs"));
+///     }
+/// }
+/// ```
+pub fn to_extended_length<T: AsPath + ?Sized>(path: &T) -> FsIOResult<PathBuf> {
+    let path_obj = path.as_path();
+
+    if !cfg!(windows) {
+        return Ok(path_obj.to_path_buf());
+    }
+
+    let absolute_path = if path_obj.is_absolute() {
+        path_obj.to_path_buf()
+    } else {
+        match env::current_dir() {
+            Ok(current_dir) => current_dir.join(path_obj),
+            Err(error) => {
+                return Err(FsIOError::io(Operation::Canonicalize, path_obj, error))
+            }
+        }
+    };
+
+    let mut components = absolute_path.components();
+    let prefix = match components.next() {
+        Some(Component::Prefix(prefix)) => prefix.kind(),
+        _ => return Ok(absolute_path),
+    };
+
+    let mut extended = match prefix {
+        Prefix::Verbatim(_) | Prefix::VerbatimUNC(..) | Prefix::VerbatimDisk(_) => {
+            return Ok(absolute_path)
+        }
+        Prefix::DeviceNS(_) => return Ok(absolute_path),
+        Prefix::UNC(server, share) => {
+            let mut extended = OsString::from(r"\\?\UNC\");
+            extended.push(server);
+            extended.push(r"\");
+            extended.push(share);
+            extended
+        }
+        Prefix::Disk(letter) => OsString::from(format!(r"\\?\{}:", letter as char)),
+    };
+
+    // verbatim paths are passed to the system as is, so the separators must be `\`
+    let mut has_names = false;
+    for component in normalize_lexically(&components.as_path()).components() {
+        if let Component::Normal(name) = component {
+            extended.push(r"\");
+            extended.push(name);
+            has_names = true;
+        }
+    }
+    if !has_names {
+        extended.push(r"\");
+    }
+
+    Ok(PathBuf::from(extended))
+}
+
+/// 返回路径是否为UNC网络路径(`\\server\share` 或 `\\?\UNC\server\share`), 其他平台上总是返回false
+///
+/// # 参数
+///
+/// * `path` - 路径
+///
+/// # 示例
+///
+/// ```
+/// use x_io::path;
+///
+/// fn main() {
+///     assert!(!path::is_unc("./src/path/mod.rs"));
+///     assert_eq!(path::is_unc(r"\\server\share\file.txt"), cfg!(windows));
+/// }
+/// ```
+pub fn is_unc<T: AsPath + ?Sized>(path: &T) -> bool {
+    match path.as_path().components().next() {
+        Some(Component::Prefix(prefix)) => {
+            matches!(prefix.kind(), Prefix::UNC(..) | Prefix::VerbatimUNC(..))
+        }
+        _ => false,
+    }
+}
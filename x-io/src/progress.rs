@@ -0,0 +1,184 @@
+//! # progress
+//!
+//! Progress events emitted by file system operations, for progress bars and structured logs.
+//!
+use std::fmt;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use crate::filesystem::{FileSystem, OsFs};
+use crate::result::FsIOResult;
+
+/// 操作完成后发出的进度事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// 文件已复制(源路径, 目标路径)
+    FileCopied(PathBuf, PathBuf),
+    /// 已写入字节(路径, 字节数), 写入和追加文件, 以及 `directory::copy` 和解压归档写入每个文件后发出(`copy_file` 只发出 `FileCopied`)
+    BytesWritten(PathBuf, u64),
+    /// 目录已创建
+    DirCreated(PathBuf),
+    /// 文件已删除
+    FileDeleted(PathBuf),
+    /// 目录已删除
+    DirDeleted(PathBuf),
+    /// 文件或目录已移动(源路径, 目标路径)
+    Renamed(PathBuf, PathBuf),
+}
+
+impl Display for ProgressEvent {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Self::FileCopied(src, dst) => write!(formatter, "copied {:?} to {:?}", src, dst),
+            Self::BytesWritten(path, size) => {
+                write!(formatter, "wrote {} bytes to {:?}", size, path)
+            }
+            Self::DirCreated(path) => write!(formatter, "created directory {:?}", path),
+            Self::FileDeleted(path) => write!(formatter, "deleted file {:?}", path),
+            Self::DirDeleted(path) => write!(formatter, "deleted directory {:?}", path),
+            Self::Renamed(src, dst) => write!(formatter, "renamed {:?} to {:?}", src, dst),
+        }
+    }
+}
+
+/// 进度事件回调
+pub type ProgressCallback = Box<dyn Fn(&ProgressEvent) + Send + Sync>;
+
+/// 观察文件系统, 修改操作成功后将进度事件发送给回调, 所有操作都使用内部文件系统
+///
+/// # 示例
+///
+/// ```
+/// use x_io::filesystem::{FileSystem, OsFs};
+/// use x_io::progress::{ObservedFs, ProgressEvent};
+/// use std::path::{Path, PathBuf};
+/// use std::sync::{Arc, Mutex};
+///
+/// fn main() {
+///     let events = Arc::new(Mutex::new(vec![]));
+///     let recorded = events.clone();
+///     let fs = ObservedFs::new(OsFs, move |event: &ProgressEvent| {
+///         recorded.lock().unwrap().push(event.clone());
+///     });
+///
+///     let file_path = Path::new("./target/__test/progress_test/observed_fs/file.txt");
+///     fs.write_text_file(file_path, "some content").unwrap();
+///
+///     assert_eq!(
+///         *events.lock().unwrap(),
+///         vec![ProgressEvent::BytesWritten(PathBuf::from(file_path), 12)]
+///     );
+/// }
+/// ```
+pub struct ObservedFs<F: FileSystem = OsFs> {
+    inner: F,
+    callback: ProgressCallback,
+}
+
+impl<F: FileSystem> ObservedFs<F> {
+    /// 创建观察文件系统
+    ///
+    /// # 参数
+    ///
+    /// * `inner` - 执行操作的文件系统
+    /// * `callback` - 进度事件回调
+    pub fn new<C>(inner: F, callback: C) -> ObservedFs<F>
+    where
+        C: Fn(&ProgressEvent) + Send + Sync + 'static,
+    {
+        ObservedFs {
+            inner,
+            callback: Box::new(callback),
+        }
+    }
+
+    /// 返回内部文件系统
+    pub fn inner(&self) -> &F {
+        &self.inner
+    }
+
+    fn emit(&self, result: FsIOResult<()>, event: ProgressEvent) -> FsIOResult<()> {
+        if result.is_ok() {
+            (self.callback)(&event);
+        }
+        result
+    }
+}
+
+impl<F: FileSystem + fmt::Debug> fmt::Debug for ObservedFs<F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter
+            .debug_struct("ObservedFs")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<F: FileSystem> FileSystem for ObservedFs<F> {
+    fn read_file(&self, path: &Path) -> FsIOResult<Vec<u8>> {
+        self.inner.read_file(path)
+    }
+
+    fn write_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        let result = self.inner.write_file(path, data);
+        self.emit(
+            result,
+            ProgressEvent::BytesWritten(path.to_path_buf(), data.len() as u64),
+        )
+    }
+
+    fn append_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        let result = self.inner.append_file(path, data);
+        self.emit(
+            result,
+            ProgressEvent::BytesWritten(path.to_path_buf(), data.len() as u64),
+        )
+    }
+
+    fn delete_file(&self, path: &Path) -> FsIOResult<()> {
+        let result = self.inner.delete_file(path);
+        self.emit(result, ProgressEvent::FileDeleted(path.to_path_buf()))
+    }
+
+    fn create_directory(&self, path: &Path) -> FsIOResult<()> {
+        let result = self.inner.create_directory(path);
+        self.emit(result, ProgressEvent::DirCreated(path.to_path_buf()))
+    }
+
+    fn delete_directory(&self, path: &Path) -> FsIOResult<()> {
+        let result = self.inner.delete_directory(path);
+        self.emit(result, ProgressEvent::DirDeleted(path.to_path_buf()))
+    }
+
+    fn copy_file(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        let result = self.inner.copy_file(src, dst);
+        self.emit(
+            result,
+            ProgressEvent::FileCopied(src.to_path_buf(), dst.to_path_buf()),
+        )
+    }
+
+    fn rename(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        let result = self.inner.rename(src, dst);
+        self.emit(
+            result,
+            ProgressEvent::Renamed(src.to_path_buf(), dst.to_path_buf()),
+        )
+    }
+
+    fn list_directory(&self, path: &Path) -> FsIOResult<Vec<PathBuf>> {
+        self.inner.list_directory(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.inner.exists(path)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.inner.is_file(path)
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.inner.is_dir(path)
+    }
+}
@@ -3,6 +3,146 @@
 //! Common types.
 //!
 use crate::error::FsIOError;
+use crate::path::as_path::AsPath;
 
 /// Result aliasing for project-wide error type.
-pub type FsIOResult<T> = Result<T, FsIOError>;
\ No newline at end of file
+pub type FsIOResult<T> = Result<T, FsIOError>;
+
+/// `FsIOResult`(以及 `io::Result`)的错误处理扩展
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::result::{FsIOResult, ResultExt};
+///
+/// fn export_report() -> FsIOResult<()> {
+///     file::write_text_file("./target/__test/result_test/result_ext/report.txt", "report")
+///         .with_context(|| "while exporting report")
+/// }
+///
+/// fn main() {
+///     export_report().unwrap();
+///
+///     let text = file::read_text_file("./target/__test/result_test/result_ext/missing.txt")
+///         .ignore_not_found()
+///         .unwrap();
+///     assert!(text.is_none());
+/// }
+/// ```
+pub trait ResultExt<T> {
+    /// 为路径未知的错误(例如由 `io::Error` 转换的错误)设置路径
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 路径
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::result::ResultExt;
+    /// use std::fs::File;
+    ///
+    /// fn main() {
+    ///     let path = "./target/__test/result_test/with_path/missing.txt";
+    ///     let error = File::open(path).with_path(path).unwrap_err();
+    ///     assert!(error.is_not_found());
+    ///     assert!(error.to_string().contains("missing.txt"));
+    /// }
+    /// ```
+    fn with_path<P: AsPath + ?Sized>(self, path: &P) -> FsIOResult<T>;
+
+    /// 为错误添加上下文信息, 仅在出错时调用 `context`
+    ///
+    /// # 参数
+    ///
+    /// * `context` - 返回上下文信息的函数
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::file;
+    /// use x_io::error::FsIOError;
+    /// use x_io::result::ResultExt;
+    ///
+    /// fn main() {
+    ///     let error = file::read_text_file("./target/__test/result_test/with_context/missing.txt")
+    ///         .with_context(|| "while exporting report")
+    ///         .unwrap_err();
+    ///     assert!(error.to_string().starts_with("while exporting report\n"));
+    ///     assert!(error.is_not_found());
+    ///     assert!(matches!(error, FsIOError::Context(_, _)));
+    /// }
+    /// ```
+    fn with_context<S, F>(self, context: F) -> FsIOResult<T>
+    where
+        S: Into<String>,
+        F: FnOnce() -> S;
+
+    /// 路径不存在时返回 `Ok(None)`, 成功时返回 `Ok(Some(value))`, 其他错误原样返回
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::file;
+    /// use x_io::result::ResultExt;
+    ///
+    /// fn main() {
+    ///     let result = file::delete("./target/__test/result_test/ignore_not_found/missing.txt")
+    ///         .ignore_not_found();
+    ///     assert!(result.is_ok());
+    ///
+    ///     let text = file::read_text_file("./Cargo.toml").ignore_not_found().unwrap();
+    ///     assert!(text.is_some());
+    /// }
+    /// ```
+    fn ignore_not_found(self) -> FsIOResult<Option<T>>;
+}
+
+impl<T, E> ResultExt<T> for Result<T, E>
+where
+    E: Into<FsIOError>,
+{
+    fn with_path<P: AsPath + ?Sized>(self, path: &P) -> FsIOResult<T> {
+        self.map_err(|error| match error.into() {
+            FsIOError::Io {
+                operation,
+                path: error_path,
+                source,
+            } => {
+                if error_path.as_os_str().is_empty() {
+                    FsIOError::io(operation, path, source)
+                } else {
+                    FsIOError::Io {
+                        operation,
+                        path: error_path,
+                        source,
+                    }
+                }
+            }
+            error => error,
+        })
+    }
+
+    fn with_context<S, F>(self, context: F) -> FsIOResult<T>
+    where
+        S: Into<String>,
+        F: FnOnce() -> S,
+    {
+        self.map_err(|error| FsIOError::Context(context().into(), Box::new(error.into())))
+    }
+
+    fn ignore_not_found(self) -> FsIOResult<Option<T>> {
+        match self {
+            Ok(value) => Ok(Some(value)),
+            Err(error) => {
+                let error = error.into();
+                if error.is_not_found() {
+                    Ok(None)
+                } else {
+                    Err(error)
+                }
+            }
+        }
+    }
+}
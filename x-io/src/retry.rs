@@ -0,0 +1,161 @@
+//! # retry
+//!
+//! Retrying of file system operations that failed with transient errors.
+//!
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+use crate::error::FsIOError;
+use crate::result::FsIOResult;
+
+/// windows上的 `ERROR_SHARING_VIOLATION`
+const ERROR_SHARING_VIOLATION: i32 = 32;
+/// windows上的 `ERROR_LOCK_VIOLATION`
+const ERROR_LOCK_VIOLATION: i32 = 33;
+
+/// 重试策略
+///
+/// # 示例
+///
+/// ```
+/// use x_io::retry::RetryPolicy;
+/// use std::time::Duration;
+///
+/// fn main() {
+///     let policy = RetryPolicy::default();
+///     assert_eq!(policy.attempts, 3);
+///
+///     let policy = RetryPolicy { attempts: 5, backoff: Duration::from_millis(20) };
+///     assert_eq!(policy.backoff, Duration::from_millis(20));
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 最多尝试次数(包括第一次, 0按1处理)
+    pub attempts: u32,
+    /// 第一次重试前的等待时间, 之后每次重试翻倍
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            attempts: 3,
+            backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+/// 执行操作, 失败并且错误可重试(参见 `is_retryable`)时按策略等待后重试, 返回最后一次的结果
+///
+/// # 参数
+///
+/// * `policy` - 重试策略
+/// * `operation` - 要执行的操作
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::retry::{self, RetryPolicy};
+/// use std::io;
+/// use std::time::Duration;
+///
+/// fn main() {
+///     let policy = RetryPolicy { attempts: 3, backoff: Duration::from_millis(10) };
+///
+///     let result = retry::with_retry(policy, || {
+///         file::write_text_file("./target/__test/retry_test/with_retry/file.txt", "some content")
+///     });
+///     assert!(result.is_ok());
+///
+///     let mut calls = 0;
+///     let result: Result<(), _> = retry::with_retry(policy, || {
+///         calls += 1;
+///         Err(io::Error::from(io::ErrorKind::Interrupted).into())
+///     });
+///     assert!(result.is_err());
+///     assert_eq!(calls, 3);
+///
+///     let mut calls = 0;
+///     let result = retry::with_retry(policy, || {
+///         calls += 1;
+///         file::read_text_file("./target/__test/retry_test/with_retry/missing.txt")
+///     });
+///     assert!(result.unwrap_err().is_not_found());
+///     assert_eq!(calls, 1);
+/// }
+/// ```
+pub fn with_retry<T, F>(policy: RetryPolicy, mut operation: F) -> FsIOResult<T>
+where
+    F: FnMut() -> FsIOResult<T>,
+{
+    let attempts = policy.attempts.max(1);
+    let mut backoff = policy.backoff;
+    let mut attempt = 1;
+
+    loop {
+        match operation() {
+            Ok(value) => return Ok(value),
+            Err(error) => {
+                if attempt >= attempts || !is_retryable(&error) {
+                    return Err(error);
+                }
+            }
+        }
+
+        thread::sleep(backoff);
+        backoff = backoff.saturating_mul(2);
+        attempt += 1;
+    }
+}
+
+/// 返回错误是否为暂时性错误(可重试)
+///
+/// 包括 `Interrupted`, `WouldBlock`, `TimedOut` 以及windows上的共享冲突和锁定冲突
+/// (通常由杀毒软件或索引服务暂时打开文件导致).
+///
+/// # 参数
+///
+/// * `error` - 错误
+///
+/// # 示例
+///
+/// ```
+/// use x_io::error::FsIOError;
+/// use x_io::retry;
+/// use std::io;
+///
+/// fn main() {
+///     let error: FsIOError = io::Error::from(io::ErrorKind::WouldBlock).into();
+///     assert!(retry::is_retryable(&error));
+///
+///     let error: FsIOError = io::Error::from(io::ErrorKind::NotFound).into();
+///     assert!(!retry::is_retryable(&error));
+/// }
+/// ```
+pub fn is_retryable(error: &FsIOError) -> bool {
+    match error.kind() {
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => {
+            return true;
+        }
+        _ => {}
+    }
+
+    cfg!(windows)
+        && io_source(error)
+            .and_then(|source| source.raw_os_error())
+            .is_some_and(|code| code == ERROR_SHARING_VIOLATION || code == ERROR_LOCK_VIOLATION)
+}
+
+/// 返回错误包含的io::Error
+fn io_source(error: &FsIOError) -> Option<&io::Error> {
+    match error {
+        FsIOError::Io { source, .. } => Some(source),
+        FsIOError::IOError(_, source) => source.as_ref(),
+        FsIOError::NotFound(_, source) | FsIOError::PermissionDenied(_, source) => source.as_ref(),
+        FsIOError::Context(_, error) => io_source(error),
+        _ => None,
+    }
+}
@@ -0,0 +1,175 @@
+//! # safe
+//!
+//! File system access confined to a root directory.
+//!
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::dunce;
+use crate::error::{FsIOError, Operation};
+use crate::filesystem::{FileSystem, OsFs};
+use crate::path::as_path::AsPath;
+use crate::path::resolve_jailed;
+use crate::result::FsIOResult;
+
+/// 限制在根目录内的文件系统, 所有路径都相对于根目录
+///
+/// 路径按以下规则解析, 解析结果在根目录之外时返回 `FsIOError::PermissionDenied`:
+///
+/// * 绝对路径被拒绝
+/// * `..` 按字面移除上一级组件, 超出根目录时被拒绝
+/// * 解析路径中的所有符号链接, 指向根目录之外的链接被拒绝(删除和移动不跟随最后一级的符号链接)
+///
+/// 检查和操作之间并发创建的符号链接不在防护范围内.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{directory, file};
+/// use x_io::filesystem::FileSystem;
+/// use x_io::safe::RootedFs;
+/// use std::path::{Path, PathBuf};
+///
+/// fn main() {
+///     directory::create("./target/__test/safe_test/rooted_fs/root").unwrap();
+///     file::write_text_file("./target/__test/safe_test/rooted_fs/secret.txt", "secret").unwrap();
+///
+///     let fs = RootedFs::new("./target/__test/safe_test/rooted_fs/root").unwrap();
+///     fs.write_text_file(Path::new("uploads/file.txt"), "some content").unwrap();
+///     assert_eq!(fs.read_text_file(Path::new("uploads/../uploads/file.txt")).unwrap(), "some content");
+///     assert_eq!(fs.list_directory(Path::new("uploads")).unwrap(), vec![PathBuf::from("uploads/file.txt")]);
+///
+///     let error = fs.read_file(Path::new("../secret.txt")).unwrap_err();
+///     assert!(error.is_permission_denied());
+///     assert!(fs.read_file(Path::new("/etc/passwd")).unwrap_err().is_permission_denied());
+///     assert!(!fs.exists(Path::new("../secret.txt")));
+///
+///     let error = RootedFs::new("./target/__test/safe_test/rooted_fs/secret.txt").unwrap_err();
+///     assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+///
+///     #[cfg(unix)]
+///     {
+///         let link_path = "./target/__test/safe_test/rooted_fs/root/escape.txt";
+///         let _ = std::fs::remove_file(link_path);
+///         std::os::unix::fs::symlink("../secret.txt", link_path).unwrap();
+///
+///         let error = fs.read_file(Path::new("escape.txt")).unwrap_err();
+///         assert!(error.is_permission_denied());
+///
+///         // the link itself is inside the root
+///         fs.delete_file(Path::new("escape.txt")).unwrap();
+///         assert!(!Path::new(link_path).is_symlink());
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RootedFs {
+    root: PathBuf,
+    inner: OsFs,
+}
+
+impl RootedFs {
+    /// 创建限制在根目录内的文件系统, 根目录必须存在
+    ///
+    /// # 参数
+    ///
+    /// * `root` - 根目录
+    pub fn new<T: AsPath + ?Sized>(root: &T) -> FsIOResult<RootedFs> {
+        let root_path = root.as_path();
+
+        let root = match dunce::canonicalize(root_path) {
+            Ok(root) => root,
+            Err(error) => return Err(FsIOError::io(Operation::Canonicalize, &root_path, error)),
+        };
+        if !root.is_dir() {
+            return Err(FsIOError::io(
+                Operation::ReadDirectory,
+                &root_path,
+                io::Error::new(io::ErrorKind::InvalidInput, "not a directory"),
+            ));
+        }
+
+        Ok(RootedFs { root, inner: OsFs })
+    }
+
+    /// 返回根目录(规范化的绝对路径)
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 将相对于根目录的路径解析为根目录内的绝对路径(解析所有符号链接)
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 相对于根目录的路径
+    pub fn resolve<T: AsPath + ?Sized>(&self, path: &T) -> FsIOResult<PathBuf> {
+        self.contain(path.as_path(), true)
+    }
+
+    /// 解析路径, `follow_last` 为false时不跟随最后一级的符号链接
+    fn contain(&self, path: &Path, follow_last: bool) -> FsIOResult<PathBuf> {
+        resolve_jailed(&self.root, path, follow_last)
+    }
+}
+
+impl FileSystem for RootedFs {
+    fn read_file(&self, path: &Path) -> FsIOResult<Vec<u8>> {
+        self.inner.read_file(&self.contain(path, true)?)
+    }
+
+    fn write_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        self.inner.write_file(&self.contain(path, true)?, data)
+    }
+
+    fn append_file(&self, path: &Path, data: &[u8]) -> FsIOResult<()> {
+        self.inner.append_file(&self.contain(path, true)?, data)
+    }
+
+    fn delete_file(&self, path: &Path) -> FsIOResult<()> {
+        self.inner.delete_file(&self.contain(path, false)?)
+    }
+
+    fn create_directory(&self, path: &Path) -> FsIOResult<()> {
+        self.inner.create_directory(&self.contain(path, true)?)
+    }
+
+    fn delete_directory(&self, path: &Path) -> FsIOResult<()> {
+        self.inner.delete_directory(&self.contain(path, false)?)
+    }
+
+    fn copy_file(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        self.inner
+            .copy_file(&self.contain(src, true)?, &self.contain(dst, true)?)
+    }
+
+    fn rename(&self, src: &Path, dst: &Path) -> FsIOResult<()> {
+        self.inner
+            .rename(&self.contain(src, false)?, &self.contain(dst, false)?)
+    }
+
+    /// 返回目录下的条目路径(按字典序), 条目路径为 `path` 与条目名称拼接
+    fn list_directory(&self, path: &Path) -> FsIOResult<Vec<PathBuf>> {
+        let entries = self.inner.list_directory(&self.contain(path, true)?)?;
+
+        Ok(entries
+            .iter()
+            .filter_map(|entry| entry.file_name())
+            .map(|name| path.join(name))
+            .collect())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.contain(path, true)
+            .is_ok_and(|resolved| self.inner.exists(&resolved))
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.contain(path, true)
+            .is_ok_and(|resolved| self.inner.is_file(&resolved))
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.contain(path, true)
+            .is_ok_and(|resolved| self.inner.is_dir(&resolved))
+    }
+}
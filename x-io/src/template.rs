@@ -0,0 +1,300 @@
+//! # template
+//!
+//! Template tree expansion for project scaffolding.
+//!
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::path::join_relative;
+use crate::result::FsIOResult;
+use crate::walk::{SymlinkPolicy, Walk, WalkOptions};
+use crate::{directory, file};
+
+/// 检测二进制文件时读取的最大字节数
+const BINARY_DETECTION_SIZE: usize = 8000;
+
+/// 模板展开选项
+#[derive(Debug, Clone)]
+pub struct TemplateOptions {
+    /// 占位符的开始分隔符
+    pub open: String,
+    /// 占位符的结束分隔符
+    pub close: String,
+    /// 遇到未定义的变量时是否返回错误(否则保留占位符原样)
+    pub strict: bool,
+    /// 是否检测二进制文件(前8000字节包含0字节), 二进制文件的内容不替换占位符, 名称仍然替换
+    pub detect_binary: bool,
+}
+
+impl Default for TemplateOptions {
+    fn default() -> Self {
+        TemplateOptions {
+            open: "{{".to_string(),
+            close: "}}".to_string(),
+            strict: false,
+            detect_binary: true,
+        }
+    }
+}
+
+/// 复制模板目录到输出目录, 替换文件内容和文件/目录名称中的 `{{变量}}` 占位符(变量名两侧的空白被忽略)
+///
+/// 如果输出文件存在则覆盖文件, 符号链接按其指向的内容复制.
+///
+/// # 参数
+///
+/// * `template_dir` - 模板目录
+/// * `output_dir` - 输出目录
+/// * `variables` - 变量
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, template};
+/// use std::collections::HashMap;
+///
+/// fn main() {
+///     file::write_text_file(
+///         "./target/__test/template_test/render_tree/template/{{name}}/Cargo.toml",
+///         "[package]\nname = \"{{ name }}\"\nversion = \"{{version}}\"\n",
+///     )
+///     .unwrap();
+///     file::write_file("./target/__test/template_test/render_tree/template/{{name}}/logo.png", b"\x89PNG\0{{name}}").unwrap();
+///
+///     let variables = HashMap::from([
+///         ("name".to_string(), "demo".to_string()),
+///         ("version".to_string(), "0.1.0".to_string()),
+///     ]);
+///     template::render_tree(
+///         "./target/__test/template_test/render_tree/template",
+///         "./target/__test/template_test/render_tree/output",
+///         &variables,
+///     )
+///     .unwrap();
+///
+///     let text = file::read_text_file("./target/__test/template_test/render_tree/output/demo/Cargo.toml").unwrap();
+///     assert_eq!(text, "[package]\nname = \"demo\"\nversion = \"0.1.0\"\n");
+///     let data = file::read_file("./target/__test/template_test/render_tree/output/demo/logo.png").unwrap();
+///     assert_eq!(data, b"\x89PNG\0{{name}}");
+/// }
+/// ```
+pub fn render_tree<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    template_dir: &S,
+    output_dir: &T,
+    variables: &HashMap<String, String>,
+) -> FsIOResult<()> {
+    render_tree_with(
+        template_dir,
+        output_dir,
+        variables,
+        &TemplateOptions::default(),
+    )
+}
+
+/// 复制模板目录到输出目录, 替换文件内容和文件/目录名称中的占位符
+///
+/// # 参数
+///
+/// * `template_dir` - 模板目录
+/// * `output_dir` - 输出目录
+/// * `variables` - 变量
+/// * `options` - 展开选项
+///
+/// # 示例
+///
+/// ```
+/// use x_io::{file, template};
+/// use x_io::template::TemplateOptions;
+/// use std::collections::HashMap;
+///
+/// fn main() {
+///     file::write_text_file(
+///         "./target/__test/template_test/render_tree_with/template/ci.yml",
+///         "name: <%project%>\ntoken: ${{ secrets.TOKEN }}\n",
+///     )
+///     .unwrap();
+///
+///     let variables = HashMap::from([("project".to_string(), "demo".to_string())]);
+///     let options = TemplateOptions {
+///         open: "<%".to_string(),
+///         close: "%>".to_string(),
+///         strict: true,
+///         ..TemplateOptions::default()
+///     };
+///     template::render_tree_with(
+///         "./target/__test/template_test/render_tree_with/template",
+///         "./target/__test/template_test/render_tree_with/output",
+///         &variables,
+///         &options,
+///     )
+///     .unwrap();
+///
+///     let text = file::read_text_file("./target/__test/template_test/render_tree_with/output/ci.yml").unwrap();
+///     assert_eq!(text, "name: demo\ntoken: ${{ secrets.TOKEN }}\n");
+///
+///     let result = template::render_tree_with(
+///         "./target/__test/template_test/render_tree_with/template",
+///         "./target/__test/template_test/render_tree_with/output",
+///         &HashMap::new(),
+///         &options,
+///     );
+///     assert!(result.is_err());
+///
+///     let error = template::render_tree_with(
+///         "./target/__test/template_test/render_tree_with/template/ci.yml",
+///         "./target/__test/template_test/render_tree_with/output",
+///         &variables,
+///         &options,
+///     )
+///     .unwrap_err();
+///     assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+/// }
+/// ```
+pub fn render_tree_with<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+    template_dir: &S,
+    output_dir: &T,
+    variables: &HashMap<String, String>,
+    options: &TemplateOptions,
+) -> FsIOResult<()> {
+    let template_path = template_dir.as_path();
+    let output_path = output_dir.as_path();
+
+    if options.open.is_empty() || options.close.is_empty() {
+        return Err(FsIOError::Parse(
+            "Template delimiters must not be empty.".to_string(),
+            None,
+        ));
+    }
+    if !template_path.is_dir() {
+        return Err(FsIOError::io(
+            Operation::ReadDirectory,
+            &template_path,
+            io::Error::new(io::ErrorKind::InvalidInput, "not a directory"),
+        ));
+    }
+
+    directory::create(&output_path)?;
+
+    let walk_options = WalkOptions {
+        symlinks: SymlinkPolicy::Follow,
+        ..WalkOptions::default()
+    };
+    for entry in Walk::new(template_path, walk_options) {
+        let entry = entry?;
+        let relative_path = match entry.path().strip_prefix(template_path) {
+            Ok(relative_path) => relative_path,
+            Err(_) => continue,
+        };
+
+        let target_path = join_relative(
+            output_path,
+            &render_path(relative_path, variables, options)?,
+        )?;
+
+        if entry.is_dir() {
+            directory::create(&target_path)?;
+            continue;
+        }
+
+        let data = file::read_file(entry.path())?;
+        let binary = options.detect_binary
+            && data
+                .iter()
+                .take(BINARY_DETECTION_SIZE)
+                .any(|byte| *byte == 0);
+        if binary {
+            file::write_file(&target_path, &data)?;
+        } else {
+            let output = render(&data, variables, options, entry.path())?;
+            file::write_file(&target_path, &output)?;
+        }
+
+        match fs::metadata(entry.path()) {
+            Ok(metadata) => {
+                if let Err(error) = fs::set_permissions(&target_path, metadata.permissions()) {
+                    return Err(FsIOError::io(Operation::Write, &target_path, error));
+                }
+            }
+            Err(error) => return Err(FsIOError::io(Operation::Metadata, &entry.path(), error)),
+        }
+    }
+
+    Ok(())
+}
+
+/// 替换相对路径中每个名称的占位符, 非UTF-8的名称保留原样
+fn render_path(
+    relative_path: &Path,
+    variables: &HashMap<String, String>,
+    options: &TemplateOptions,
+) -> FsIOResult<PathBuf> {
+    let mut rendered_path = PathBuf::new();
+
+    for component in relative_path.iter() {
+        match component.to_str() {
+            Some(name) => {
+                let rendered = render(name.as_bytes(), variables, options, relative_path)?;
+                rendered_path.push(String::from_utf8_lossy(&rendered).as_ref());
+            }
+            None => rendered_path.push(component),
+        }
+    }
+
+    Ok(rendered_path)
+}
+
+/// 替换内容中的占位符
+fn render(
+    content: &[u8],
+    variables: &HashMap<String, String>,
+    options: &TemplateOptions,
+    source: &Path,
+) -> FsIOResult<Vec<u8>> {
+    let open = options.open.as_bytes();
+    let close = options.close.as_bytes();
+
+    let mut output = Vec::with_capacity(content.len());
+    let mut position = 0;
+    while let Some(start) = find(content, open, position) {
+        let name_start = start + open.len();
+        let end = match find(content, close, name_start) {
+            Some(end) => end,
+            None => break,
+        };
+
+        output.extend_from_slice(&content[position..start]);
+        let name = String::from_utf8_lossy(&content[name_start..end]);
+        match variables.get(name.trim()) {
+            Some(value) => output.extend_from_slice(value.as_bytes()),
+            None if options.strict => {
+                return Err(FsIOError::Parse(
+                    format!(
+                        "Undefined template variable: {:?} in: {:?}",
+                        name.trim(),
+                        source
+                    )
+                    .to_string(),
+                    None,
+                ))
+            }
+            None => output.extend_from_slice(&content[start..end + close.len()]),
+        }
+        position = end + close.len();
+    }
+    output.extend_from_slice(&content[position..]);
+
+    Ok(output)
+}
+
+/// 从 `from` 开始查找子序列的位置
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| index + from)
+}
@@ -0,0 +1,143 @@
+//! # throttle
+//!
+//! Bandwidth limiting for reads and copies.
+//!
+use std::fmt;
+use std::io;
+use std::io::Read;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 单次读取的最大字节数
+const MAX_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// 带宽限制(令牌桶), 多次读取或复制共用同一个 `Throttle` 时共享带宽
+///
+/// 超过速率时当前线程会等待, 开始时允许最多约0.1秒数据量的突发.
+///
+/// # 示例
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use x_io::throttle::Throttle;
+///
+/// fn main() {
+///     let mut throttle = Throttle::new(1_000_000);
+///     assert_eq!(throttle.bytes_per_sec(), 1_000_000);
+///
+///     let started = Instant::now();
+///     throttle.consume(300_000);
+///     assert!(started.elapsed() >= Duration::from_millis(200));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Throttle {
+    bytes_per_sec: u64,
+    started: Option<Instant>,
+    consumed: u64,
+}
+
+impl fmt::Debug for Throttle {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        formatter
+            .debug_struct("Throttle")
+            .field("bytes_per_sec", &self.bytes_per_sec)
+            .finish()
+    }
+}
+
+impl PartialEq for Throttle {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes_per_sec == other.bytes_per_sec
+    }
+}
+
+impl Eq for Throttle {}
+
+impl Throttle {
+    /// 创建带宽限制
+    ///
+    /// # 参数
+    ///
+    /// * `bytes_per_sec` - 每秒最多字节数(0按1处理)
+    pub fn new(bytes_per_sec: u64) -> Throttle {
+        Throttle {
+            bytes_per_sec: bytes_per_sec.max(1),
+            started: None,
+            consumed: 0,
+        }
+    }
+
+    /// 返回每秒最多字节数
+    pub fn bytes_per_sec(&self) -> u64 {
+        self.bytes_per_sec
+    }
+
+    /// 返回每次读取的建议字节数(约0.1秒的数据量, 最多64KiB)
+    pub fn chunk_size(&self) -> usize {
+        (self.bytes_per_sec / 10).clamp(1, MAX_CHUNK_SIZE) as usize
+    }
+
+    /// 记录传输了 `bytes` 字节, 超过速率时等待
+    ///
+    /// # 参数
+    ///
+    /// * `bytes` - 字节数
+    pub fn consume(&mut self, bytes: u64) {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let burst = self.chunk_size() as u64;
+        self.consumed += bytes;
+
+        let allowed = self.consumed.saturating_sub(burst);
+        let expected = Duration::from_secs_f64(allowed as f64 / self.bytes_per_sec as f64);
+        let elapsed = started.elapsed();
+        if expected > elapsed {
+            thread::sleep(expected - elapsed);
+        }
+    }
+
+    /// 使用该带宽限制包装读取器
+    ///
+    /// # 参数
+    ///
+    /// * `inner` - 读取器
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use std::io::Read;
+    /// use x_io::file;
+    /// use x_io::throttle::Throttle;
+    ///
+    /// fn main() {
+    ///     file::write_text_file("./target/__test/throttle_test/reader/file.txt", "some content").unwrap();
+    ///
+    ///     let mut throttle = Throttle::new(1024 * 1024);
+    ///     let file = std::fs::File::open("./target/__test/throttle_test/reader/file.txt").unwrap();
+    ///     let mut text = String::new();
+    ///     throttle.reader(file).read_to_string(&mut text).unwrap();
+    ///     assert_eq!(text, "some content");
+    /// }
+    /// ```
+    pub fn reader<R: Read>(&mut self, inner: R) -> ThrottledReader<'_, R> {
+        ThrottledReader {
+            inner,
+            throttle: self,
+        }
+    }
+}
+
+/// 限制带宽的读取器, 参见 `Throttle::reader`
+pub struct ThrottledReader<'a, R: Read> {
+    inner: R,
+    throttle: &'a mut Throttle,
+}
+
+impl<R: Read> Read for ThrottledReader<'_, R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let limit = buffer.len().min(self.throttle.chunk_size());
+        let size = self.inner.read(&mut buffer[..limit])?;
+        self.throttle.consume(size as u64);
+        Ok(size)
+    }
+}
@@ -0,0 +1,76 @@
+//! # timeout
+//!
+//! Deadlines for blocking operations (for example on hung network mounts).
+//!
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::Duration;
+
+use crate::error::FsIOError;
+use crate::result::FsIOResult;
+
+/// 在辅助线程上执行阻塞操作, 超过期限时返回 `FsIOError::TimedOut`
+///
+/// 超时后操作不会被取消, 而是在后台继续执行直到完成, 其结果会被丢弃.
+/// 操作panic时在调用线程上重新panic.
+///
+/// # 参数
+///
+/// * `duration` - 期限
+/// * `operation` - 操作
+///
+/// # 示例
+///
+/// ```
+/// use std::time::Duration;
+/// use x_io::{file, timeout};
+///
+/// fn main() {
+///     file::write_text_file("./target/__test/timeout_test/with_deadline/file.txt", "some content").unwrap();
+///
+///     let text = timeout::with_deadline(Duration::from_secs(10), || {
+///         file::read_text_file("./target/__test/timeout_test/with_deadline/file.txt")
+///     })
+///     .unwrap();
+///     assert_eq!(text, "some content");
+///
+///     let error = timeout::with_deadline(Duration::from_millis(10), || {
+///         std::thread::sleep(Duration::from_secs(1));
+///         Ok(())
+///     })
+///     .unwrap_err();
+///     assert!(error.is_timed_out());
+/// }
+/// ```
+pub fn with_deadline<F, R>(duration: Duration, operation: F) -> FsIOResult<R>
+where
+    F: FnOnce() -> FsIOResult<R> + Send + 'static,
+    R: Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    let handle = thread::Builder::new()
+        .name("x-io-deadline".to_string())
+        .spawn(move || {
+            // the receiver is gone after a timeout, the result is dropped
+            let _ = sender.send(operation());
+        });
+    let handle = match handle {
+        Ok(handle) => handle,
+        Err(error) => {
+            return Err(FsIOError::Context(
+                "Unable to spawn deadline thread.".to_string(),
+                Box::new(error.into()),
+            ))
+        }
+    };
+
+    match receiver.recv_timeout(duration) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => Err(FsIOError::TimedOut(duration)),
+        Err(RecvTimeoutError::Disconnected) => match handle.join() {
+            Err(panic) => std::panic::resume_unwind(panic),
+            Ok(_) => Err(FsIOError::TimedOut(duration)),
+        },
+    }
+}
@@ -0,0 +1,303 @@
+//! # transaction
+//!
+//! All-or-nothing file system changes.
+//!
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::directory;
+use crate::error::{FsIOError, Operation};
+use crate::file;
+use crate::path::as_path::AsPath;
+use crate::path::unique_sibling;
+use crate::result::FsIOResult;
+
+enum Step {
+    Write(PathBuf, Vec<u8>),
+    Copy(PathBuf, PathBuf),
+    Delete(PathBuf),
+    Rename(PathBuf, PathBuf),
+}
+
+enum Undo {
+    RemoveCreated(PathBuf),
+    RestoreBackup(PathBuf, PathBuf),
+    RenameBack(PathBuf, PathBuf),
+    RemoveDirectory(PathBuf),
+}
+
+/// 文件系统事务, 暂存写入/复制/删除/移动操作, 提交时全部执行, 任意一步失败则回滚已执行的操作
+///
+/// 被覆盖或删除的文件会先移动到同目录下的备份文件, 回滚时恢复, 提交成功后删除.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::transaction::FsTransaction;
+///
+/// fn main() {
+///     let config_path = "./target/__test/transaction_test/example/config.toml";
+///     let data_path = "./target/__test/transaction_test/example/data.txt";
+///     file::write_text_file(config_path, "old").unwrap();
+///
+///     let mut transaction = FsTransaction::new();
+///     transaction.write_file(config_path, "new".as_bytes());
+///     transaction.write_file(data_path, "data".as_bytes());
+///     let result = transaction.commit();
+///     assert!(result.is_ok());
+///
+///     assert_eq!(file::read_text_file(config_path).unwrap(), "new");
+///     assert_eq!(file::read_text_file(data_path).unwrap(), "data");
+/// }
+/// ```
+#[derive(Default)]
+pub struct FsTransaction {
+    steps: Vec<Step>,
+}
+
+impl FsTransaction {
+    /// 创建空事务
+    pub fn new() -> FsTransaction {
+        FsTransaction { steps: vec![] }
+    }
+
+    /// 暂存写入文件操作, 如果存在则覆盖文件
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件路径
+    /// * `data` - 原始数据
+    pub fn write_file<T: AsPath + ?Sized>(&mut self, path: &T, data: &[u8]) -> &mut FsTransaction {
+        self.steps
+            .push(Step::Write(path.as_path().to_path_buf(), data.to_vec()));
+        self
+    }
+
+    /// 暂存写入文本文件操作, 如果存在则覆盖文件
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件路径
+    /// * `text` - 文本内容
+    pub fn write_text_file<T: AsPath + ?Sized>(
+        &mut self,
+        path: &T,
+        text: &str,
+    ) -> &mut FsTransaction {
+        self.write_file(path, text.as_bytes())
+    }
+
+    /// 暂存复制文件操作, 如果目标存在则覆盖文件
+    ///
+    /// # 参数
+    ///
+    /// * `src` - 源文件路径
+    /// * `dst` - 目标文件路径
+    pub fn copy<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+        &mut self,
+        src: &S,
+        dst: &T,
+    ) -> &mut FsTransaction {
+        self.steps.push(Step::Copy(
+            src.as_path().to_path_buf(),
+            dst.as_path().to_path_buf(),
+        ));
+        self
+    }
+
+    /// 暂存删除文件或目录操作
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 路径
+    pub fn delete<T: AsPath + ?Sized>(&mut self, path: &T) -> &mut FsTransaction {
+        self.steps.push(Step::Delete(path.as_path().to_path_buf()));
+        self
+    }
+
+    /// 暂存移动文件或目录操作, 如果目标存在则覆盖
+    ///
+    /// # 参数
+    ///
+    /// * `src` - 源路径
+    /// * `dst` - 目标路径
+    pub fn rename<S: AsPath + ?Sized, T: AsPath + ?Sized>(
+        &mut self,
+        src: &S,
+        dst: &T,
+    ) -> &mut FsTransaction {
+        self.steps.push(Step::Rename(
+            src.as_path().to_path_buf(),
+            dst.as_path().to_path_buf(),
+        ));
+        self
+    }
+
+    /// 返回暂存的操作数量
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// 返回是否没有暂存的操作
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// 按顺序执行所有暂存的操作, 任意一步失败则回滚并返回该错误
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::file;
+    /// use x_io::transaction::FsTransaction;
+    ///
+    /// fn main() {
+    ///     let config_path = "./target/__test/transaction_test/rollback/config.toml";
+    ///     file::write_text_file(config_path, "old").unwrap();
+    ///
+    ///     let mut transaction = FsTransaction::new();
+    ///     transaction.write_file(config_path, "new".as_bytes());
+    ///     transaction.copy("./target/__test/transaction_test/rollback/missing.txt", "./target/__test/transaction_test/rollback/copy.txt");
+    ///     let result = transaction.commit();
+    ///     assert!(result.is_err());
+    ///
+    ///     assert_eq!(file::read_text_file(config_path).unwrap(), "old");
+    /// }
+    /// ```
+    pub fn commit(self) -> FsIOResult<()> {
+        let mut undo_log = vec![];
+
+        for step in self.steps {
+            if let Err(error) = apply_step(step, &mut undo_log) {
+                rollback(undo_log);
+                return Err(error);
+            }
+        }
+
+        for undo in undo_log {
+            if let Undo::RestoreBackup(backup, _) = undo {
+                remove_path(&backup);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn apply_step(step: Step, undo_log: &mut Vec<Undo>) -> FsIOResult<()> {
+    match step {
+        Step::Write(path, data) => {
+            prepare_target(&path, undo_log)?;
+            undo_log.push(Undo::RemoveCreated(path.clone()));
+            file::write_file(&path, &data)
+        }
+        Step::Copy(src, dst) => {
+            if !src.is_file() {
+                return Err(FsIOError::NotFile(
+                    format!("Path: {:?} is not a file.", &src).to_string(),
+                ));
+            }
+            prepare_target(&dst, undo_log)?;
+            undo_log.push(Undo::RemoveCreated(dst.clone()));
+            match fs::copy(&src, &dst) {
+                Ok(_) => Ok(()),
+                Err(error) => Err(FsIOError::io(
+                    Operation::Copy(dst.to_path_buf()),
+                    &src,
+                    error,
+                )),
+            }
+        }
+        Step::Delete(path) => {
+            if fs::symlink_metadata(&path).is_ok() {
+                move_to_backup(&path, undo_log)?;
+            }
+            Ok(())
+        }
+        Step::Rename(src, dst) => {
+            prepare_target(&dst, undo_log)?;
+            match fs::rename(&src, &dst) {
+                Ok(_) => {
+                    undo_log.push(Undo::RenameBack(dst, src));
+                    Ok(())
+                }
+                Err(error) => Err(FsIOError::io(
+                    Operation::Rename(dst.to_path_buf()),
+                    &src,
+                    error,
+                )),
+            }
+        }
+    }
+}
+
+/// 创建缺少的父级目录, 并将已存在的目标移动到备份
+fn prepare_target(path: &Path, undo_log: &mut Vec<Undo>) -> FsIOResult<()> {
+    let mut missing = vec![];
+    let mut ancestor = path.parent();
+    while let Some(directory_path) = ancestor {
+        if directory_path.as_os_str().is_empty() || directory_path.exists() {
+            break;
+        }
+        missing.push(directory_path.to_path_buf());
+        ancestor = directory_path.parent();
+    }
+
+    for directory_path in missing.into_iter().rev() {
+        directory::create(&directory_path)?;
+        undo_log.push(Undo::RemoveDirectory(directory_path));
+    }
+
+    if fs::symlink_metadata(path).is_ok() {
+        move_to_backup(path, undo_log)?;
+    }
+
+    Ok(())
+}
+
+fn move_to_backup(path: &Path, undo_log: &mut Vec<Undo>) -> FsIOResult<()> {
+    let backup = unique_sibling(path, "backup");
+
+    match fs::rename(path, &backup) {
+        Ok(_) => {
+            undo_log.push(Undo::RestoreBackup(backup, path.to_path_buf()));
+            Ok(())
+        }
+        Err(error) => Err(FsIOError::io(
+            Operation::Rename(backup.clone()),
+            &path,
+            error,
+        )),
+    }
+}
+
+fn rollback(undo_log: Vec<Undo>) {
+    for undo in undo_log.into_iter().rev() {
+        match undo {
+            Undo::RemoveCreated(path) => remove_path(&path),
+            Undo::RestoreBackup(backup, original) => {
+                remove_path(&original);
+                let _ = fs::rename(&backup, &original);
+            }
+            Undo::RenameBack(from, to) => {
+                let _ = fs::rename(&from, &to);
+            }
+            Undo::RemoveDirectory(path) => {
+                let _ = fs::remove_dir(&path);
+            }
+        }
+    }
+}
+
+fn remove_path(path: &Path) {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => {
+            let _ = fs::remove_dir_all(path);
+        }
+        Ok(_) => {
+            let _ = fs::remove_file(path);
+        }
+        Err(_) => {}
+    }
+}
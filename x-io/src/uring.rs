@@ -0,0 +1,411 @@
+//! # uring
+//!
+//! Batched reads, writes and copies of many small files through io_uring (Linux, `uring` feature).
+//!
+use std::ffi::CString;
+use std::fs::File;
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{FileExt, MetadataExt};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use io_uring::{opcode, squeue, types, IoUring};
+
+/// 提交队列的大小
+const RING_ENTRIES: u32 = 128;
+
+/// 每批处理的文件数量
+const BATCH_SIZE: usize = 64;
+
+/// 提交失败后轮询未完成操作的间隔
+const DRAIN_INTERVAL: Duration = Duration::from_millis(1);
+
+/// 使用io_uring读取的最大字节数, 剩余部分(包括读取不足时)使用普通读取
+const READ_SIZE: usize = 64 * 1024;
+
+/// 批量读取文件内容, 无法使用io_uring(内核不支持或被禁止)时返回 `None`
+///
+/// 失败的文件返回错误, 由调用方使用普通读取重试以得到一致的错误信息.
+pub(crate) fn read_files(paths: &[PathBuf]) -> Option<Vec<io::Result<Vec<u8>>>> {
+    let mut ring = Ring::new()?;
+
+    let mut results = Vec::with_capacity(paths.len());
+    for chunk in paths.chunks(BATCH_SIZE) {
+        let files = ring.open(chunk, libc::O_RDONLY);
+        let buffers = ring.read(&files);
+
+        let mut opened = vec![];
+        for (file, buffer) in files.into_iter().zip(buffers) {
+            results.push(file.and_then(|mut file| {
+                // a short first read does not mean the end of the file (FUSE, NFS, signals)
+                let mut buffer = buffer?;
+                file.seek(SeekFrom::Start(buffer.len() as u64))?;
+                file.read_to_end(&mut buffer)?;
+                opened.push(file);
+                Ok(buffer)
+            }));
+        }
+        ring.close(opened);
+    }
+
+    Some(results)
+}
+
+/// 批量创建或覆盖文件, 无法使用io_uring时返回 `None`
+///
+/// 失败的文件返回错误, 由调用方使用普通写入重试.
+pub(crate) fn write_files(files: &[(PathBuf, Vec<u8>)]) -> Option<Vec<io::Result<()>>> {
+    let mut ring = Ring::new()?;
+
+    let mut results = Vec::with_capacity(files.len());
+    for chunk in files.chunks(BATCH_SIZE) {
+        let paths: Vec<PathBuf> = chunk.iter().map(|(path, _)| path.clone()).collect();
+        let targets = ring.open(&paths, libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC);
+        let data: Vec<&[u8]> = chunk.iter().map(|(_, data)| data.as_slice()).collect();
+        let written = ring.write(&targets, &data);
+
+        let mut opened = vec![];
+        for ((target, data), size) in targets.into_iter().zip(data).zip(written) {
+            results.push(target.and_then(|target| {
+                // a short write is completed with regular writes
+                let size = size?;
+                let result = target.write_all_at(&data[size..], size as u64);
+                opened.push(target);
+                result
+            }));
+        }
+        ring.close(opened);
+    }
+
+    Some(results)
+}
+
+/// 批量复制文件(包括权限), 返回每个文件复制的字节数, 无法使用io_uring时返回 `None`
+///
+/// 失败的文件返回错误, 由调用方使用普通复制重试.
+pub(crate) fn copy_files(files: &[(PathBuf, PathBuf)]) -> Option<Vec<io::Result<u64>>> {
+    let mut ring = Ring::new()?;
+
+    let mut results = Vec::with_capacity(files.len());
+    for chunk in files.chunks(BATCH_SIZE) {
+        let paths: Vec<PathBuf> = chunk.iter().map(|(source, _)| source.clone()).collect();
+        let sources = ring.open(&paths, libc::O_RDONLY);
+
+        // targets are only created for sources that are regular files, a target that is a
+        // hard link of its source is never opened, truncating it would empty the source
+        let sources: Vec<io::Result<(File, std::fs::Permissions)>> = sources
+            .into_iter()
+            .zip(chunk)
+            .map(|(source, (_, target))| {
+                let source = source?;
+                let metadata = source.metadata()?;
+                if !metadata.is_file() {
+                    return Err(io::Error::from(io::ErrorKind::InvalidInput));
+                }
+                if let Ok(target_metadata) = std::fs::metadata(target) {
+                    if target_metadata.dev() == metadata.dev()
+                        && target_metadata.ino() == metadata.ino()
+                    {
+                        return Err(io::Error::from(io::ErrorKind::InvalidInput));
+                    }
+                }
+                Ok((source, metadata.permissions()))
+            })
+            .collect();
+        let paths: Vec<PathBuf> = chunk
+            .iter()
+            .zip(&sources)
+            .filter(|(_, source)| source.is_ok())
+            .map(|((_, target), _)| target.clone())
+            .collect();
+        let mut targets = ring
+            .open(&paths, libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC)
+            .into_iter();
+
+        let mut readers = vec![];
+        let mut writers = vec![];
+        for source in sources {
+            match source {
+                Ok((source, permissions)) => {
+                    let target = targets.next().unwrap_or_else(|| Err(not_submitted()));
+                    let target = target.and_then(|target| {
+                        target.set_permissions(permissions)?;
+                        Ok(target)
+                    });
+                    match target {
+                        Ok(target) => {
+                            readers.push(Ok(source));
+                            writers.push(Ok(target));
+                        }
+                        Err(error) => {
+                            ring.close(vec![source]);
+                            readers.push(Err(not_submitted()));
+                            writers.push(Err(error));
+                        }
+                    }
+                }
+                Err(error) => {
+                    readers.push(Err(not_submitted()));
+                    writers.push(Err(error));
+                }
+            }
+        }
+
+        let buffers = ring.read(&readers);
+        let empty = vec![];
+        let data: Vec<&[u8]> = buffers
+            .iter()
+            .map(|buffer| buffer.as_ref().unwrap_or(&empty).as_slice())
+            .collect();
+        let written = ring.write(&writers, &data);
+
+        let mut opened = vec![];
+        for (((source, target), buffer), size) in
+            readers.into_iter().zip(writers).zip(&buffers).zip(written)
+        {
+            let result = target.and_then(|mut target| {
+                let result = (|| {
+                    let mut source = source?;
+                    let buffer = buffer.as_ref().map_err(clone_error)?;
+                    let size = size?;
+                    target.write_all_at(&buffer[size..], size as u64)?;
+
+                    // a short first read does not mean the end of the file
+                    let mut bytes = buffer.len() as u64;
+                    source.seek(SeekFrom::Start(bytes))?;
+                    target.seek(SeekFrom::Start(bytes))?;
+                    bytes += io::copy(&mut source, &mut target)?;
+                    opened.push(source);
+                    Ok(bytes)
+                })();
+                opened.push(target);
+                result
+            });
+            results.push(result);
+        }
+        ring.close(opened);
+    }
+
+    Some(results)
+}
+
+struct Ring {
+    ring: IoUring,
+    failed: bool,
+}
+
+impl Ring {
+    fn new() -> Option<Ring> {
+        match IoUring::new(RING_ENTRIES) {
+            Ok(ring) => Some(Ring {
+                ring,
+                failed: false,
+            }),
+            Err(_) => None,
+        }
+    }
+
+    /// 提交操作并等待全部完成, 按顺序返回每个操作的结果, `None` 对应的结果为错误
+    ///
+    /// # Safety
+    ///
+    /// 操作引用的缓冲区和路径在返回之前必须保持有效.
+    unsafe fn run(&mut self, entries: Vec<Option<squeue::Entry>>) -> Vec<io::Result<usize>> {
+        let mut results: Vec<Option<io::Result<usize>>> = entries
+            .iter()
+            .map(|entry| match entry {
+                Some(_) => None,
+                None => Some(Err(not_submitted())),
+            })
+            .collect();
+
+        let mut pending = entries
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, entry)| Some(entry?.user_data(index as u64)))
+            .peekable();
+        while !self.failed && pending.peek().is_some() {
+            let mut submission = self.ring.submission();
+            let mut submitted = 0;
+            while !submission.is_full() {
+                match pending.next() {
+                    Some(entry) => {
+                        // cannot fail, the queue is not full
+                        let _ = submission.push(&entry);
+                        submitted += 1;
+                    }
+                    None => break,
+                }
+            }
+            drop(submission);
+
+            let mut completed = 0;
+            while completed < submitted {
+                if let Err(error) = self.ring.submit_and_wait(submitted - completed) {
+                    if !is_retryable(&error) {
+                        // entries the kernel already consumed (by this or an earlier call) can
+                        // still complete into the buffers, they are reaped before returning,
+                        // the rest stay in the queue and the ring is not used again
+                        self.failed = true;
+                        let unconsumed = self.ring.submission().len();
+                        let in_flight = (submitted - unconsumed).saturating_sub(completed);
+                        self.drain(in_flight, &mut results);
+                        break;
+                    }
+                }
+                completed += self.reap(&mut results);
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.unwrap_or_else(|| Err(not_submitted())))
+            .collect()
+    }
+
+    /// 保存完成队列中的结果, 返回完成的操作数量
+    fn reap(&mut self, results: &mut [Option<io::Result<usize>>]) -> usize {
+        let mut completed = 0;
+        for completion in self.ring.completion() {
+            let result = completion.result();
+            results[completion.user_data() as usize] = Some(if result < 0 {
+                Err(io::Error::from_raw_os_error(-result))
+            } else {
+                Ok(result as usize)
+            });
+            completed += 1;
+        }
+        completed
+    }
+
+    /// 提交失败后轮询完成队列(不再调用io_uring_enter), 直到内核已接受的 `in_flight` 个操作全部完成
+    fn drain(&mut self, mut in_flight: usize, results: &mut [Option<io::Result<usize>>]) {
+        loop {
+            in_flight = in_flight.saturating_sub(self.reap(results));
+            if in_flight == 0 {
+                return;
+            }
+            thread::sleep(DRAIN_INTERVAL);
+        }
+    }
+
+    /// 打开一批文件(新建文件的权限为 `0o666`, 受umask影响)
+    fn open(&mut self, paths: &[PathBuf], flags: i32) -> Vec<io::Result<File>> {
+        let names: Vec<Option<CString>> = paths
+            .iter()
+            .map(|path| CString::new(path.as_os_str().as_bytes()).ok())
+            .collect();
+
+        let entries = names
+            .iter()
+            .map(|name| {
+                let name = name.as_ref()?;
+                Some(
+                    opcode::OpenAt::new(types::Fd(libc::AT_FDCWD), name.as_ptr())
+                        .flags(flags | libc::O_CLOEXEC)
+                        .mode(0o666)
+                        .build(),
+                )
+            })
+            .collect();
+        // the names outlive the submission
+        let opened = unsafe { self.run(entries) };
+
+        names
+            .iter()
+            .zip(opened)
+            .map(|(name, fd)| match name {
+                Some(_) => Ok(unsafe { File::from_raw_fd(fd? as RawFd) }),
+                None => Err(io::Error::from(io::ErrorKind::InvalidInput)),
+            })
+            .collect()
+    }
+
+    /// 从文件开头读取最多 `READ_SIZE` 字节
+    fn read(&mut self, files: &[io::Result<File>]) -> Vec<io::Result<Vec<u8>>> {
+        let mut buffers: Vec<Vec<u8>> = files
+            .iter()
+            .map(|file| match file {
+                Ok(_) => vec![0u8; READ_SIZE],
+                Err(_) => vec![],
+            })
+            .collect();
+
+        let entries = files
+            .iter()
+            .zip(buffers.iter_mut())
+            .map(|(file, buffer)| {
+                let file = file.as_ref().ok()?;
+                let fd = types::Fd(file.as_raw_fd());
+                Some(
+                    opcode::Read::new(fd, buffer.as_mut_ptr(), buffer.len() as u32)
+                        .offset(0)
+                        .build(),
+                )
+            })
+            .collect();
+        // the buffers outlive the submission
+        let sizes = unsafe { self.run(entries) };
+
+        buffers
+            .into_iter()
+            .zip(sizes)
+            .map(|(mut buffer, size)| {
+                buffer.truncate(size?);
+                Ok(buffer)
+            })
+            .collect()
+    }
+
+    /// 从文件开头写入数据, 返回写入的字节数(可能少于数据的长度)
+    fn write(&mut self, files: &[io::Result<File>], data: &[&[u8]]) -> Vec<io::Result<usize>> {
+        let entries = files
+            .iter()
+            .zip(data)
+            .map(|(file, data)| {
+                let file = file.as_ref().ok()?;
+                let fd = types::Fd(file.as_raw_fd());
+                let length = data.len().min(u32::MAX as usize) as u32;
+                Some(
+                    opcode::Write::new(fd, data.as_ptr(), length)
+                        .offset(0)
+                        .build(),
+                )
+            })
+            .collect();
+        // the data outlives the submission
+        unsafe { self.run(entries) }
+    }
+
+    /// 关闭一批文件, 忽略关闭错误(与 `File` 的drop相同)
+    fn close(&mut self, files: Vec<File>) {
+        if self.failed {
+            return;
+        }
+        let entries = files
+            .into_iter()
+            .map(|file| Some(opcode::Close::new(types::Fd(file.into_raw_fd())).build()))
+            .collect();
+        unsafe { self.run(entries) };
+    }
+}
+
+fn is_retryable(error: &io::Error) -> bool {
+    error.kind() == io::ErrorKind::Interrupted
+        || matches!(error.raw_os_error(), Some(libc::EAGAIN) | Some(libc::EBUSY))
+}
+
+fn clone_error(error: &io::Error) -> io::Error {
+    match error.raw_os_error() {
+        Some(code) => io::Error::from_raw_os_error(code),
+        None => io::Error::from(error.kind()),
+    }
+}
+
+fn not_submitted() -> io::Error {
+    io::Error::other("io_uring operation was not completed")
+}
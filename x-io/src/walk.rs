@@ -0,0 +1,432 @@
+//! # walk
+//!
+//! Lazy directory traversal with depth/breadth first order, depth limits and pruning.
+//!
+use std::collections::VecDeque;
+use std::ffi::OsStr;
+use std::fs::{metadata, read_dir, FileType};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::error::{FsIOError, Operation};
+use crate::filter::PathFilter;
+use crate::path::as_path::AsPath;
+use crate::path::{file_id, FileId, FileKind};
+use crate::result::FsIOResult;
+
+/// 遍历顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// 深度优先, 目录在其子条目之前返回
+    #[default]
+    DepthFirst,
+    /// 广度优先, 按层级返回(先返回所有深度为1的条目, 然后是深度为2的条目, ...)
+    BreadthFirst,
+}
+
+/// 符号链接的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// 跳过符号链接
+    Skip,
+    /// 跟随符号链接, 按其指向的文件或目录处理, 检测到循环时返回 `FsIOError::SymlinkLoop`
+    Follow,
+    /// 保留符号链接本身(遍历时返回链接条目但不进入其指向的目录, 复制时重新创建链接)
+    Preserve,
+}
+
+/// 遍历选项
+#[derive(Debug, Clone, Copy)]
+pub struct WalkOptions {
+    /// 遍历顺序
+    pub strategy: Strategy,
+    /// 最大深度(根目录的子条目深度为1), None表示不限制
+    pub max_depth: Option<usize>,
+    /// 同一目录下的条目是否按名称的字典序返回(否则为平台的目录读取顺序)
+    pub sorted: bool,
+    /// 符号链接的处理方式
+    pub symlinks: SymlinkPolicy,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        WalkOptions {
+            strategy: Strategy::DepthFirst,
+            max_depth: None,
+            sorted: true,
+            symlinks: SymlinkPolicy::Preserve,
+        }
+    }
+}
+
+/// 遍历返回的条目
+///
+/// 条目的类型来自目录读取结果, 只有跟随符号链接时才会读取链接指向的元数据.
+#[derive(Debug, Clone)]
+pub struct DirEntryInfo {
+    path: PathBuf,
+    depth: usize,
+    file_type: FileType,
+    symlink: bool,
+    skip: Arc<AtomicBool>,
+}
+
+impl DirEntryInfo {
+    /// 返回条目路径(根目录路径与条目名称拼接)
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 返回条目路径
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    /// 返回条目名称
+    pub fn file_name(&self) -> &OsStr {
+        self.path.file_name().unwrap_or(self.path.as_os_str())
+    }
+
+    /// 返回深度(根目录的子条目为1)
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// 返回条目类型, `SymlinkPolicy::Follow` 时为符号链接指向的类型(链接无效时为链接本身的类型)
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    /// 返回条目类型, 可以区分套接字, 命名管道和设备等特殊文件(与 `file_type` 一致)
+    pub fn kind(&self) -> FileKind {
+        FileKind::from_file_type(self.file_type)
+    }
+
+    /// 返回是否为目录
+    pub fn is_dir(&self) -> bool {
+        self.file_type.is_dir()
+    }
+
+    /// 返回是否为文件
+    pub fn is_file(&self) -> bool {
+        self.file_type.is_file()
+    }
+
+    /// 返回条目本身是否为符号链接
+    pub fn is_symlink(&self) -> bool {
+        self.symlink
+    }
+
+    /// 不遍历该目录的子条目(必须在继续遍历之前调用), 对非目录条目无效
+    pub fn skip_subtree(&self) {
+        self.skip.store(true, Ordering::Relaxed);
+    }
+}
+
+/// 目录遍历迭代器, 不返回根目录本身
+///
+/// 目录在被返回之后才会被读取, 因此 `DirEntryInfo::skip_subtree` 可以跳过整个子树而不读取其中的条目.
+/// 读取目录失败(或跟随符号链接时检测到循环)时返回该错误并继续遍历其他目录.
+///
+/// # 示例
+///
+/// ```
+/// use x_io::file;
+/// use x_io::walk::{Strategy, Walk, WalkOptions};
+/// use std::path::{Path, PathBuf};
+///
+/// fn main() {
+///     let root = Path::new("./target/__test/walk_test/walk");
+///     for name in ["a/b/c.txt", "a/d.txt", "e.txt", "node_modules/x/y.js"] {
+///         file::write_text_file(&root.join(name), "").unwrap();
+///     }
+///     let relative = |entries: Vec<PathBuf>| -> Vec<String> {
+///         entries.iter().map(|entry| entry.strip_prefix(root).unwrap().to_str().unwrap().replace('\\', "/")).collect()
+///     };
+///
+///     let mut entries = vec![];
+///     for entry in Walk::new(root, WalkOptions::default()) {
+///         let entry = entry.unwrap();
+///         if entry.file_name() == "node_modules" {
+///             entry.skip_subtree();
+///         }
+///         entries.push(entry.into_path());
+///     }
+///     assert_eq!(relative(entries), vec!["a", "a/b", "a/b/c.txt", "a/d.txt", "e.txt", "node_modules"]);
+///
+///     let options = WalkOptions {
+///         strategy: Strategy::BreadthFirst,
+///         max_depth: Some(2),
+///         ..WalkOptions::default()
+///     };
+///     let entries: Vec<PathBuf> = Walk::new(root, options).map(|entry| entry.unwrap().into_path()).collect();
+///     assert_eq!(relative(entries), vec!["a", "e.txt", "node_modules", "a/b", "a/d.txt", "node_modules/x"]);
+/// }
+/// ```
+///
+/// 跟随符号链接时检测循环:
+///
+/// ```
+/// use x_io::error::FsIOError;
+/// use x_io::file;
+/// use x_io::walk::{SymlinkPolicy, Walk, WalkOptions};
+///
+/// fn main() {
+///     let root = "./target/__test/walk_test/walk_symlinks";
+///     file::write_text_file("./target/__test/walk_test/walk_symlinks/dir/file.txt", "").unwrap();
+///
+///     #[cfg(unix)]
+///     {
+///         let link_path = "./target/__test/walk_test/walk_symlinks/dir/parent";
+///         let _ = std::fs::remove_file(link_path);
+///         std::os::unix::fs::symlink("..", link_path).unwrap();
+///
+///         let options = WalkOptions {
+///             symlinks: SymlinkPolicy::Follow,
+///             ..WalkOptions::default()
+///         };
+///         let results: Vec<_> = Walk::new(root, options).collect();
+///         assert_eq!(results.len(), 4);
+///         assert!(matches!(results[3], Err(FsIOError::SymlinkLoop(_))));
+///         let link = results[2].as_ref().unwrap();
+///         assert!(link.is_symlink() && link.is_dir());
+///
+///         let options = WalkOptions {
+///             symlinks: SymlinkPolicy::Skip,
+///             ..WalkOptions::default()
+///         };
+///         assert_eq!(Walk::new(root, options).count(), 2);
+///     }
+/// }
+/// ```
+///
+/// 跳过特殊文件:
+///
+/// ```
+/// use x_io::file;
+/// use x_io::walk::{Walk, WalkOptions};
+///
+/// fn main() {
+///     let root = "./target/__test/walk_test/walk_special";
+///     file::write_text_file("./target/__test/walk_test/walk_special/file.txt", "").unwrap();
+///
+///     #[cfg(unix)]
+///     {
+///         let fifo_path = "./target/__test/walk_test/walk_special/events.fifo";
+///         let _ = std::fs::remove_file(fifo_path);
+///         file::create_fifo(fifo_path, 0o600).unwrap();
+///     }
+///
+///     let regular: Vec<_> = Walk::new(root, WalkOptions::default())
+///         .map(|entry| entry.unwrap())
+///         .filter(|entry| !entry.kind().is_special())
+///         .collect();
+///     assert_eq!(regular.len(), 1);
+///     assert_eq!(regular[0].file_name(), "file.txt");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Walk {
+    options: WalkOptions,
+    /// 根目录路径
+    root: PathBuf,
+    /// 路径过滤器
+    filter: Option<PathFilter>,
+    /// 等待返回的条目(条目, 父目录及其祖先目录的文件标识)
+    queue: VecDeque<(DirEntryInfo, Arc<Vec<FileId>>)>,
+    /// 已返回但尚未读取的目录
+    pending: Option<PendingDirectory>,
+}
+
+/// 已返回但尚未读取的目录
+#[derive(Debug)]
+struct PendingDirectory {
+    path: PathBuf,
+    depth: usize,
+    skip: Arc<AtomicBool>,
+    /// 父目录及其祖先目录的文件标识(跟随符号链接时用于检测循环)
+    ancestors: Arc<Vec<FileId>>,
+}
+
+impl Walk {
+    /// 创建遍历迭代器(在第一次调用 `next` 时读取根目录)
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 根目录路径
+    /// * `options` - 遍历选项
+    pub fn new<T: AsPath + ?Sized>(path: &T, options: WalkOptions) -> Walk {
+        Walk {
+            options,
+            root: path.as_path().to_path_buf(),
+            filter: None,
+            queue: VecDeque::new(),
+            pending: Some(PendingDirectory {
+                path: path.as_path().to_path_buf(),
+                depth: 0,
+                skip: Arc::new(AtomicBool::new(false)),
+                ancestors: Arc::new(vec![]),
+            }),
+        }
+    }
+
+    /// 只返回通过过滤器的条目(路径相对于根目录), 被排除的目录不会被读取
+    ///
+    /// # 参数
+    ///
+    /// * `filter` - 路径过滤器
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::file;
+    /// use x_io::filter::PathFilter;
+    /// use x_io::walk::{Walk, WalkOptions};
+    /// use std::path::PathBuf;
+    ///
+    /// fn main() {
+    ///     let root = "./target/__test/walk_test/with_filter";
+    ///     file::write_text_file("./target/__test/walk_test/with_filter/src/lib.rs", "").unwrap();
+    ///     file::write_text_file("./target/__test/walk_test/with_filter/src/notes.txt", "").unwrap();
+    ///     file::write_text_file("./target/__test/walk_test/with_filter/target/build.rs", "").unwrap();
+    ///
+    ///     let filter = PathFilter::new().exclude("target").unwrap().extensions(["rs"]);
+    ///     let entries: Vec<PathBuf> = Walk::new(root, WalkOptions::default())
+    ///         .with_filter(filter)
+    ///         .map(|entry| entry.unwrap().into_path())
+    ///         .collect();
+    ///     assert_eq!(entries.len(), 2);
+    ///     assert!(entries[0].ends_with("src"));
+    ///     assert!(entries[1].ends_with("src/lib.rs"));
+    /// }
+    /// ```
+    pub fn with_filter(mut self, filter: PathFilter) -> Walk {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// 返回条目是否通过过滤器
+    fn is_included(&self, entry: &DirEntryInfo) -> bool {
+        match self.filter {
+            Some(ref filter) => match entry.path.strip_prefix(&self.root) {
+                Ok(relative) => filter.matches_entry(relative, entry.is_dir(), &entry.path),
+                Err(_) => true,
+            },
+            None => true,
+        }
+    }
+
+    /// 读取目录并将子条目加入队列
+    fn expand(&mut self, directory: PendingDirectory) -> FsIOResult<()> {
+        let path = directory.path.as_path();
+
+        let ancestors = if self.options.symlinks == SymlinkPolicy::Follow {
+            let id = file_id(path)?;
+            if directory.ancestors.contains(&id) {
+                return Err(FsIOError::SymlinkLoop(format!(
+                    "Symbolic link cycle detected at: {:?}",
+                    path
+                )));
+            }
+            let mut ancestors = directory.ancestors.as_ref().clone();
+            ancestors.push(id);
+            Arc::new(ancestors)
+        } else {
+            directory.ancestors.clone()
+        };
+
+        let entries = match read_dir(path) {
+            Ok(entries) => entries,
+            Err(error) => return Err(FsIOError::io(Operation::ReadDirectory, &path, error)),
+        };
+
+        let mut children = vec![];
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(error) => return Err(FsIOError::io(Operation::ReadDirectory, &path, error)),
+            };
+            let entry_path = entry.path();
+            let mut file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(error) => return Err(FsIOError::io(Operation::Metadata, &entry_path, error)),
+            };
+
+            let symlink = file_type.is_symlink();
+            if symlink {
+                match self.options.symlinks {
+                    SymlinkPolicy::Skip => continue,
+                    SymlinkPolicy::Follow => {
+                        if let Ok(target_metadata) = metadata(&entry_path) {
+                            file_type = target_metadata.file_type();
+                        }
+                    }
+                    SymlinkPolicy::Preserve => {}
+                }
+            }
+
+            children.push(DirEntryInfo {
+                path: entry_path,
+                depth: directory.depth + 1,
+                file_type,
+                symlink,
+                skip: Arc::new(AtomicBool::new(false)),
+            });
+        }
+        if self.options.sorted {
+            children.sort_by(|left, right| left.path.file_name().cmp(&right.path.file_name()));
+        }
+
+        match self.options.strategy {
+            Strategy::DepthFirst => {
+                for child in children.into_iter().rev() {
+                    self.queue.push_front((child, ancestors.clone()));
+                }
+            }
+            Strategy::BreadthFirst => {
+                for child in children {
+                    self.queue.push_back((child, ancestors.clone()));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Iterator for Walk {
+    type Item = FsIOResult<DirEntryInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(directory) = self.pending.take() {
+            let within_depth = self
+                .options
+                .max_depth
+                .map_or(true, |max| directory.depth < max);
+            if within_depth && !directory.skip.load(Ordering::Relaxed) {
+                if let Err(error) = self.expand(directory) {
+                    return Some(Err(error));
+                }
+            }
+        }
+
+        loop {
+            let (entry, ancestors) = self.queue.pop_front()?;
+            if !self.is_included(&entry) {
+                continue;
+            }
+
+            if entry.is_dir() {
+                self.pending = Some(PendingDirectory {
+                    path: entry.path.clone(),
+                    depth: entry.depth,
+                    skip: entry.skip.clone(),
+                    ancestors,
+                });
+            }
+
+            return Some(Ok(entry));
+        }
+    }
+}
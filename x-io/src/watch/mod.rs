@@ -0,0 +1,337 @@
+//! # watch
+//!
+//! File system watching (requires the `watch` feature).
+//!
+//! Uses the native backend of the platform (inotify/FSEvents/ReadDirectoryChangesW) and falls
+//! back to polling when the native backend is not available. `PollWatcher` only uses polling,
+//! which also works on network mounts.
+//!
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::PollWatcher as NotifyPollWatcher;
+use notify::Watcher as NotifyWatcher;
+use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode};
+
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::result::FsIOResult;
+
+pub use poll::{PollOptions, PollWatcher};
+
+mod poll;
+
+/// 文件系统事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// 文件或目录已创建
+    Created(PathBuf),
+    /// 文件或目录已修改
+    Modified(PathBuf),
+    /// 文件或目录已删除
+    Deleted(PathBuf),
+    /// 文件或目录已重命名(原路径, 新路径)
+    Renamed(PathBuf, PathBuf),
+}
+
+impl WatchEvent {
+    /// 返回事件的(新)路径
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Created(path) => path,
+            Self::Modified(path) => path,
+            Self::Deleted(path) => path,
+            Self::Renamed(_, path) => path,
+        }
+    }
+}
+
+/// 监听选项
+#[derive(Debug, Clone, Copy)]
+pub struct WatchOptions {
+    /// 是否监听子目录
+    pub recursive: bool,
+    /// 合并事件的时间窗口
+    pub debounce: Duration,
+    /// 回退到轮询时的轮询间隔
+    pub poll_interval: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        WatchOptions {
+            recursive: true,
+            debounce: Duration::from_millis(100),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+/// 文件系统监听器, 释放后停止监听
+pub struct Watcher {
+    _watcher: Box<dyn NotifyWatcher + Send>,
+    polling: bool,
+    receiver: Receiver<WatchEvent>,
+}
+
+impl Watcher {
+    /// 监听文件或目录
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件或目录路径
+    /// * `options` - 监听选项
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::{directory, file};
+    /// use x_io::watch::{WatchEvent, WatchOptions, Watcher};
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     let directory_path = "./target/__test/watch_test/watcher";
+    ///     directory::delete(directory_path).unwrap();
+    ///     directory::create(directory_path).unwrap();
+    ///
+    ///     let watcher = Watcher::new(directory_path, WatchOptions::default()).unwrap();
+    ///     file::write_text_file("./target/__test/watch_test/watcher/file.txt", "some content").unwrap();
+    ///
+    ///     let event = watcher.recv_timeout(Duration::from_secs(5)).unwrap();
+    ///     assert!(event.path().ends_with("file.txt"));
+    ///     assert!(matches!(event, WatchEvent::Created(_) | WatchEvent::Modified(_)));
+    /// }
+    /// ```
+    pub fn new<T: AsPath + ?Sized>(path: &T, options: WatchOptions) -> FsIOResult<Watcher> {
+        let watch_path = path.as_path();
+        let mode = if options.recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+
+        let (raw_sender, raw_receiver) = channel();
+        let (watcher, polling): (Box<dyn NotifyWatcher + Send>, bool) =
+            match start_native(watch_path, mode, raw_sender.clone()) {
+                Ok(watcher) => (Box::new(watcher), false),
+                Err(_) => {
+                    let config = Config::default().with_poll_interval(options.poll_interval);
+                    let mut watcher = match NotifyPollWatcher::new(raw_sender, config) {
+                        Ok(watcher) => watcher,
+                        Err(error) => {
+                            return Err(to_fs_io_error(watch_path, error))
+                        }
+                    };
+                    if let Err(error) = watcher.watch(watch_path, mode) {
+                        return Err(to_fs_io_error(watch_path, error));
+                    }
+                    (Box::new(watcher), true)
+                }
+            };
+
+        let (sender, receiver) = channel();
+        let debounce = options.debounce;
+        thread::spawn(move || debounce_events(raw_receiver, sender, debounce));
+
+        Ok(Watcher {
+            _watcher: watcher,
+            polling,
+            receiver,
+        })
+    }
+
+    /// 返回是否使用轮询方式监听
+    pub fn is_polling(&self) -> bool {
+        self.polling
+    }
+
+    /// 阻塞直到下一个事件
+    pub fn recv(&self) -> Option<WatchEvent> {
+        self.receiver.recv().ok()
+    }
+
+    /// 阻塞直到下一个事件或超时
+    ///
+    /// # 参数
+    ///
+    /// * `timeout` - 超时时间
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<WatchEvent> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// 返回已到达的事件, 不阻塞
+    pub fn try_recv(&self) -> Option<WatchEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// 返回事件迭代器(阻塞)
+    pub fn iter(&self) -> impl Iterator<Item = WatchEvent> + '_ {
+        self.receiver.iter()
+    }
+}
+
+fn start_native(
+    path: &Path,
+    mode: RecursiveMode,
+    sender: Sender<notify::Result<Event>>,
+) -> notify::Result<RecommendedWatcher> {
+    let mut watcher = RecommendedWatcher::new(sender, Config::default())?;
+    watcher.watch(path, mode)?;
+    Ok(watcher)
+}
+
+fn to_fs_io_error(path: &Path, error: notify::Error) -> FsIOError {
+    match error.kind {
+        notify::ErrorKind::Io(io_error) => FsIOError::io(Operation::Watch, path, io_error),
+        kind => FsIOError::io(Operation::Watch, path, io::Error::other(format!("{:?}", kind))),
+    }
+}
+
+fn convert_event(event: Event) -> Vec<WatchEvent> {
+    let mut paths = event.paths;
+    match event.kind {
+        EventKind::Create(_) => paths.into_iter().map(WatchEvent::Created).collect(),
+        EventKind::Remove(_) => paths.into_iter().map(WatchEvent::Deleted).collect(),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+            let to = paths.pop().unwrap();
+            let from = paths.pop().unwrap();
+            vec![WatchEvent::Renamed(from, to)]
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+            paths.into_iter().map(WatchEvent::Deleted).collect()
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+            paths.into_iter().map(WatchEvent::Created).collect()
+        }
+        EventKind::Modify(ModifyKind::Name(_)) => paths
+            .into_iter()
+            .map(|path| {
+                if path.exists() {
+                    WatchEvent::Created(path)
+                } else {
+                    WatchEvent::Deleted(path)
+                }
+            })
+            .collect(),
+        EventKind::Modify(_) | EventKind::Any => {
+            paths.into_iter().map(WatchEvent::Modified).collect()
+        }
+        EventKind::Access(_) | EventKind::Other => vec![],
+    }
+}
+
+/// 合并同一路径上的事件, 保持首次出现的顺序
+#[derive(Default)]
+pub(crate) struct EventQueue {
+    order: Vec<PathBuf>,
+    pending: HashMap<PathBuf, WatchEvent>,
+}
+
+impl EventQueue {
+    pub(crate) fn push(&mut self, event: WatchEvent) {
+        if let WatchEvent::Renamed(ref from, ref to) = event {
+            self.remove(from);
+            self.remove(to);
+            self.insert(event.clone());
+            return;
+        }
+
+        let path = event.path().to_path_buf();
+        let merged = match (self.pending.remove(&path), event) {
+            (None, event) => Some(event),
+            (Some(WatchEvent::Created(_)), WatchEvent::Modified(path)) => {
+                Some(WatchEvent::Created(path))
+            }
+            (Some(WatchEvent::Created(_)), WatchEvent::Deleted(_)) => None,
+            (Some(WatchEvent::Deleted(_)), WatchEvent::Created(path)) => {
+                Some(WatchEvent::Modified(path))
+            }
+            (Some(WatchEvent::Renamed(from, to)), WatchEvent::Modified(_)) => {
+                Some(WatchEvent::Renamed(from, to))
+            }
+            (Some(_), event) => Some(event),
+        };
+
+        match merged {
+            Some(event) => self.insert(event),
+            None => self.remove(&path),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub(crate) fn drain(&mut self) -> Vec<WatchEvent> {
+        let mut events = Vec::with_capacity(self.order.len());
+        for path in self.order.drain(..) {
+            if let Some(event) = self.pending.remove(&path) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    fn insert(&mut self, event: WatchEvent) {
+        let path = event.path().to_path_buf();
+        if !self.pending.contains_key(&path) {
+            self.order.push(path.clone());
+        }
+        self.pending.insert(path, event);
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if self.pending.remove(path).is_some() {
+            self.order.retain(|value| value != path);
+        }
+    }
+}
+
+fn debounce_events(
+    raw_receiver: Receiver<notify::Result<Event>>,
+    sender: Sender<WatchEvent>,
+    debounce: Duration,
+) {
+    let mut queue = EventQueue::default();
+    let mut deadline: Option<Instant> = None;
+
+    loop {
+        let received = match deadline {
+            Some(time) => raw_receiver.recv_timeout(time.saturating_duration_since(Instant::now())),
+            None => raw_receiver
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match received {
+            Ok(Ok(event)) => {
+                for watch_event in convert_event(event) {
+                    queue.push(watch_event);
+                }
+                if deadline.is_none() && !queue.is_empty() {
+                    deadline = Some(Instant::now() + debounce);
+                }
+            }
+            Ok(Err(_)) => {}
+            Err(RecvTimeoutError::Timeout) => {
+                deadline = None;
+                for event in queue.drain() {
+                    if sender.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                for event in queue.drain() {
+                    let _ = sender.send(event);
+                }
+                return;
+            }
+        }
+    }
+}
@@ -0,0 +1,281 @@
+//! # poll
+//!
+//! Snapshot based change detection for file systems without native notifications (NFS, SMB, ...).
+//!
+use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use x_hash::Sha256;
+
+use crate::error::{FsIOError, Operation};
+use crate::path::as_path::AsPath;
+use crate::result::FsIOResult;
+use crate::watch::WatchEvent;
+
+/// 轮询选项
+#[derive(Debug, Clone, Copy)]
+pub struct PollOptions {
+    /// 是否监听子目录
+    pub recursive: bool,
+    /// 轮询间隔
+    pub interval: Duration,
+    /// 是否比较文件内容摘要(修改时间和大小不变时也能检测到修改), 只有比较内容摘要时才报告文件重命名
+    pub compare_contents: bool,
+}
+
+impl Default for PollOptions {
+    fn default() -> Self {
+        PollOptions {
+            recursive: true,
+            interval: Duration::from_secs(2),
+            compare_contents: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EntryState {
+    is_dir: bool,
+    size: u64,
+    modified: Option<SystemTime>,
+    digest: Option<[u8; 32]>,
+}
+
+type Snapshot = HashMap<PathBuf, EntryState>;
+
+/// 轮询监听器, 定期比较文件的修改时间/大小/摘要, 释放后停止监听
+pub struct PollWatcher {
+    _stop: Sender<()>,
+    receiver: Receiver<WatchEvent>,
+}
+
+impl PollWatcher {
+    /// 轮询监听文件或目录
+    ///
+    /// # 参数
+    ///
+    /// * `path` - 文件或目录路径
+    /// * `options` - 轮询选项
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use x_io::{directory, file};
+    /// use x_io::watch::{PollOptions, PollWatcher, WatchEvent};
+    /// use std::time::Duration;
+    ///
+    /// fn main() {
+    ///     let directory_path = "./target/__test/watch_test/poll_watcher";
+    ///     directory::delete(directory_path).unwrap();
+    ///     directory::create(directory_path).unwrap();
+    ///
+    ///     let options = PollOptions {
+    ///         interval: Duration::from_millis(50),
+    ///         ..PollOptions::default()
+    ///     };
+    ///     let watcher = PollWatcher::new(directory_path, options).unwrap();
+    ///     file::write_text_file("./target/__test/watch_test/poll_watcher/file.txt", "some content").unwrap();
+    ///
+    ///     let event = watcher.recv_timeout(Duration::from_secs(5)).unwrap();
+    ///     assert!(event.path().ends_with("file.txt"));
+    ///     assert!(matches!(event, WatchEvent::Created(_)));
+    ///
+    ///     // symbolic links to directories are not followed
+    ///     #[cfg(unix)]
+    ///     {
+    ///         std::os::unix::fs::symlink(".", "./target/__test/watch_test/poll_watcher/self1").unwrap();
+    ///         std::os::unix::fs::symlink(".", "./target/__test/watch_test/poll_watcher/self2").unwrap();
+    ///         let watcher = PollWatcher::new(directory_path, options).unwrap();
+    ///         drop(watcher);
+    ///     }
+    /// }
+    /// ```
+    pub fn new<T: AsPath + ?Sized>(path: &T, options: PollOptions) -> FsIOResult<PollWatcher> {
+        let root = path.as_path().to_path_buf();
+
+        if let Err(error) = fs::metadata(&root) {
+            return Err(FsIOError::io(Operation::Watch, &root, error));
+        }
+
+        let mut previous = take_snapshot(&root, &options);
+        let (stop_sender, stop_receiver) = channel::<()>();
+        let (sender, receiver) = channel();
+
+        thread::spawn(move || loop {
+            match stop_receiver.recv_timeout(options.interval) {
+                Err(RecvTimeoutError::Timeout) => {
+                    let current = take_snapshot(&root, &options);
+                    for event in diff_snapshots(&previous, &current) {
+                        if sender.send(event).is_err() {
+                            return;
+                        }
+                    }
+                    previous = current;
+                }
+                _ => return,
+            }
+        });
+
+        Ok(PollWatcher {
+            _stop: stop_sender,
+            receiver,
+        })
+    }
+
+    /// 阻塞直到下一个事件
+    pub fn recv(&self) -> Option<WatchEvent> {
+        self.receiver.recv().ok()
+    }
+
+    /// 阻塞直到下一个事件或超时
+    ///
+    /// # 参数
+    ///
+    /// * `timeout` - 超时时间
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<WatchEvent> {
+        self.receiver.recv_timeout(timeout).ok()
+    }
+
+    /// 返回已到达的事件, 不阻塞
+    pub fn try_recv(&self) -> Option<WatchEvent> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// 返回事件迭代器(阻塞)
+    pub fn iter(&self) -> impl Iterator<Item = WatchEvent> + '_ {
+        self.receiver.iter()
+    }
+}
+
+fn take_snapshot(root: &Path, options: &PollOptions) -> Snapshot {
+    let mut snapshot = HashMap::new();
+
+    match fs::metadata(root) {
+        Ok(metadata) if metadata.is_dir() => {
+            collect_directory(root, options, &mut snapshot);
+        }
+        Ok(metadata) => {
+            snapshot.insert(root.to_path_buf(), entry_state(root, &metadata, options));
+        }
+        Err(_) => {}
+    }
+
+    snapshot
+}
+
+fn collect_directory(directory: &Path, options: &PollOptions, snapshot: &mut Snapshot) {
+    let entries = match fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let metadata = match fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(_) => continue,
+        };
+        snapshot.insert(path.clone(), entry_state(&path, &metadata, options));
+
+        // symbolic links to directories are not followed, they may form cycles
+        let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+        if is_dir && options.recursive {
+            collect_directory(&path, options, snapshot);
+        }
+    }
+}
+
+fn entry_state(path: &Path, metadata: &fs::Metadata, options: &PollOptions) -> EntryState {
+    let is_dir = metadata.is_dir();
+    EntryState {
+        is_dir,
+        size: if is_dir { 0 } else { metadata.len() },
+        modified: metadata.modified().ok(),
+        digest: if options.compare_contents && !is_dir {
+            file_digest(path)
+        } else {
+            None
+        },
+    }
+}
+
+fn file_digest(path: &Path) -> Option<[u8; 32]> {
+    let mut file = File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(size) => hasher.update(&buffer[..size]),
+            Err(_) => return None,
+        }
+    }
+
+    Some(hasher.result())
+}
+
+fn diff_snapshots(previous: &Snapshot, current: &Snapshot) -> Vec<WatchEvent> {
+    let mut created: Vec<&PathBuf> = current
+        .keys()
+        .filter(|path| !previous.contains_key(*path))
+        .collect();
+    let mut deleted: Vec<&PathBuf> = previous
+        .keys()
+        .filter(|path| !current.contains_key(*path))
+        .collect();
+    let mut modified: Vec<&PathBuf> = current
+        .iter()
+        .filter(|(path, state)| match previous.get(*path) {
+            Some(previous_state) => !state.is_dir && previous_state != *state,
+            None => false,
+        })
+        .map(|(path, _)| path)
+        .collect();
+    created.sort();
+    deleted.sort();
+    modified.sort();
+
+    let mut events = Vec::new();
+
+    // a deleted file and a created file with the same state (including the content digest)
+    // are reported as rename, without digests unrelated files could share size and time
+    deleted.retain(|from| {
+        let from_state = &previous[*from];
+        if from_state.is_dir || from_state.digest.is_none() {
+            return true;
+        }
+        match created.iter().position(|to| current[*to] == *from_state) {
+            Some(index) => {
+                let to = created.remove(index);
+                events.push(WatchEvent::Renamed((*from).clone(), to.clone()));
+                false
+            }
+            None => true,
+        }
+    });
+
+    events.extend(
+        created
+            .into_iter()
+            .map(|path| WatchEvent::Created(path.clone())),
+    );
+    events.extend(
+        modified
+            .into_iter()
+            .map(|path| WatchEvent::Modified(path.clone())),
+    );
+    events.extend(
+        deleted
+            .into_iter()
+            .map(|path| WatchEvent::Deleted(path.clone())),
+    );
+
+    events
+}